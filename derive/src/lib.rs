@@ -92,12 +92,51 @@ impl<'a> ToPrimitive for Literal<'a> {
 }
 
 
+// Build TryFrom impls delegating to from_u64() and from_number().
+//
+// Only a single impl is built if int_type is u64 since both impls would
+// otherwise be for the same type.
+fn mk_try_from_impl(name: &syn::Ident, int_type: &syn::Ident)
+    -> quote::Tokens
+{
+    let from_u64 = quote! {
+        impl ::std::convert::TryFrom<u64> for #name {
+            type Error = CodeValueError;
+
+            fn try_from(num: u64) -> Result<#name, CodeValueError> {
+                <#name as CodeConvert<#name>>::from_u64(num)
+            }
+        }
+    };
+
+    if int_type == "u64" {
+        return from_u64;
+    }
+
+    quote! {
+        #from_u64
+
+        impl ::std::convert::TryFrom<#int_type> for #name {
+            type Error = CodeValueError;
+
+            fn try_from(num: #int_type) -> Result<#name, CodeValueError> {
+                <#name as CodeConvert<#name>>::from_number(num)
+            }
+        }
+    }
+}
+
+
 fn mk_code_impl(
-    name: &syn::Ident, cases: &Vec<quote::Tokens>, int_type: syn::Ident,
-    maxnum: u64
+    name: &syn::Ident, cases: &Vec<quote::Tokens>,
+    variants: &Vec<quote::Tokens>, names: &Vec<quote::Tokens>,
+    int_type: syn::Ident, minnum: u64, maxnum: u64
 ) -> quote::Tokens
 {
+    let try_from = mk_try_from_impl(name, &int_type);
     quote! {
+        #try_from
+
         impl CodeConvert<#name> for #name {
             type int_type = #int_type;
 
@@ -120,10 +159,24 @@ fn mk_code_impl(
                 self.clone() as u64
             }
 
+            fn min_number() -> u64 {
+                #minnum
+            }
+
             fn max_number() -> u64 {
                 #maxnum
             }
 
+            fn all() -> Vec<#name> {
+                vec![#(#variants),*]
+            }
+
+            fn name(&self) -> &'static str {
+                match *self {
+                    #(#names),*
+                }
+            }
+
             fn cast_number(n: u64) -> Option<#int_type> {
                 let maxval = #int_type::max_value() as u64;
                 if n <= maxval {
@@ -143,7 +196,10 @@ fn impl_code_convert(ast: &syn::DeriveInput) -> quote::Tokens
 
         let name = &ast.ident;
         let mut num = 0;
+        let mut minnum: Option<u64> = None;
         let mut maxnum: u64 = 0;
+        let mut variants: Vec<(u64, quote::Tokens)> = Vec::new();
+        let mut names: Vec<quote::Tokens> = Vec::new();
         let cases: Vec<_> = body.iter()
             .map(|case| {
                 // Panic if the variant is a struct or tuple
@@ -174,7 +230,14 @@ fn impl_code_convert(ast: &syn::DeriveInput) -> quote::Tokens
                     if num > maxnum {
                         maxnum = num;
                     }
+                    minnum = match minnum {
+                        Some(m) if m <= num => Some(m),
+                        _ => Some(num),
+                    };
                     let ret = quote! { #num => Ok(#ident) };
+                    let variant_name = variant.as_ref();
+                    names.push(quote! { #ident => #variant_name });
+                    variants.push((num, ident));
                     num += 1;
                     ret
                 } else {
@@ -198,7 +261,15 @@ fn impl_code_convert(ast: &syn::DeriveInput) -> quote::Tokens
         } else {
             syn::Ident::from("u8")
         };
-        mk_code_impl(name, &cases, int_type, maxnum)
+        let minnum = minnum.unwrap_or(0);
+
+        // Order the variants by value
+        variants.sort_by_key(|&(n, _)| n);
+        let variants = variants.into_iter().map(|(_, v)| v).collect();
+
+        mk_code_impl(
+            name, &cases, &variants, &names, int_type, minnum, maxnum
+        )
     } else {
         panic!("#[derive(CodeConvert)] is only defined for enums not structs");
     }
@@ -0,0 +1,85 @@
+// examples/fuzz_decode.rs
+// Copyright (C) 2017 authors and contributors (see AUTHORS file)
+//
+// This file is released under the MIT License.
+
+//! Fuzz target for the message decode path
+//!
+//! Reads raw bytes from stdin and feeds them through the full decode and
+//! validate pipeline: every message that can be decoded is decoded as a
+//! generic message, a v1 request, a v1 response, and a generic message read
+//! from a ChainedBuf of 2 chunks, and decoded requests are checked against
+//! the v1 spec. The pipeline must only ever return `Ok` or `Err`; a panic or
+//! abort is a bug.
+//!
+//! This is meant to be driven by an external stdin-based fuzzer, eg
+//!
+//! ```text
+//! cargo build --example fuzz_decode
+//! afl-fuzz -i corpus -o findings target/debug/examples/fuzz_decode
+//! ```
+//!
+//! A bounded random-bytes version of the same pipeline, along with its seed
+//! corpus, runs as part of `cargo test`.
+
+// ===========================================================================
+// Externs
+// ===========================================================================
+
+
+extern crate bytes;
+extern crate siminau_rpc;
+
+
+// ===========================================================================
+// Imports
+// ===========================================================================
+
+
+// Stdlib imports
+
+use std::io::{self, Read};
+
+// Third-party imports
+
+use bytes::{Bytes, BytesMut};
+
+// Local imports
+
+use siminau_rpc::core::{ChainedBuf, FromBytes, Message, ToMessageError};
+use siminau_rpc::message::v1::{Request, Response};
+
+
+// ===========================================================================
+// Main
+// ===========================================================================
+
+
+fn main()
+{
+    let mut data = Vec::new();
+    io::stdin()
+        .read_to_end(&mut data)
+        .expect("unable to read stdin");
+
+    let mut msgbuf = BytesMut::from(&data[..]);
+    while let Ok(Some(_)) = Message::from_bytes(&mut msgbuf) {}
+
+    let mut reqbuf = BytesMut::from(&data[..]);
+    while let Ok(Some(req)) = Request::from_bytes(&mut reqbuf) {
+        let _ = req.validate_spec();
+    }
+
+    let mut respbuf = BytesMut::from(&data[..]);
+    while let Ok(Some(_)) = Response::from_bytes(&mut respbuf) {}
+
+    let (head, tail) = data.split_at(data.len() / 2);
+    let mut chained = ChainedBuf::new(vec![Bytes::from(head),
+                                           Bytes::from(tail)]);
+    while let Ok(Some(_)) = chained.decode::<Message, ToMessageError>() {}
+}
+
+
+// ===========================================================================
+//
+// ===========================================================================
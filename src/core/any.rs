@@ -0,0 +1,195 @@
+// src/core/any.rs
+// Copyright (C) 2017 authors and contributors (see AUTHORS file)
+//
+// This file is released under the MIT License.
+
+//! This module defines a typed view of a message of any type.
+//!
+//! A server loop reads generic [`Message`] objects without knowing ahead of
+//! time whether the next one is a request, a response, or a notification.
+//! [`AnyMessage`] converts a [`Message`] into whichever of the typed messages
+//! matches its type, and [`into_typed`] does the same for every message of
+//! an iterator.
+//!
+//! # Example
+//!
+//! ```rust
+//! extern crate rmpv;
+//! extern crate siminau_rpc;
+//!
+//! use rmpv::Value;
+//! use siminau_rpc::core::{CodeConvert, FromMessage, Message, MessageType};
+//! use siminau_rpc::core::any::{into_typed, AnyMessage};
+//!
+//! # fn main() {
+//! let msgtype = Value::from(MessageType::Notification.to_number());
+//! let code = Value::from(MessageType::Request.to_number());
+//! let val = Value::Array(vec![msgtype, code, Value::Array(vec![])]);
+//! let msg = Message::from_msg(val).unwrap();
+//!
+//! let mut typed = into_typed::<MessageType, _>(vec![msg]);
+//! match typed.next() {
+//!     Some(Ok(AnyMessage::Notification(_))) => {}
+//!     _ => panic!("expected a notification"),
+//! }
+//! assert!(typed.next().is_none());
+//! # }
+//! ```
+//!
+//! [`Message`]: ../struct.Message.html
+//! [`AnyMessage`]: enum.AnyMessage.html
+//! [`into_typed`]: fn.into_typed.html
+
+// ===========================================================================
+// Imports
+// ===========================================================================
+
+
+// Stdlib imports
+
+use std::marker::PhantomData;
+
+// Third-party imports
+
+// Local imports
+
+use core::{CodeConvert, FromMessage, Message, MessageType, RpcMessage};
+use core::notify::{NotificationMessage, ToNoticeError};
+use core::request::{RequestMessage, ToRequestError};
+use core::response::{ResponseMessage, ToResponseError};
+
+
+// ===========================================================================
+// AnyMessage errors
+// ===========================================================================
+
+
+#[derive(Debug, Fail)]
+pub enum ToAnyMessageError
+{
+    #[fail(display = "Invalid request message")]
+    Request(#[cause] ToRequestError),
+
+    #[fail(display = "Invalid response message")]
+    Response(#[cause] ToResponseError),
+
+    #[fail(display = "Invalid notification message")]
+    Notification(#[cause] ToNoticeError),
+}
+
+
+// ===========================================================================
+// AnyMessage
+// ===========================================================================
+
+
+/// A request, response, or notification using the code type `C`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnyMessage<C>
+{
+    Request(RequestMessage<C>),
+    Response(ResponseMessage<C>),
+    Notification(NotificationMessage<C>),
+}
+
+
+impl<C> AnyMessage<C>
+where
+    C: CodeConvert<C>,
+{
+    /// Create an AnyMessage from a Message.
+    ///
+    /// The message is converted into the typed message matching its type.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the message cannot be converted into the
+    /// typed message, eg if its code is not a valid `C` value.
+    pub fn from_msg(msg: Message) -> Result<AnyMessage<C>, ToAnyMessageError>
+    {
+        match msg.message_type() {
+            MessageType::Request => RequestMessage::from_msg(msg)
+                .map(AnyMessage::Request)
+                .map_err(ToAnyMessageError::Request),
+            MessageType::Response => ResponseMessage::from_msg(msg)
+                .map(AnyMessage::Response)
+                .map_err(ToAnyMessageError::Response),
+            MessageType::Notification => NotificationMessage::from_msg(msg)
+                .map(AnyMessage::Notification)
+                .map_err(ToAnyMessageError::Notification),
+        }
+    }
+
+    /// Return the message's type.
+    pub fn message_type(&self) -> MessageType
+    {
+        match *self {
+            AnyMessage::Request(_) => MessageType::Request,
+            AnyMessage::Response(_) => MessageType::Response,
+            AnyMessage::Notification(_) => MessageType::Notification,
+        }
+    }
+}
+
+
+// ===========================================================================
+// Typed message iterator
+// ===========================================================================
+
+
+/// Iterator converting each [`Message`] of another iterator into an
+/// [`AnyMessage`].
+///
+/// This is created by [`into_typed`].
+///
+/// [`Message`]: ../struct.Message.html
+/// [`AnyMessage`]: enum.AnyMessage.html
+/// [`into_typed`]: fn.into_typed.html
+#[derive(Debug)]
+pub struct IntoTyped<I, C>
+{
+    iter: I,
+    codetype: PhantomData<C>,
+}
+
+
+impl<I, C> Iterator for IntoTyped<I, C>
+where
+    I: Iterator<Item = Message>,
+    C: CodeConvert<C>,
+{
+    type Item = Result<AnyMessage<C>, ToAnyMessageError>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        self.iter.next().map(AnyMessage::from_msg)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>)
+    {
+        self.iter.size_hint()
+    }
+}
+
+
+/// Convert every message of `raw` into an [`AnyMessage`], in order.
+///
+/// A message that cannot be converted is yielded as an error; the messages
+/// following it are still converted.
+///
+/// [`AnyMessage`]: enum.AnyMessage.html
+pub fn into_typed<C, I>(raw: I) -> IntoTyped<I::IntoIter, C>
+where
+    I: IntoIterator<Item = Message>,
+    C: CodeConvert<C>,
+{
+    IntoTyped {
+        iter: raw.into_iter(),
+        codetype: PhantomData,
+    }
+}
+
+
+// ===========================================================================
+//
+// ===========================================================================
@@ -20,11 +20,14 @@
 //!
 //! * MessageType
 //! * Message
+//! * ChainedBuf
+//! * DecodeOutcome
 //!
 //! And the traits provided are:
 //!
 //! * AsBytes
 //! * CodeConvert
+//! * DynMessage
 //! * FromBytes
 //! * FromMessage
 //! * RpcMessage
@@ -47,10 +50,24 @@
 //!
 //! The core base type of all RPC messages.
 //!
+//! ## ChainedBuf
+//!
+//! A buffer made of several chunks of bytes that can be decoded as if the
+//! chunks were contiguous.
+//!
+//! ## DecodeOutcome
+//!
+//! This is an enum describing the result of a single attempt to decode a
+//! message from a buffer of bytes.
+//!
 //! ## AsBytes
 //!
 //! This trait provides an interface to convert a message into msgpack bytes.
 //!
+//! ## DynMessage
+//!
+//! This trait provides an object safe interface common to all messages.
+//!
 //! ## FromBytes
 //!
 //! This trait provides an interface to convert a msgpack bytes into a message.
@@ -139,6 +156,8 @@
 pub mod request;
 pub mod response;
 pub mod notify;
+pub mod raw;
+pub mod any;
 
 
 // ===========================================================================
@@ -149,18 +168,26 @@ pub mod notify;
 // Stdlib imports
 
 use std::clone::Clone;
-use std::io;
+use std::collections::VecDeque;
+use std::error;
+use std::fmt;
+use std::io::{self, Read};
 
 // Third-party imports
 
-use bytes::{Bytes, BytesMut};
+use bytes::{BufMut, Bytes, BytesMut};
 use failure::Fail;
+use rmp::Marker;
 use rmps::{decode, Deserializer, Serializer};
+use rmps::encode::{StructArrayWriter, VariantWriter};
 use rmpv::Value;
+use rmpv::encode::write_value;
 use serde::{Deserialize, Serialize};
 
 // Local imports
 
+use util::value_to_json;
+
 
 // ===========================================================================
 // Helpers
@@ -186,6 +213,33 @@ pub fn value_type(arg: &Value) -> String
 }
 
 
+// Return true if both values are equal, treating integers as equal if they
+// hold the same number regardless of the width used to encode them
+fn value_wire_eq(a: &Value, b: &Value) -> bool
+{
+    match (a, b) {
+        (Value::Integer(x), Value::Integer(y)) => {
+            match (x.as_u64(), y.as_u64()) {
+                (Some(x), Some(y)) => x == y,
+                (None, None) => x.as_i64() == y.as_i64(),
+                _ => false,
+            }
+        }
+        (Value::Array(x), Value::Array(y)) => {
+            x.len() == y.len() &&
+                x.iter().zip(y.iter()).all(|(x, y)| value_wire_eq(x, y))
+        }
+        (Value::Map(x), Value::Map(y)) => {
+            x.len() == y.len() &&
+                x.iter().zip(y.iter()).all(|(x, y)| {
+                    value_wire_eq(&x.0, &y.0) && value_wire_eq(&x.1, &y.1)
+                })
+        }
+        _ => a == b,
+    }
+}
+
+
 #[derive(Debug, Fail)]
 pub enum CheckIntError
 {
@@ -235,14 +289,29 @@ pub fn check_int(
 // ===========================================================================
 
 
-#[derive(Fail, Debug)]
-#[fail(display = "Unknown code value: {}", code)]
+// Implements std::error::Error directly, rather than deriving Fail, so that
+// it can be used as the error of the TryFrom impls built by the CodeConvert
+// derive. Fail is still implemented through failure's impl for all std
+// errors.
+#[derive(Debug)]
 pub struct CodeValueError
 {
     pub code: u64,
 }
 
 
+impl fmt::Display for CodeValueError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "Unknown code value: {}", self.code)
+    }
+}
+
+
+impl error::Error for CodeValueError {}
+
+
 /// Allows converting between a number and a type.
 ///
 /// The type implementing [`CodeConvert`] will usually be an enum that defines
@@ -254,10 +323,55 @@ pub struct CodeValueError
 ///
 /// 1. The enum is a C-style enum
 /// 2. The enum's values are unsigned integers
-/// 3. The enum's values are continuous without any gaps ie 0, 1, 2 are valid
-///    values but 0, 2, 4 is not
+///
+/// The values do not need to be continuous, eg 4, 6, 8 is fine. Only the
+/// values of the enum's variants are converted into the enum; any other
+/// number, including one between [`min_number`] and [`max_number`], is a
+/// [`CodeValueError`].
+///
+/// # Custom codes
+///
+/// The generic message types accept any code type implementing
+/// [`CodeConvert`], so users may define their own codes. The derive macro
+/// from the `siminau-rpc-derive` crate refers to [`CodeConvert`] and
+/// [`CodeValueError`] by name, so both must be in scope where it is used.
+/// Besides [`CodeConvert`], the derive implements `TryFrom<u64>` and
+/// `TryFrom` for the enum's int type, both using [`CodeValueError`] as
+/// their error.
+///
+/// ```rust
+/// extern crate bytes;
+/// extern crate rmpv;
+/// extern crate siminau_rpc;
+/// #[macro_use]
+/// extern crate siminau_rpc_derive;
+///
+/// use bytes::BytesMut;
+/// use rmpv::Value;
+/// use siminau_rpc::core::{AsBytes, CodeConvert, CodeValueError, FromBytes};
+/// use siminau_rpc::core::request::{RequestMessage, RpcRequest};
+///
+/// #[derive(Debug, PartialEq, Clone, CodeConvert)]
+/// enum UserCode {
+///     Fetch = 100,
+///     Store = 101,
+/// }
+///
+/// # fn main() {
+/// let req = RequestMessage::new(1, UserCode::Store, vec![Value::from(42)]);
+/// let mut buf = BytesMut::from(&req.as_bytes()[..]);
+///
+/// let decoded = RequestMessage::<UserCode>::from_bytes(&mut buf)
+///     .unwrap()
+///     .unwrap();
+/// assert_eq!(decoded.message_method(), UserCode::Store);
+/// # }
+/// ```
 ///
 /// [`CodeConvert`]: trait.CodeConvert.html
+/// [`CodeValueError`]: struct.CodeValueError.html
+/// [`min_number`]: trait.CodeConvert.html#tymethod.min_number
+/// [`max_number`]: trait.CodeConvert.html#tymethod.max_number
 pub trait CodeConvert<T>: Clone + PartialEq
 {
     type int_type;
@@ -274,9 +388,34 @@ pub trait CodeConvert<T>: Clone + PartialEq
     /// Convert type T to a u64.
     fn to_u64(&self) -> u64;
 
-    /// Return the maximum number value
+    /// Return the smallest value of any variant
+    fn min_number() -> u64;
+
+    /// Return the largest value of any variant
     fn max_number() -> u64;
 
+    /// Return every variant, ordered by value.
+    ///
+    /// The default implementation tries to convert every number from
+    /// `min_number()` to `max_number()`. The derive instead returns the
+    /// enum's variants directly.
+    fn all() -> Vec<T>
+    {
+        (Self::min_number()..=Self::max_number())
+            .filter_map(|n| Self::from_u64(n).ok())
+            .collect()
+    }
+
+    /// Return the name of the variant, eg "Walk" for `RequestCode::Walk`.
+    ///
+    /// The default implementation returns an empty string, so hand-written
+    /// impls do not need to provide it. The derive returns the variant's
+    /// identifier.
+    fn name(&self) -> &'static str
+    {
+        ""
+    }
+
     /// Cast a u64 number into acceptable int type
     fn cast_number(n: u64) -> Option<Self::int_type>;
 }
@@ -288,7 +427,7 @@ pub trait CodeConvert<T>: Clone + PartialEq
 
 
 /// Enum defining different types of messages
-#[derive(Debug, PartialEq, Clone, CodeConvert)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, CodeConvert)]
 pub enum MessageType
 {
     /// A message initiating a request.
@@ -302,11 +441,47 @@ pub enum MessageType
 }
 
 
+// ===========================================================================
+// Role
+// ===========================================================================
+
+
+/// The side of a connection that a peer plays.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Role
+{
+    /// The side that sends requests.
+    Client,
+
+    /// The side that answers requests.
+    Server,
+}
+
+
+#[derive(Debug, Fail)]
+#[fail(display = "{:?} message cannot be received by a {:?}", msgtype, role)]
+pub struct RoleError
+{
+    pub role: Role,
+    pub msgtype: MessageType,
+}
+
+
 // ===========================================================================
 // Message traits
 // ===========================================================================
 
 
+#[derive(Debug, Fail)]
+#[fail(display = "Message of {} bytes does not fit into buffer of {} bytes",
+       needed, capacity)]
+pub struct SerializeError
+{
+    pub needed: usize,
+    pub capacity: usize,
+}
+
+
 pub trait FromMessage<M>
 {
     type Err: Fail + From<ToMessageError>;
@@ -336,6 +511,18 @@ impl<M> FromMessage<Value> for M
 }
 
 
+// Return the length of the binary value serialized last when serializing the
+// given value, or 0 if the last serialized value is not binary.
+fn trailing_binary_len(val: &Value) -> usize
+{
+    match *val {
+        Value::Binary(ref b) => b.len(),
+        Value::Array(ref a) => a.last().map_or(0, trailing_binary_len),
+        _ => 0,
+    }
+}
+
+
 /// Define methods common to all RPC messages
 pub trait RpcMessage
 {
@@ -357,6 +544,134 @@ pub trait RpcMessage
         MessageType::from_number(msgtype)
             .expect(&format!("bad msgtype? {}", msgtype))
     }
+
+    /// Check that a peer playing the given role may receive this message.
+    ///
+    /// Servers never initiate requests, so a client only receives responses
+    /// and notifications, and a server only receives requests and
+    /// notifications.
+    fn validate_role(&self, role: Role) -> Result<(), RoleError>
+    {
+        let msgtype = self.message_type();
+        let allowed = match (role, &msgtype) {
+            (_, &MessageType::Notification) => true,
+            (Role::Client, &MessageType::Response) => true,
+            (Role::Server, &MessageType::Request) => true,
+            _ => false,
+        };
+        if allowed {
+            Ok(())
+        } else {
+            Err(RoleError {
+                role: role,
+                msgtype: msgtype,
+            })
+        }
+    }
+
+    /// Serialize the message into msgpack bytes using the given variant
+    /// writer.
+    ///
+    /// The `configure` closure is called with the serializer before the
+    /// message is serialized, allowing the caller to set options on it.
+    fn as_bytes_with<V, F>(&self, variant: V, configure: F) -> Bytes
+    where
+        V: VariantWriter,
+        F: FnOnce(&mut Serializer<&mut Vec<u8>, V>),
+    {
+        let mut tmpbuf = Vec::new();
+        {
+            let mut se = Serializer::with(&mut tmpbuf, variant);
+            configure(&mut se);
+            self.as_value().serialize(&mut se).unwrap();
+        }
+        let mut buf = Bytes::with_capacity(tmpbuf.len());
+        buf.extend_from_slice(&tmpbuf[..]);
+        buf
+    }
+
+    /// Serialize the message into msgpack bytes, splitting off a trailing
+    /// binary payload.
+    ///
+    /// If the last value of the message (eg the data of a Write request) is
+    /// binary, the first item returned holds all bytes up to and including
+    /// the binary's header while the second item holds the raw payload.
+    /// Otherwise, the second item is empty. Concatenating both items gives
+    /// the same bytes as `as_bytes()`.
+    ///
+    /// Both items share the same underlying buffer, so a transport can write
+    /// them with a vectored write without copying.
+    fn as_bytes_split(&self) -> (Bytes, Bytes)
+    {
+        let mut header = self.as_bytes_with(StructArrayWriter, |_| {});
+        let payload_len = trailing_binary_len(self.as_value());
+        let payload = header.split_off(header.len() - payload_len);
+        (header, payload)
+    }
+
+    /// Serialize the message into a length prefixed frame.
+    ///
+    /// The frame starts with the length of the message's msgpack bytes as a
+    /// big-endian u32, followed by the bytes themselves. This is the frame
+    /// read by [`from_length_prefixed`].
+    ///
+    /// [`from_length_prefixed`]: fn.from_length_prefixed.html
+    fn as_length_prefixed(&self) -> Bytes
+    {
+        let body = self.as_bytes_with(StructArrayWriter, |_| {});
+        let mut buf = BytesMut::with_capacity(4 + body.len());
+        buf.put_u32_be(body.len() as u32);
+        buf.put_slice(&body[..]);
+        buf.freeze()
+    }
+
+    /// Serialize the message into a caller owned buffer.
+    ///
+    /// The message is written to the start of `buf` using the same encoding
+    /// as `as_bytes()`, and the number of bytes written is returned. This
+    /// allows a server to serialize straight into a pre-registered buffer.
+    /// If `buf` is too small to hold the whole message, an error is returned
+    /// and the contents of `buf` are unspecified.
+    fn serialize_into(&self, buf: &mut [u8]) -> Result<usize, SerializeError>
+    {
+        let capacity = buf.len();
+        let mut wr = &mut buf[..];
+        let ret = {
+            let mut se = Serializer::with(&mut wr, StructArrayWriter);
+            self.as_value().serialize(&mut se)
+        };
+        match ret {
+            Ok(()) => Ok(capacity - wr.len()),
+            Err(_) => Err(SerializeError {
+                needed: self.as_bytes_with(StructArrayWriter, |_| {}).len(),
+                capacity: capacity,
+            }),
+        }
+    }
+
+    /// Serialize the message into a canonical msgpack representation.
+    ///
+    /// Every integer is written using the smallest encoding that can hold
+    /// it, and strings, binaries, and floats are always written with the
+    /// same markers, so messages that are `wire_eq` always produce identical
+    /// bytes. This makes the result suitable as input for a signature or an
+    /// HMAC.
+    fn canonical_bytes(&self) -> Bytes
+    {
+        let mut tmpbuf = Vec::new();
+        write_value(&mut tmpbuf, self.as_value()).unwrap();
+        Bytes::from(tmpbuf)
+    }
+
+    /// Convert the message into a JSON string for logging.
+    ///
+    /// See [`value_to_json`] for how msgpack values are mapped to JSON.
+    ///
+    /// [`value_to_json`]: ../util/fn.value_to_json.html
+    fn to_json_string(&self) -> String
+    {
+        value_to_json(self.as_value())
+    }
 }
 
 
@@ -368,6 +683,52 @@ pub trait RpcMessageType
 }
 
 
+/// Object safe view of an RPC message.
+///
+/// [`RpcMessage`] has an associated error type, so messages with different
+/// error types cannot be stored together as `RpcMessage` trait objects.
+/// `DynMessage` has no associated types and is implemented for every
+/// [`RpcMessage`], allowing heterogeneous messages to be stored in a
+/// `Vec<Box<dyn DynMessage>>`.
+///
+/// The methods are prefixed with `dyn_` so they do not clash with the
+/// [`RpcMessage`] methods when both traits are in scope.
+///
+/// [`RpcMessage`]: trait.RpcMessage.html
+pub trait DynMessage
+{
+    /// View the message as a vector of [`rmpv::Value`] objects.
+    fn dyn_as_vec(&self) -> &Vec<Value>;
+
+    /// Return a reference to the internally owned [`rmpv::Value`] object.
+    fn dyn_as_value(&self) -> &Value;
+
+    /// Return the message's type.
+    fn dyn_message_type(&self) -> MessageType;
+}
+
+
+impl<T> DynMessage for T
+where
+    T: RpcMessage,
+{
+    fn dyn_as_vec(&self) -> &Vec<Value>
+    {
+        RpcMessage::as_vec(self)
+    }
+
+    fn dyn_as_value(&self) -> &Value
+    {
+        RpcMessage::as_value(self)
+    }
+
+    fn dyn_message_type(&self) -> MessageType
+    {
+        RpcMessage::message_type(self)
+    }
+}
+
+
 pub trait AsBytes<V>
     where V: AsRef<[u8]>,
 {
@@ -380,16 +741,207 @@ impl<T> AsBytes<Bytes> for T
 {
     // TODO: should there be an unwrap here?
     fn as_bytes(&self) -> Bytes {
-        let mut tmpbuf = Vec::new();
-        let msg = self.as_value();
-        msg.serialize(&mut Serializer::new(&mut tmpbuf)).unwrap();
-        let mut buf = Bytes::with_capacity(tmpbuf.len());
-        buf.extend_from_slice(&tmpbuf[..]);
-        buf
+        self.as_bytes_with(StructArrayWriter, |_| {})
     }
 }
 
 
+// Maximum nesting of msgpack arrays and maps accepted when decoding.
+//
+// No rpc message comes close to this; the limit only exists so that
+// malicious input cannot exhaust the stack while being decoded.
+const MAX_DECODE_DEPTH: usize = 64;
+
+
+// Read a big-endian length of size bytes from buf, advancing pos past it
+fn read_len(buf: &[u8], pos: &mut usize, size: usize) -> Option<u64>
+{
+    let data = buf.get(*pos..*pos + size)?;
+    *pos += size;
+    Some(data.iter().fold(0, |acc, &b| (acc << 8) | u64::from(b)))
+}
+
+
+// Return the number of bytes holding the length that follows marker
+fn len_size(marker: Marker) -> usize
+{
+    match marker {
+        Marker::Str8 | Marker::Bin8 | Marker::Ext8 => 1,
+        Marker::Str16 | Marker::Bin16 | Marker::Ext16 => 2,
+        Marker::Array16 | Marker::Map16 => 2,
+        Marker::Str32 | Marker::Bin32 | Marker::Ext32 => 4,
+        Marker::Array32 | Marker::Map32 => 4,
+        _ => 0,
+    }
+}
+
+
+// Return the number of payload bytes following a marker and its length, and
+// the number of child values contained by the marker's value
+fn value_extent(marker: Marker, len: u64) -> (u64, u64)
+{
+    match marker {
+        Marker::U8 | Marker::I8 => (1, 0),
+        Marker::U16 | Marker::I16 => (2, 0),
+        Marker::U32 | Marker::I32 | Marker::F32 => (4, 0),
+        Marker::U64 | Marker::I64 | Marker::F64 => (8, 0),
+        Marker::FixStr(len) => (u64::from(len), 0),
+        Marker::Str8 | Marker::Str16 | Marker::Str32 => (len, 0),
+        Marker::Bin8 | Marker::Bin16 | Marker::Bin32 => (len, 0),
+        Marker::FixExt1 => (2, 0),
+        Marker::FixExt2 => (3, 0),
+        Marker::FixExt4 => (5, 0),
+        Marker::FixExt8 => (9, 0),
+        Marker::FixExt16 => (17, 0),
+        Marker::Ext8 | Marker::Ext16 | Marker::Ext32 => (len + 1, 0),
+        Marker::FixArray(len) => (0, u64::from(len)),
+        Marker::Array16 | Marker::Array32 => (0, len),
+        Marker::FixMap(len) => (0, u64::from(len) * 2),
+        Marker::Map16 | Marker::Map32 => (0, len * 2),
+        _ => (0, 0),
+    }
+}
+
+
+// Source of the bytes of a msgpack value walked by walk_value()
+trait MarkerSource
+{
+    type Error;
+
+    // Take a marker byte
+    fn marker(&mut self) -> Result<u8, Self::Error>;
+
+    // Take a big-endian length of size bytes
+    fn length(&mut self, size: usize) -> Result<u64, Self::Error>;
+
+    // Take size bytes of payload without looking at them
+    fn skip(&mut self, size: u64) -> Result<(), Self::Error>;
+}
+
+
+// How a walk over the markers of a value ended
+enum WalkEnd
+{
+    // The end of the value was reached
+    Complete,
+
+    // An array or map header nesting deeper than the max depth was taken
+    TooDeep,
+}
+
+
+// Walk the markers of exactly one msgpack value taken from src.
+//
+// rmp-serde accepts a max depth setting but never enforces it, and
+// deserializing a value recurses once per level of nesting. This walks the
+// markers iteratively instead, stopping right after the end of the value or
+// right after the first array or map header nesting deeper than maxdepth.
+// Nothing past that point is taken from src.
+fn walk_value<S>(src: &mut S, maxdepth: usize) -> Result<WalkEnd, S::Error>
+where
+    S: MarkerSource,
+{
+    // Number of values left to take at each open level of nesting
+    let mut remaining: Vec<u64> = vec![1];
+
+    loop {
+        while remaining.last() == Some(&0) {
+            remaining.pop();
+        }
+        match remaining.last_mut() {
+            Some(count) => *count -= 1,
+            None => return Ok(WalkEnd::Complete),
+        }
+
+        let marker = Marker::from_u8(src.marker()?);
+        let len = src.length(len_size(marker))?;
+        let (skip, children) = value_extent(marker, len);
+        src.skip(skip)?;
+
+        if children > 0 {
+            if remaining.len() > maxdepth {
+                return Ok(WalkEnd::TooDeep);
+            }
+            remaining.push(children);
+        }
+    }
+}
+
+
+// Marker source over a slice, failing if the slice ends before the walk does
+struct SliceSource<'a>
+{
+    buf: &'a [u8],
+    pos: usize,
+}
+
+
+impl<'a> MarkerSource for SliceSource<'a>
+{
+    type Error = ();
+
+    fn marker(&mut self) -> Result<u8, ()>
+    {
+        let ret = *self.buf.get(self.pos).ok_or(())?;
+        self.pos += 1;
+        Ok(ret)
+    }
+
+    fn length(&mut self, size: usize) -> Result<u64, ()>
+    {
+        read_len(self.buf, &mut self.pos, size).ok_or(())
+    }
+
+    fn skip(&mut self, size: u64) -> Result<(), ()>
+    {
+        if size > (self.buf.len() - self.pos) as u64 {
+            return Err(());
+        }
+        self.pos += size as usize;
+        Ok(())
+    }
+}
+
+
+// Walk the first value in buf, returning how the walk ended along with the
+// number of bytes walked, or None if buf ends before the walk does
+fn walk_slice(buf: &[u8], maxdepth: usize) -> Option<(WalkEnd, usize)>
+{
+    let mut src = SliceSource { buf: buf, pos: 0 };
+    let end = walk_value(&mut src, maxdepth).ok()?;
+    Some((end, src.pos))
+}
+
+
+// Return the offset just past the first array or map header in buf that
+// nests deeper than maxdepth, if any.
+//
+// Scanning stops at the end of the first value or at the end of buf;
+// truncated or malformed data is left for the deserializer to report.
+fn exceeds_depth(buf: &[u8], maxdepth: usize) -> Option<usize>
+{
+    match walk_slice(buf, maxdepth) {
+        Some((WalkEnd::TooDeep, pos)) => Some(pos),
+        _ => None,
+    }
+}
+
+
+// Return the number of bytes taken by the first value in buf, or None if buf
+// ends before the value does.
+//
+// Nothing is decoded, so this is cheap to use for skipping over a value.
+fn value_len(buf: &[u8]) -> Option<usize>
+{
+    match walk_slice(buf, usize::max_value()) {
+        Some((WalkEnd::Complete, len)) => Some(len),
+        _ => None,
+    }
+}
+
+
+// Errors raised while decoding msgpack data carry the offset of the decoder
+// in the buffer at the point the error was detected.
 #[derive(Debug, Fail)]
 pub enum FromBytesError<E>
     where E: Fail
@@ -400,49 +952,136 @@ pub enum FromBytesError<E>
     #[fail(display = "MsgPack error: invalid data")]
     InvalidDataRead(#[cause] io::Error),
 
-    #[fail(display = "MsgPack error: type mismatch")]
-    TypeMismatch(#[cause] decode::Error),
+    #[fail(display = "MsgPack error at byte {}: type mismatch", offset)]
+    TypeMismatch
+    {
+        #[cause] err: decode::Error,
+        offset: usize,
+    },
 
-    #[fail(display = "MsgPack error: value out of range")]
-    OutOfRange,
+    #[fail(display = "MsgPack error at byte {}: value out of range", offset)]
+    OutOfRange
+    {
+        offset: usize
+    },
 
-    #[fail(display = "MsgPack error: length mismatch {}", _0)]
-    LengthMismatch(u32),
+    #[fail(display = "MsgPack error at byte {}: length mismatch {}",
+           offset, len)]
+    LengthMismatch
+    {
+        len: u32, offset: usize
+    },
 
-    #[fail(display = "MsgPack error: {}", _0)]
-    Uncategorized(String),
+    #[fail(display = "MsgPack error at byte {}: {}", offset, msg)]
+    Uncategorized
+    {
+        msg: String, offset: usize
+    },
+
+    #[fail(display = "MsgPack syntax error at byte {}: {}", offset, msg)]
+    Syntax
+    {
+        msg: String, offset: usize
+    },
+
+    #[fail(display = "MsgPack utf-8 error at byte {}: invalid byte starts at \
+                      {}",
+           offset, valid_up_to)]
+    Utf8Error
+    {
+        valid_up_to: usize, offset: usize
+    },
 
-    #[fail(display = "MsgPack syntax error: {}", _0)]
-    Syntax(String),
+    #[fail(display = "MsgPack error at byte {}: depth limit exceeded", offset)]
+    DepthLimitExceeded
+    {
+        offset: usize
+    },
 
-    #[fail(display = "MsgPack utf-8 error: invalid byte starts at {}", _0)]
-    Utf8Error(usize),
+    #[fail(display = "Frame length {} exceeds maximum of {}", len, max)]
+    FrameTooLarge
+    {
+        len: u32, max: u32
+    },
 
-    #[fail(display = "MsgPack error: depth limit exceeded")]
-    DepthLimitExceeded,
+    #[fail(display = "Frame length {} does not match length of message",
+           _0)]
+    FrameMismatch(u32),
 
     #[fail(display = "Invalid message")]
     InvalidMessage(#[cause] E),
 }
 
 
-impl<E> From<decode::Error> for FromBytesError<E>
+impl<E> FromBytesError<E>
     where E: Fail
 {
-    fn from(e: decode::Error) -> FromBytesError<E> {
+    /// Convert a decode error raised when the decoder was at offset.
+    pub fn from_decode(e: decode::Error, offset: usize) -> FromBytesError<E>
+    {
         match e {
-            decode::Error::InvalidMarkerRead(err) => FromBytesError::InvalidMarkerRead(err),
-            decode::Error::InvalidDataRead(err) => FromBytesError::InvalidDataRead(err),
-            err @ decode::Error::TypeMismatch(_) => FromBytesError::TypeMismatch(err),
-            decode::Error::OutOfRange => FromBytesError::OutOfRange,
-            decode::Error::LengthMismatch(v) => FromBytesError::LengthMismatch(v),
-            decode::Error::Uncategorized(v) => FromBytesError::Uncategorized(v),
-            decode::Error::Syntax(v) => FromBytesError::Syntax(v),
-            decode::Error::Utf8Error(utferr) => {
-                let invalid_byte = utferr.valid_up_to();
-                FromBytesError::Utf8Error(invalid_byte)
+            decode::Error::InvalidMarkerRead(err) => {
+                FromBytesError::InvalidMarkerRead(err)
+            }
+            decode::Error::InvalidDataRead(err) => {
+                FromBytesError::InvalidDataRead(err)
+            }
+            err @ decode::Error::TypeMismatch(_) => {
+                FromBytesError::TypeMismatch {
+                    err: err,
+                    offset: offset,
+                }
+            }
+            decode::Error::OutOfRange => {
+                FromBytesError::OutOfRange { offset: offset }
+            }
+            decode::Error::LengthMismatch(len) => {
+                FromBytesError::LengthMismatch {
+                    len: len,
+                    offset: offset,
+                }
+            }
+            decode::Error::Uncategorized(msg) => {
+                FromBytesError::Uncategorized {
+                    msg: msg,
+                    offset: offset,
+                }
+            }
+            decode::Error::Syntax(msg) => FromBytesError::Syntax {
+                msg: msg,
+                offset: offset,
             },
-            decode::Error::DepthLimitExceeded => FromBytesError::DepthLimitExceeded,
+            decode::Error::Utf8Error(utferr) => FromBytesError::Utf8Error {
+                valid_up_to: utferr.valid_up_to(),
+                offset: offset,
+            },
+            decode::Error::DepthLimitExceeded => {
+                FromBytesError::DepthLimitExceeded { offset: offset }
+            }
+        }
+    }
+
+    /// Return the offset of the decoder when the error was detected.
+    ///
+    /// None is returned for io errors, which are only raised when the data
+    /// runs out, for frame errors, which are about the frame rather than the
+    /// data inside it, and for invalid messages, which are only detected
+    /// once the entire message has been decoded.
+    pub fn offset(&self) -> Option<usize>
+    {
+        match *self {
+            FromBytesError::TypeMismatch { offset, .. } |
+            FromBytesError::OutOfRange { offset } |
+            FromBytesError::LengthMismatch { offset, .. } |
+            FromBytesError::Uncategorized { offset, .. } |
+            FromBytesError::Syntax { offset, .. } |
+            FromBytesError::Utf8Error { offset, .. } |
+            FromBytesError::DepthLimitExceeded { offset } => Some(offset),
+            FromBytesError::InvalidMarkerRead(_) |
+            FromBytesError::InvalidDataRead(_) |
+            FromBytesError::FrameTooLarge { .. } |
+            FromBytesError::FrameMismatch(_) |
+            FromBytesError::InvalidMessage(_) => None,
         }
     }
 }
@@ -457,8 +1096,8 @@ impl<E> From<FromBytesError<E>> for io::Error
             FromBytesError::InvalidMarkerRead(ioerr) => return ioerr,
             FromBytesError::InvalidDataRead(ioerr) => return ioerr,
 
-            err @ FromBytesError::Uncategorized(_) |
-            err @ FromBytesError::DepthLimitExceeded => {
+            err @ FromBytesError::Uncategorized { .. } |
+            err @ FromBytesError::DepthLimitExceeded { .. } => {
                 (io::ErrorKind::Other, err.to_string())
             }
 
@@ -485,21 +1124,13 @@ impl<T, E> FromBytes<T, E> for T
           E: Fail + From<ToMessageError>,
 {
     fn from_bytes(buf: &mut BytesMut) -> Result<Option<T>, FromBytesError<E>> {
-        let result;
-        let curpos: usize;
-
         // If no data has been given yet, ask for data to be sent
         if buf.is_empty() {
             return Ok(None);
         }
 
         // Attempt to deserialize the current buffer
-        {
-            let cursor = io::Cursor::new(&buf[..]);
-            let mut de = Deserializer::new(cursor);
-            result = Value::deserialize(&mut de);
-            curpos = de.position() as usize;
-        }
+        let (result, curpos) = decode_slice(&buf[..]);
 
         // Discard read bytes
         buf.split_to(curpos);
@@ -518,13 +1149,240 @@ impl<T, E> FromBytes<T, E> for T
                     }
                 }
 
-                Err(e.into())
+                Err(FromBytesError::from_decode(e, curpos))
             }
         }
     }
 }
 
 
+// Deserialize the first value in buf, returning the result along with the
+// number of bytes read.
+//
+// Deserializing straight from the slice means a str or bin length marker is
+// checked against the bytes actually available before anything is allocated
+// for it, and values nesting deeper than MAX_DECODE_DEPTH are rejected
+// before they are deserialized.
+fn decode_slice(buf: &[u8]) -> (Result<Value, decode::Error>, usize)
+{
+    match exceeds_depth(buf, MAX_DECODE_DEPTH) {
+        Some(pos) => (Err(decode::Error::DepthLimitExceeded), pos),
+        None => {
+            let mut de = Deserializer::from_slice(buf);
+            let result = Value::deserialize(&mut de);
+            (result, buf.len() - de.get_ref().len())
+        }
+    }
+}
+
+
+/// The result of a single attempt at decoding a message from a buffer
+///
+/// This makes explicit the three outcomes of [`FromBytes::from_bytes`]: a
+/// decoded message, not enough bytes to decode a message, or an error.
+///
+/// [`FromBytes::from_bytes`]: trait.FromBytes.html#tymethod.from_bytes
+#[derive(Debug)]
+pub enum DecodeOutcome<T, E>
+where
+    E: Fail,
+{
+    Complete(T),
+    NeedMore,
+    Error(FromBytesError<E>),
+}
+
+
+/// Attempt to decode a message from a buffer, returning a [`DecodeOutcome`]
+///
+/// [`DecodeOutcome`]: enum.DecodeOutcome.html
+pub fn decode_outcome<T, E>(buf: &mut BytesMut) -> DecodeOutcome<T, E>
+where
+    T: RpcMessage + FromBytes<T, E>,
+    E: Fail + From<ToMessageError>,
+{
+    match T::from_bytes(buf) {
+        Ok(Some(msg)) => DecodeOutcome::Complete(msg),
+        Ok(None) => DecodeOutcome::NeedMore,
+        Err(e) => DecodeOutcome::Error(e),
+    }
+}
+
+
+/// Attempt to decode a message from a length prefixed frame.
+///
+/// Each frame starts with the length of the message it holds as a big-endian
+/// u32, followed by exactly that many bytes of msgpack data. Nothing is
+/// consumed from `buf` and `Ok(None)` is returned until the whole frame is
+/// available, at which point the frame is removed from `buf` and its message
+/// is decoded and validated the same way as `FromBytes::from_bytes()`.
+///
+/// # Errors
+///
+/// If the frame's length is greater than `max_len`, an error is returned
+/// without consuming anything from `buf`. If the frame does not hold exactly
+/// one message, an error is returned after the frame is consumed.
+pub fn from_length_prefixed<T, E>(
+    buf: &mut BytesMut, max_len: u32
+) -> Result<Option<T>, FromBytesError<E>>
+where
+    T: RpcMessage + FromBytes<T, E>,
+    E: Fail + From<ToMessageError>,
+{
+    const PREFIX_LEN: usize = 4;
+
+    let len = match read_len(&buf[..], &mut 0, PREFIX_LEN) {
+        Some(len) => len as u32,
+        None => return Ok(None),
+    };
+    if len > max_len {
+        return Err(FromBytesError::FrameTooLarge {
+            len: len,
+            max: max_len,
+        });
+    }
+
+    // Wait for the rest of the frame
+    let frame_len = PREFIX_LEN + len as usize;
+    if buf.len() < frame_len {
+        return Ok(None);
+    }
+    let mut body = buf.split_to(frame_len);
+    body.advance(PREFIX_LEN);
+
+    match T::from_bytes(&mut body)? {
+        Some(msg) if body.is_empty() => Ok(Some(msg)),
+        _ => Err(FromBytesError::FrameMismatch(len)),
+    }
+}
+
+
+// Append exactly size bytes from rd to buf.
+//
+// The bytes are read in chunks, so a bogus size only grows buf as far as the
+// data that is actually available.
+fn read_into<R>(rd: &mut R, buf: &mut Vec<u8>, size: u64) -> io::Result<()>
+where
+    R: io::Read,
+{
+    let nread = rd.by_ref().take(size).read_to_end(buf)?;
+    if (nread as u64) < size {
+        let err = io::Error::new(io::ErrorKind::UnexpectedEof,
+                                 "unexpected end of msgpack data");
+        return Err(err);
+    }
+    Ok(())
+}
+
+
+// Errors raised while taking a value's bytes from a reader
+enum ReadSourceError
+{
+    Marker(io::Error),
+    Data(io::Error),
+}
+
+
+// Marker source over a reader, keeping every byte taken in buf
+struct ReadSource<'a, R: 'a>
+{
+    rd: &'a mut R,
+    buf: Vec<u8>,
+}
+
+
+impl<'a, R> MarkerSource for ReadSource<'a, R>
+where
+    R: io::Read,
+{
+    type Error = ReadSourceError;
+
+    fn marker(&mut self) -> Result<u8, ReadSourceError>
+    {
+        read_into(self.rd, &mut self.buf, 1)
+            .map_err(ReadSourceError::Marker)?;
+        Ok(self.buf[self.buf.len() - 1])
+    }
+
+    fn length(&mut self, size: usize) -> Result<u64, ReadSourceError>
+    {
+        let mut pos = self.buf.len();
+        read_into(self.rd, &mut self.buf, size as u64)
+            .map_err(ReadSourceError::Data)?;
+        Ok(read_len(&self.buf[..], &mut pos, size).unwrap_or(0))
+    }
+
+    fn skip(&mut self, size: u64) -> Result<(), ReadSourceError>
+    {
+        read_into(self.rd, &mut self.buf, size).map_err(ReadSourceError::Data)
+    }
+}
+
+
+// Read the bytes of exactly one msgpack value from rd.
+//
+// The markers are walked with walk_value() so that nothing past the end of
+// the value is read and values nesting deeper than maxdepth are rejected
+// before they are decoded.
+fn read_raw_value<R, E>(rd: &mut R, maxdepth: usize)
+    -> Result<Vec<u8>, FromBytesError<E>>
+where
+    R: io::Read,
+    E: Fail,
+{
+    let mut src = ReadSource {
+        rd: rd,
+        buf: Vec::new(),
+    };
+    match walk_value(&mut src, maxdepth) {
+        Ok(WalkEnd::Complete) => Ok(src.buf),
+        Ok(WalkEnd::TooDeep) => {
+            let offset = src.buf.len();
+            Err(FromBytesError::DepthLimitExceeded { offset: offset })
+        }
+        Err(ReadSourceError::Marker(e)) => {
+            Err(FromBytesError::InvalidMarkerRead(e))
+        }
+        Err(ReadSourceError::Data(e)) => {
+            Err(FromBytesError::InvalidDataRead(e))
+        }
+    }
+}
+
+
+/// Read a single message from a reader and convert it into `T`.
+///
+/// Exactly the bytes of one msgpack value are read, so any data following
+/// the message is left in the reader. This is meant for blocking clients;
+/// non-blocking code should use [`FromBytes::from_bytes`] instead.
+///
+/// # Errors
+///
+/// Io errors, including the reader running out of data in the middle of the
+/// message, are returned as `InvalidMarkerRead` or `InvalidDataRead` errors.
+/// If the bytes read cannot be decoded, or the decoded value is not a valid
+/// `T`, the same errors as [`FromBytes::from_bytes`] are returned.
+///
+/// [`FromBytes::from_bytes`]: trait.FromBytes.html#tymethod.from_bytes
+pub fn read_one<R, T>(rd: &mut R) -> Result<T, FromBytesError<T::Err>>
+where
+    R: io::Read,
+    T: FromMessage<Message>,
+{
+    let buf = read_raw_value(rd, MAX_DECODE_DEPTH)?;
+
+    let mut de = Deserializer::from_slice(&buf[..]);
+    let value = Value::deserialize(&mut de).map_err(|e| {
+        let offset = buf.len() - de.get_ref().len();
+        FromBytesError::from_decode(e, offset)
+    })?;
+
+    let msg = Message::from_msg(value)
+        .map_err(|e| FromBytesError::InvalidMessage(T::Err::from(e)))?;
+    T::from_msg(msg).map_err(|e| FromBytesError::InvalidMessage(e))
+}
+
+
 // ===========================================================================
 // Message
 // ===========================================================================
@@ -558,11 +1416,136 @@ pub struct Message
 }
 
 
+impl Message
+{
+    /// Create a minimal valid message of the given type.
+    ///
+    /// All ids and codes are 0. Requests and notifications have no args, and
+    /// responses have a nil result.
+    pub fn template(msgtype: MessageType) -> Message
+    {
+        let msgtypeval = Value::from(msgtype.to_number());
+        let zero = Value::from(0);
+        let noargs = Value::Array(vec![]);
+        let array = match msgtype {
+            MessageType::Request => {
+                vec![msgtypeval, zero.clone(), zero, noargs]
+            }
+            MessageType::Response => {
+                vec![msgtypeval, zero.clone(), zero, Value::Nil]
+            }
+            MessageType::Notification => vec![msgtypeval, zero, noargs],
+        };
+        Message::from_msg(Value::Array(array))
+            .expect("message template failed validation")
+    }
+
+    /// Return true if both messages encode the same values on the wire.
+    ///
+    /// Unlike `==`, integers are compared by the number they hold rather
+    /// than by how they are represented, so a number stored as a u8 is equal
+    /// to the same number stored as a u64 or an i64.
+    pub fn wire_eq(&self, other: &Message) -> bool
+    {
+        value_wire_eq(&self.msg, &other.msg)
+    }
+
+    /// Return true if the messages' types, ids, or codes differ.
+    ///
+    /// Only the items before the args or result are compared, so this is a
+    /// cheap pre-filter for `==` and [`wire_eq`]: if it returns true, the
+    /// messages are not equal by either, and if it returns false a full
+    /// comparison is still needed.
+    ///
+    /// [`wire_eq`]: #method.wire_eq
+    pub fn fast_ne(&self, other: &Message) -> bool
+    {
+        let (left, right) = (self.as_vec(), other.as_vec());
+        if left.len() != right.len() {
+            return true;
+        }
+
+        // The last item holds the args or result
+        let prefix = left.len() - 1;
+        left[..prefix]
+            .iter()
+            .zip(&right[..prefix])
+            .any(|(a, b)| !value_wire_eq(a, b))
+    }
+
+    /// Describe each position where the messages differ.
+    ///
+    /// Each entry names the position and both values, eg
+    /// `arg[2]: int(42) != int(43)`. The args of requests and notifications
+    /// are compared one arg at a time, while a response's result is compared
+    /// as a whole. If the message types differ, only the type is reported.
+    ///
+    /// The result is empty exactly when the messages are equal.
+    pub fn diff(&self, other: &Message) -> Vec<String>
+    {
+        let (left, right) = (self.as_vec(), other.as_vec());
+        let describe = |v: &Value| format!("{}({})", value_type(v), v);
+        let mut ret = Vec::new();
+        let mut push = |name: String, a: Option<&Value>, b: Option<&Value>| {
+            if a != b {
+                let a = a.map_or("missing".to_owned(), &describe);
+                let b = b.map_or("missing".to_owned(), &describe);
+                ret.push(format!("{}: {} != {}", name, a, b));
+            }
+        };
+
+        let msgtype = self.message_type();
+        if msgtype != other.message_type() {
+            push("type".to_owned(), left.first(), right.first());
+            return ret;
+        }
+
+        let names: &[&str] = match msgtype {
+            MessageType::Notification => &["type", "code"],
+            _ => &["type", "id", "code"],
+        };
+        for (i, name) in names.iter().enumerate() {
+            push(name.to_string(), left.get(i), right.get(i));
+        }
+
+        // A message that passed validation may still lack its args or result
+        let last = names.len();
+        let (a, b) = (left.get(last), right.get(last));
+        match (a.and_then(Value::as_array), b.and_then(Value::as_array)) {
+            (Some(a), Some(b)) if msgtype != MessageType::Response => {
+                for i in 0..a.len().max(b.len()) {
+                    push(format!("arg[{}]", i), a.get(i), b.get(i));
+                }
+            }
+            _ => {
+                let name = match msgtype {
+                    MessageType::Response => "result",
+                    _ => "args",
+                };
+                push(name.to_owned(), a, b);
+            }
+        }
+        ret
+    }
+
+    // Return a mutable reference to the message's array of values.
+    //
+    // The array's length must not be changed since it has already been
+    // validated.
+    fn as_vec_mut(&mut self) -> &mut Vec<Value>
+    {
+        if let Value::Array(ref mut array) = self.msg {
+            array
+        } else {
+            unreachable!()
+        }
+    }
+}
+
+
 impl FromMessage<Value> for Message {
     type Err = ToMessageError;
 
-    // TODO: improve call to check_int since it's possible the array's first
-    // element is not an integer
     /// Converts an [`rmpv::Value`].
     ///
     /// # Errors
@@ -586,7 +1569,7 @@ impl FromMessage<Value> for Message {
             check_int(
                 array[0].as_u64(),
                 MessageType::max_number() as u64,
-                array[0].as_u64().unwrap().to_string(),
+                "u8".to_string(),
             ).map_err(|e| ToMessageError::InvalidType(e))?;
         } else {
             return Err(ToMessageError::NotArray(value_type(&val)));
@@ -652,6 +1635,222 @@ impl From<Message> for Value
 }
 
 
+// ===========================================================================
+// ChainedBuf
+// ===========================================================================
+
+
+/// A sequence of [`Bytes`] chunks read as a single logical buffer.
+///
+/// Messages whose bytes are split across several chunks can be decoded
+/// without first concatenating the chunks into a contiguous buffer.
+///
+/// [`Bytes`]: https://docs.rs/bytes/0.4/bytes/struct.Bytes.html
+#[derive(Debug, Clone, Default)]
+pub struct ChainedBuf
+{
+    chunks: VecDeque<Bytes>,
+}
+
+
+impl ChainedBuf
+{
+    /// Create a buffer from a list of chunks.
+    pub fn new(chunks: Vec<Bytes>) -> Self
+    {
+        let mut ret = Self::default();
+        for chunk in chunks {
+            ret.push(chunk);
+        }
+        ret
+    }
+
+    /// Append a chunk to the end of the buffer.
+    pub fn push(&mut self, chunk: Bytes)
+    {
+        if !chunk.is_empty() {
+            self.chunks.push_back(chunk);
+        }
+    }
+
+    /// Return the total number of unread bytes.
+    pub fn len(&self) -> usize
+    {
+        self.chunks.iter().map(|c| c.len()).sum()
+    }
+
+    /// Return true if there are no unread bytes.
+    pub fn is_empty(&self) -> bool
+    {
+        self.chunks.is_empty()
+    }
+
+    /// Decode a single message from the front of the buffer.
+    ///
+    /// Returns `Ok(None)` if the buffer does not yet hold a complete
+    /// message. In that case no bytes are consumed, so decoding can be
+    /// retried once more chunks have been pushed.
+    pub fn decode<T, E>(&mut self) -> Result<Option<T>, FromBytesError<E>>
+    where
+        T: RpcMessage<Err = E> + FromMessage<Value, Err = E>,
+        E: Fail + From<ToMessageError>,
+    {
+        if self.is_empty() {
+            return Ok(None);
+        }
+
+        // Walk the message's markers across the chunks in place, so that
+        // nothing is deserialized until the whole message is available and
+        // known not to nest too deeply. Nothing is consumed until a whole
+        // message has been decoded.
+        let (end, msglen) = {
+            let mut src = ChunkSource {
+                chunks: &self.chunks,
+                index: 0,
+                offset: 0,
+                pos: 0,
+            };
+            match walk_value(&mut src, MAX_DECODE_DEPTH) {
+                Ok(end) => (end, src.pos),
+                Err(()) => return Ok(None),
+            }
+        };
+        if let WalkEnd::TooDeep = end {
+            let e = decode::Error::DepthLimitExceeded;
+            return Err(FromBytesError::from_decode(e, msglen));
+        }
+
+        // Deserialize through a clone of the chunk list, which only clones
+        // the handles to the chunks and not their bytes
+        let mut rd = self.clone();
+        let result = Value::deserialize(&mut Deserializer::new(&mut rd));
+        let curpos = self.len() - rd.len();
+
+        match result {
+            Ok(v) => {
+                self.advance(curpos);
+                let msg = T::from_msg(v)
+                    .map_err(|e| FromBytesError::InvalidMessage(e))?;
+                Ok(Some(msg))
+            }
+            Err(e) => Err(FromBytesError::from_decode(e, curpos)),
+        }
+    }
+
+    // Discard the first n unread bytes
+    fn advance(&mut self, mut n: usize)
+    {
+        while n > 0 {
+            let chunk = match self.chunks.front_mut() {
+                Some(c) => c,
+                None => break,
+            };
+
+            let size = n.min(chunk.len());
+            chunk.split_to(size);
+            n -= size;
+
+            if chunk.is_empty() {
+                self.chunks.pop_front();
+            }
+        }
+    }
+}
+
+
+impl io::Read for ChainedBuf
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize>
+    {
+        let mut numread = 0;
+        while numread < buf.len() {
+            let chunk = match self.chunks.front_mut() {
+                Some(c) => c,
+                None => break,
+            };
+
+            let size = (buf.len() - numread).min(chunk.len());
+            buf[numread..numread + size]
+                .copy_from_slice(&chunk.split_to(size)[..]);
+            numread += size;
+
+            if chunk.is_empty() {
+                self.chunks.pop_front();
+            }
+        }
+        Ok(numread)
+    }
+}
+
+
+// Marker source over the chunks of a ChainedBuf, failing if the chunks end
+// before the walk does
+struct ChunkSource<'a>
+{
+    chunks: &'a VecDeque<Bytes>,
+    index: usize,
+    offset: usize,
+    pos: usize,
+}
+
+
+impl<'a> ChunkSource<'a>
+{
+    // Take the next byte, moving on to the next chunk at the end of the
+    // current one
+    fn byte(&mut self) -> Option<u8>
+    {
+        let chunk = self.chunks.get(self.index)?;
+        let ret = chunk[self.offset];
+        self.offset += 1;
+        self.pos += 1;
+        if self.offset == chunk.len() {
+            self.index += 1;
+            self.offset = 0;
+        }
+        Some(ret)
+    }
+}
+
+
+impl<'a> MarkerSource for ChunkSource<'a>
+{
+    type Error = ();
+
+    fn marker(&mut self) -> Result<u8, ()>
+    {
+        self.byte().ok_or(())
+    }
+
+    fn length(&mut self, size: usize) -> Result<u64, ()>
+    {
+        let mut ret = 0;
+        for _ in 0..size {
+            ret = (ret << 8) | u64::from(self.byte().ok_or(())?);
+        }
+        Ok(ret)
+    }
+
+    fn skip(&mut self, mut size: u64) -> Result<(), ()>
+    {
+        while size > 0 {
+            let chunk = self.chunks.get(self.index).ok_or(())?;
+            let avail = chunk.len() - self.offset;
+            if size < avail as u64 {
+                self.offset += size as usize;
+                self.pos += size as usize;
+                break;
+            }
+            size -= avail as u64;
+            self.pos += avail;
+            self.index += 1;
+            self.offset = 0;
+        }
+        Ok(())
+    }
+}
+
+
 // ===========================================================================
 // Tests
 // ===========================================================================
@@ -668,11 +1867,13 @@ mod tests
 
     // Third-party imports
 
+    use bytes::Bytes;
     use rmpv::Value;
 
     // Local imports
 
-    use super::{Message, RpcMessage};
+    use super::{AsBytes, ChainedBuf, FromMessage, Message, MessageType,
+                RpcMessage, ToMessageError};
 
     // --------------------
     // Decode tests
@@ -723,6 +1924,61 @@ mod tests
         let msg = Message { msg: v };
         assert_eq!(msg.as_value(), &expected);
     }
+
+    // --------------------
+    // ChainedBuf
+    // --------------------
+
+    // Build a notification with a single string argument long enough that
+    // the bytes chunks holding it are never stored inline
+    fn long_notice(text: &str) -> Message
+    {
+        let msgtype = Value::from(MessageType::Notification as u8);
+        let args = Value::Array(vec![Value::from(text.repeat(64))]);
+        let val = Value::Array(vec![msgtype, Value::from(0), args]);
+        Message::from_msg(val).unwrap()
+    }
+
+    // ChainedBuf::decode
+    #[test]
+    fn chainedbuf_decode_without_copying()
+    {
+        // A first message split across 2 chunks, with the second chunk also
+        // holding all of a second message
+        let first = long_notice("a");
+        let second = long_notice("b");
+        let first_bytes = first.as_bytes();
+        let split = first_bytes.len() / 2;
+
+        let mut tail = first_bytes[split..].to_vec();
+        tail.extend_from_slice(&second.as_bytes()[..]);
+        let head = Bytes::from(first_bytes[..split].to_vec());
+        let tail = Bytes::from(tail);
+        let head_ptr = head.as_ptr();
+        let tail_ptr = tail.as_ptr();
+
+        // Retrying with only part of the first message leaves the chunk in
+        // its original allocation
+        let mut buf = ChainedBuf::new(vec![head]);
+        let result = buf.decode::<Message, ToMessageError>().unwrap();
+        assert_eq!(result, None);
+        assert_eq!(buf.chunks[0].as_ptr(), head_ptr);
+
+        buf.push(tail);
+        assert_eq!(buf.chunks[1].as_ptr(), tail_ptr);
+
+        // Decoding the first message leaves the unread part of the second
+        // chunk in its original allocation
+        let result = buf.decode::<Message, ToMessageError>().unwrap();
+        assert_eq!(result, Some(first));
+        assert_eq!(buf.chunks.len(), 1);
+        let offset = first_bytes.len() - split;
+        assert_eq!(buf.chunks[0].as_ptr(), tail_ptr.wrapping_add(offset));
+
+        let result = buf.decode::<Message, ToMessageError>().unwrap();
+        assert_eq!(result, Some(second));
+        assert!(buf.is_empty());
+    }
 }
 
 
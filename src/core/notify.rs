@@ -167,6 +167,58 @@ impl From<ToMessageError> for ToNoticeError
 }
 
 
+// ===========================================================================
+// Origin
+// ===========================================================================
+
+
+/// Largest notification code reserved for notifications sent by a client.
+pub const MAX_CLIENT_NOTICE_CODE: u64 = 127;
+
+
+/// Largest notification code reserved for notifications sent by a server.
+pub const MAX_SERVER_NOTICE_CODE: u64 = 255;
+
+
+/// Which side of a session sends a notification.
+///
+/// Notification codes are split into ranges by convention:
+///
+/// * `0..=127` are sent by the client
+/// * `128..=255` are sent by the server
+///
+/// Codes outside of these ranges have an unknown origin.
+///
+/// The crate's own `Ping` and `Pong` notify codes are an exception: either
+/// side may send a `Ping`, and the `Pong` answering it is sent by whichever
+/// side received the `Ping`, usually the server. Both codes fall in the
+/// client range, so their origin is reported as `Client` regardless of who
+/// sent them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Origin
+{
+    Client,
+    Server,
+    Unknown,
+}
+
+
+impl Origin
+{
+    /// Return the origin of a notification with the given code.
+    pub fn from_code(code: u64) -> Origin
+    {
+        if code <= MAX_CLIENT_NOTICE_CODE {
+            Origin::Client
+        } else if code <= MAX_SERVER_NOTICE_CODE {
+            Origin::Server
+        } else {
+            Origin::Unknown
+        }
+    }
+}
+
+
 // ===========================================================================
 // NotificationMessage
 // ===========================================================================
@@ -215,6 +267,49 @@ where
         let msgargs = &self.as_vec()[2];
         msgargs.as_array().unwrap()
     }
+
+    /// Return the number of arguments.
+    ///
+    /// Unlike `message_args()`, this does not panic if the arguments are
+    /// missing or are not an array. 0 is returned instead.
+    fn arg_count(&self) -> usize
+    {
+        match self.as_vec().get(2).and_then(|v| v.as_array()) {
+            Some(msgargs) => msgargs.len(),
+            None => 0,
+        }
+    }
+
+    /// Return the id of the request this notification refers to.
+    ///
+    /// This follows the convention used by `NotificationMessage::with_ref()`
+    /// where the referenced message id is the first argument. None is
+    /// returned if the first argument is missing or is not a u32.
+    fn message_ref(&self) -> Option<u32>
+    {
+        match self.message_args().first().and_then(|v| v.as_u64()) {
+            Some(v) if v <= u32::max_value() as u64 => Some(v as u32),
+            _ => None,
+        }
+    }
+
+    /// Return which side of a session sent this notification.
+    ///
+    /// The origin is taken from the range the notification code falls in;
+    /// see [`Origin`] for the ranges. `Origin::Unknown` is also returned if
+    /// the code is missing or is not an unsigned integer.
+    ///
+    /// The origin of a `Ping` or `Pong` notification does not say which side
+    /// sent it; see [`Origin`] for details.
+    ///
+    /// [`Origin`]: enum.Origin.html
+    fn notification_origin(&self) -> Origin
+    {
+        match self.as_vec().get(1).and_then(|v| v.as_u64()) {
+            Some(code) => Origin::from_code(code),
+            None => Origin::Unknown,
+        }
+    }
 }
 
 
@@ -304,6 +399,43 @@ where
         }
     }
 
+    /// Create a NotificationMessage that refers to a prior request.
+    ///
+    /// Notifications do not have a message id of their own, so by convention
+    /// the referenced request's message id is stored as the first argument,
+    /// followed by the given arguments. The id can be read back via
+    /// `RpcNotice::message_ref()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// extern crate rmpv;
+    /// extern crate siminau_rpc;
+    ///
+    /// use rmpv::Value;
+    /// use siminau_rpc::core::MessageType;
+    /// use siminau_rpc::core::notify::{NotificationMessage, RpcNotice};
+    ///
+    /// # fn main() {
+    /// // Create Notice alias
+    /// type Notice = NotificationMessage<MessageType>;
+    ///
+    /// // Refer to the request with message id 42
+    /// let notice = Notice::with_ref(MessageType::Request, 42,
+    ///                               vec![Value::from(9001)]);
+    /// assert_eq!(notice.message_ref(), Some(42));
+    /// assert_eq!(notice.message_args(),
+    ///            &vec![Value::from(42), Value::from(9001)]);
+    /// # }
+    /// ```
+    pub fn with_ref(notifycode: C, ref_id: u32, args: Vec<Value>) -> Self
+    {
+        let mut refargs = Vec::with_capacity(args.len() + 1);
+        refargs.push(Value::from(ref_id));
+        refargs.extend(args);
+        Self::new(notifycode, refargs)
+    }
+
     // Checks that the message type parameter of a Notification message is
     // valid.
     //
@@ -333,6 +465,11 @@ where
             check_int(msgcode.as_u64(), C::max_number(), "a value".to_string())
                 .map_err(|e| NoticeCodeError::InvalidValue(e))?;
 
+        // Reject values below the smallest code
+        if msgcode < C::min_number() {
+            return Err(NoticeCodeError::ToCode(msgcode));
+        }
+
         // Convert msgcode into a number that can be accepted by the
         // CodeConvert type
         let msgcode_u64 = msgcode as u64;
@@ -463,6 +600,68 @@ impl<C> From<NotificationMessage<C>> for Value
 }
 
 
+// ===========================================================================
+// Notification parsing
+// ===========================================================================
+
+
+/// Decide how [`parse_notification`] handles unknown notification codes.
+///
+/// A peer running a newer version of the protocol may send notifications
+/// that this side does not know about yet. Setting `ignore_unknown` allows
+/// these to be skipped instead of treated as an error.
+///
+/// [`parse_notification`]: fn.parse_notification.html
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct NotifyPolicy
+{
+    pub ignore_unknown: bool,
+}
+
+
+/// Convert a [`Message`] into a [`NotificationMessage`] following the given
+/// policy.
+///
+/// If the message's code is a valid integer that cannot be converted into
+/// the code type `C` and the policy ignores unknown codes, `Ok(None)` is
+/// returned. Every other problem is returned as an error, same as
+/// `NotificationMessage::from_msg()`.
+///
+/// [`Message`]: ../struct.Message.html
+/// [`NotificationMessage`]: struct.NotificationMessage.html
+pub fn parse_notification<C>(
+    msg: Message, policy: &NotifyPolicy
+) -> Result<Option<NotificationMessage<C>>, ToNoticeError>
+where
+    C: CodeConvert<C>,
+{
+    match NotificationMessage::from_msg(msg) {
+        Ok(notice) => Ok(Some(notice)),
+        Err(ToNoticeError::InvalidCode(ref e))
+            if policy.ignore_unknown && is_unknown_code(e) =>
+        {
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+
+// Return true if the code error was caused by a well-formed code that is not
+// part of the code type
+fn is_unknown_code(err: &NoticeCodeError) -> bool
+{
+    match *err {
+        NoticeCodeError::InvalidValue(CheckIntError::ValueTooBig { .. }) |
+        NoticeCodeError::ToNumber(_) |
+        NoticeCodeError::ToCode(_) => true,
+        NoticeCodeError::InvalidValue(CheckIntError::MissingValue { .. }) => {
+            false
+        }
+    }
+}
+
+
 // ===========================================================================
 //
 // ===========================================================================
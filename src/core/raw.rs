@@ -0,0 +1,611 @@
+// src/core/raw.rs
+// Copyright (C) 2017 authors and contributors (see AUTHORS file)
+//
+// This file is released under the MIT License.
+
+//! This module defines a message view that does not need a code type.
+//!
+//! Converting a [`Message`] into a request, response, or notification
+//! requires knowing the type used for the message's code. A relay that
+//! forwards messages without understanding the protocol can instead use
+//! [`AnyRawMessage`], which only checks the shape of the message and keeps
+//! the id and code as plain numbers.
+//!
+//! # Example
+//!
+//! ```rust
+//! extern crate rmpv;
+//! extern crate siminau_rpc;
+//!
+//! use rmpv::Value;
+//! use siminau_rpc::core::{CodeConvert, FromMessage, Message, MessageType};
+//! use siminau_rpc::core::raw::AnyRawMessage;
+//!
+//! # fn main() {
+//! let msgtype = Value::from(MessageType::Request.to_number());
+//! let val = Value::Array(vec![msgtype, Value::from(42), Value::from(9001),
+//!                             Value::Array(vec![])]);
+//! let msg = Message::from_msg(val).unwrap();
+//!
+//! let raw = AnyRawMessage::from_msg(msg).unwrap();
+//! assert_eq!(raw.message_type(), MessageType::Request);
+//! assert_eq!(raw.message_id(), Some(42));
+//! assert_eq!(raw.code(), 9001);
+//! # }
+//! ```
+//!
+//! A router that only needs a message's type and code to dispatch it can use
+//! [`decode_prefix`] to read them straight from the message's bytes without
+//! decoding the message's arguments, or keep the bytes in a [`LazyMessage`]
+//! that only decodes the arguments when they are asked for.
+//! [`tally_types`] counts the messages of each type in a buffer of many
+//! messages the same way.
+//!
+//! [`Message`]: ../struct.Message.html
+//! [`AnyRawMessage`]: enum.AnyRawMessage.html
+//! [`decode_prefix`]: fn.decode_prefix.html
+//! [`LazyMessage`]: struct.LazyMessage.html
+//! [`tally_types`]: fn.tally_types.html
+
+// ===========================================================================
+// Imports
+// ===========================================================================
+
+
+// Stdlib imports
+
+use std::cell::OnceCell;
+use std::collections::HashMap;
+
+// Third-party imports
+
+use bytes::Bytes;
+use rmp::decode::{read_array_len, read_int, NumValueReadError,
+                  ValueReadError};
+use rmpv::Value;
+use rmpv::decode::{self, read_value};
+
+// Local imports
+
+use core::{check_int, exceeds_depth, value_len, value_type, CheckIntError,
+           CodeConvert, CodeValueError, Message, MessageType, RpcMessage,
+           MAX_DECODE_DEPTH};
+
+
+// ===========================================================================
+// AnyRawMessage errors
+// ===========================================================================
+
+
+#[derive(Debug, Fail)]
+pub enum ToRawMessageError
+{
+    #[fail(display = "Expected array length of {} for {:?} message, got {}",
+           expected, msgtype, len)]
+    ArrayLength
+    {
+        msgtype: MessageType, expected: usize, len: usize
+    },
+
+    #[fail(display = "Invalid message id")]
+    InvalidID(#[cause] CheckIntError),
+
+    #[fail(display = "Invalid message code")]
+    InvalidCode(#[cause] CheckIntError),
+}
+
+
+// ===========================================================================
+// AnyRawMessage
+// ===========================================================================
+
+
+/// A [`Message`] along with its type, id, and code as plain numbers.
+///
+/// [`Message`]: ../struct.Message.html
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnyRawMessage
+{
+    Request
+    {
+        id: u32, code: u64, msg: Message
+    },
+
+    Response
+    {
+        id: u32, code: u64, msg: Message
+    },
+
+    Notification
+    {
+        code: u64, msg: Message
+    },
+}
+
+
+impl AnyRawMessage
+{
+    /// Create an AnyRawMessage from a Message.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the message's length does not match its
+    /// type, if a request or response id is not a u32, or if the code is
+    /// not an unsigned integer.
+    pub fn from_msg(msg: Message) -> Result<AnyRawMessage, ToRawMessageError>
+    {
+        let msgtype = msg.message_type();
+        let expected = match msgtype {
+            MessageType::Notification => 3,
+            _ => 4,
+        };
+        let len = msg.as_vec().len();
+        if len != expected {
+            let err = ToRawMessageError::ArrayLength {
+                msgtype: msgtype,
+                expected: expected,
+                len: len,
+            };
+            return Err(err);
+        }
+
+        let ret = match msgtype {
+            MessageType::Notification => {
+                let code = raw_code(&msg.as_vec()[1])?;
+                AnyRawMessage::Notification {
+                    code: code,
+                    msg: msg,
+                }
+            }
+            MessageType::Request | MessageType::Response => {
+                let id = check_int(
+                    msg.as_vec()[1].as_u64(),
+                    u32::max_value() as u64,
+                    "u32".to_string(),
+                ).map_err(|e| ToRawMessageError::InvalidID(e))?;
+                let code = raw_code(&msg.as_vec()[2])?;
+                let id = id as u32;
+                if msgtype == MessageType::Request {
+                    AnyRawMessage::Request {
+                        id: id,
+                        code: code,
+                        msg: msg,
+                    }
+                } else {
+                    AnyRawMessage::Response {
+                        id: id,
+                        code: code,
+                        msg: msg,
+                    }
+                }
+            }
+        };
+        Ok(ret)
+    }
+
+    /// Return the message's type.
+    pub fn message_type(&self) -> MessageType
+    {
+        match *self {
+            AnyRawMessage::Request { .. } => MessageType::Request,
+            AnyRawMessage::Response { .. } => MessageType::Response,
+            AnyRawMessage::Notification { .. } => MessageType::Notification,
+        }
+    }
+
+    /// Return the message's id.
+    ///
+    /// Notifications do not have an id, so None is returned for them.
+    pub fn message_id(&self) -> Option<u32>
+    {
+        match *self {
+            AnyRawMessage::Request { id, .. } |
+            AnyRawMessage::Response { id, .. } => Some(id),
+            AnyRawMessage::Notification { .. } => None,
+        }
+    }
+
+    /// Return the message's code as a plain number.
+    pub fn code(&self) -> u64
+    {
+        match *self {
+            AnyRawMessage::Request { code, .. } |
+            AnyRawMessage::Response { code, .. } |
+            AnyRawMessage::Notification { code, .. } => code,
+        }
+    }
+
+    /// Return a reference to the wrapped message.
+    pub fn message(&self) -> &Message
+    {
+        match *self {
+            AnyRawMessage::Request { ref msg, .. } |
+            AnyRawMessage::Response { ref msg, .. } |
+            AnyRawMessage::Notification { ref msg, .. } => msg,
+        }
+    }
+}
+
+
+impl From<AnyRawMessage> for Message
+{
+    fn from(raw: AnyRawMessage) -> Message
+    {
+        match raw {
+            AnyRawMessage::Request { msg, .. } |
+            AnyRawMessage::Response { msg, .. } |
+            AnyRawMessage::Notification { msg, .. } => msg,
+        }
+    }
+}
+
+
+// Return the code of a message as a plain number
+fn raw_code(code: &Value) -> Result<u64, ToRawMessageError>
+{
+    check_int(code.as_u64(), u64::max_value(), "a value".to_string())
+        .map_err(|e| ToRawMessageError::InvalidCode(e))
+}
+
+
+// ===========================================================================
+// Message prefix
+// ===========================================================================
+
+
+#[derive(Debug, Fail)]
+pub enum PrefixError
+{
+    #[fail(display = "Unable to read message array header")]
+    ArrayHeader(#[cause] ValueReadError),
+
+    #[fail(display = "Expected message array, got empty array")]
+    EmptyArray,
+
+    #[fail(display = "Unable to read message type")]
+    InvalidType(#[cause] NumValueReadError),
+
+    #[fail(display = "Invalid message type")]
+    UnknownType(#[cause] CodeValueError),
+
+    #[fail(display = "Expected array length of {} for {:?} message, got {}",
+           expected, msgtype, len)]
+    ArrayLength
+    {
+        msgtype: MessageType, expected: u32, len: u32
+    },
+
+    #[fail(display = "Unable to read message id")]
+    InvalidID(#[cause] NumValueReadError),
+
+    #[fail(display = "Unable to read message code")]
+    InvalidCode(#[cause] NumValueReadError),
+}
+
+
+/// Decode only the type and code of a msgpack encoded message.
+///
+/// Decoding stops right after the code, so the message's arguments are
+/// neither decoded nor required to be present in `buf`. The message id of
+/// a request or response is checked to be a u32 but is otherwise skipped.
+///
+/// # Errors
+///
+/// An error is returned if `buf` does not start with an array whose length
+/// matches its message type, or if the message's type, id, or code cannot
+/// be read.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate siminau_rpc;
+///
+/// use siminau_rpc::core::MessageType;
+/// use siminau_rpc::core::raw::decode_prefix;
+///
+/// # fn main() {
+/// // Request with msg id 42 and code 9, cut off inside its args
+/// let buf = [0x94, 0x00, 0x2a, 0x09, 0xdc, 0xff];
+///
+/// let (msgtype, code) = decode_prefix(&buf[..]).unwrap();
+/// assert_eq!(msgtype, MessageType::Request);
+/// assert_eq!(code, 9);
+/// # }
+/// ```
+pub fn decode_prefix(buf: &[u8]) -> Result<(MessageType, u64), PrefixError>
+{
+    let mut rd = buf;
+    let prefix = read_prefix(&mut rd)?;
+    Ok((prefix.msgtype, prefix.code))
+}
+
+
+// The items of a message before its args or result
+struct Prefix
+{
+    msgtype: MessageType,
+    id: Option<u32>,
+    code: u64,
+}
+
+
+// Read a message's prefix from rd, leaving rd at the start of the message's
+// args or result
+fn read_prefix(rd: &mut &[u8]) -> Result<Prefix, PrefixError>
+{
+    let len = read_array_len(rd).map_err(|e| PrefixError::ArrayHeader(e))?;
+    if len == 0 {
+        return Err(PrefixError::EmptyArray);
+    }
+
+    let msgtype: u8 = read_int(rd).map_err(|e| PrefixError::InvalidType(e))?;
+    let msgtype = MessageType::from_number(msgtype)
+        .map_err(|e| PrefixError::UnknownType(e))?;
+
+    let expected = match msgtype {
+        MessageType::Notification => 3,
+        _ => 4,
+    };
+    if len != expected {
+        let err = PrefixError::ArrayLength {
+            msgtype: msgtype,
+            expected: expected,
+            len: len,
+        };
+        return Err(err);
+    }
+
+    // Requests and responses have a msg id before the code
+    let id = match msgtype {
+        MessageType::Notification => None,
+        _ => Some(read_int(rd).map_err(|e| PrefixError::InvalidID(e))?),
+    };
+
+    let code: u64 = read_int(rd).map_err(|e| PrefixError::InvalidCode(e))?;
+    let ret = Prefix {
+        msgtype: msgtype,
+        id: id,
+        code: code,
+    };
+    Ok(ret)
+}
+
+
+// ===========================================================================
+// Message tallies
+// ===========================================================================
+
+
+#[derive(Debug, Fail)]
+pub enum TallyError
+{
+    #[fail(display = "Invalid message prefix at byte {}", offset)]
+    Prefix
+    {
+        #[cause] err: PrefixError,
+        offset: usize,
+    },
+
+    #[fail(display = "Message at byte {} is cut off", _0)]
+    Truncated(usize),
+}
+
+
+/// Count the messages of each type in a buffer of msgpack encoded messages.
+///
+/// Only the prefix of each message is decoded; its args or result are
+/// skipped over without being decoded. Types without any messages in `buf`
+/// have no entry in the returned map.
+///
+/// # Errors
+///
+/// An error holding the offset of the offending message is returned if a
+/// message's prefix cannot be decoded, or if `buf` ends in the middle of a
+/// message.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate siminau_rpc;
+///
+/// use siminau_rpc::core::MessageType;
+/// use siminau_rpc::core::raw::tally_types;
+///
+/// # fn main() {
+/// // Request with msg id 42 and code 9, then notification with code 1
+/// let buf = [0x94, 0x00, 0x2a, 0x09, 0x90, 0x93, 0x02, 0x01, 0x90];
+///
+/// let tally = tally_types(&buf[..]).unwrap();
+/// assert_eq!(tally[&MessageType::Request], 1);
+/// assert_eq!(tally[&MessageType::Notification], 1);
+/// assert!(!tally.contains_key(&MessageType::Response));
+/// # }
+/// ```
+pub fn tally_types(buf: &[u8])
+    -> Result<HashMap<MessageType, usize>, TallyError>
+{
+    let mut tally = HashMap::new();
+    let mut offset = 0;
+    while offset < buf.len() {
+        let mut rd = &buf[offset..];
+        let prefix = read_prefix(&mut rd).map_err(|e| {
+            TallyError::Prefix {
+                err: e,
+                offset: offset,
+            }
+        })?;
+
+        // The args or result are the only item left in the message
+        let len = value_len(rd).ok_or(TallyError::Truncated(offset))?;
+        offset = buf.len() - rd.len() + len;
+        *tally.entry(prefix.msgtype).or_insert(0) += 1;
+    }
+    Ok(tally)
+}
+
+
+// ===========================================================================
+// LazyMessage
+// ===========================================================================
+
+
+#[derive(Debug, Fail)]
+pub enum LazyArgsError
+{
+    #[fail(display = "Unable to decode message arguments")]
+    Decode(#[cause] decode::Error),
+
+    #[fail(display = "Message arguments nested deeper than {} levels", _0)]
+    DepthLimitExceeded(usize),
+
+    #[fail(display = "Expected array of message arguments, got {}", _0)]
+    NotArray(String),
+
+    #[fail(display = "Found {} unused bytes after message arguments", _0)]
+    TrailingBytes(usize),
+}
+
+
+/// The bytes of a single message, with its args decoded only when needed.
+///
+/// The message's type, id, and code are decoded when the LazyMessage is
+/// created. Its args are decoded the first time [`args`] is called, and the
+/// decoded args are kept for later calls.
+///
+/// The args are the last item of the message, which for a request or a
+/// notification is always an array. A response's result is only treated as
+/// args if it is an array.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate bytes;
+/// extern crate siminau_rpc;
+///
+/// use bytes::Bytes;
+/// use siminau_rpc::core::MessageType;
+/// use siminau_rpc::core::raw::LazyMessage;
+///
+/// # fn main() {
+/// // Request with msg id 42, code 9, and a single arg of 1
+/// let buf = Bytes::from(&[0x94, 0x00, 0x2a, 0x09, 0x91, 0x01][..]);
+///
+/// let msg = LazyMessage::new(buf).unwrap();
+/// assert_eq!(msg.message_type(), MessageType::Request);
+/// assert_eq!(msg.code(), 9);
+/// assert!(!msg.args_decoded());
+///
+/// assert_eq!(msg.args().unwrap().len(), 1);
+/// assert!(msg.args_decoded());
+/// # }
+/// ```
+///
+/// [`args`]: #method.args
+#[derive(Debug)]
+pub struct LazyMessage
+{
+    buf: Bytes,
+    msgtype: MessageType,
+    id: Option<u32>,
+    code: u64,
+    args_start: usize,
+    args: OnceCell<Vec<Value>>,
+}
+
+
+impl LazyMessage
+{
+    /// Create a LazyMessage from the bytes of a single message.
+    ///
+    /// # Errors
+    ///
+    /// The same errors as [`decode_prefix`] are returned.
+    ///
+    /// [`decode_prefix`]: fn.decode_prefix.html
+    pub fn new(buf: Bytes) -> Result<LazyMessage, PrefixError>
+    {
+        let (prefix, args_start) = {
+            let mut rd = &buf[..];
+            let prefix = read_prefix(&mut rd)?;
+            (prefix, buf.len() - rd.len())
+        };
+
+        let ret = LazyMessage {
+            buf: buf,
+            msgtype: prefix.msgtype,
+            id: prefix.id,
+            code: prefix.code,
+            args_start: args_start,
+            args: OnceCell::new(),
+        };
+        Ok(ret)
+    }
+
+    /// Return the message's type.
+    pub fn message_type(&self) -> MessageType
+    {
+        self.msgtype.clone()
+    }
+
+    /// Return the message's id.
+    ///
+    /// Notifications do not have an id, so None is returned for them.
+    pub fn message_id(&self) -> Option<u32>
+    {
+        self.id
+    }
+
+    /// Return the message's code as a plain number.
+    pub fn code(&self) -> u64
+    {
+        self.code
+    }
+
+    /// Return the bytes of the whole message.
+    pub fn as_bytes(&self) -> &Bytes
+    {
+        &self.buf
+    }
+
+    /// Return true if the message's args have been decoded.
+    pub fn args_decoded(&self) -> bool
+    {
+        self.args.get().is_some()
+    }
+
+    /// Return the message's args, decoding them if this is the first call.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the args cannot be decoded, are nested too
+    /// deeply, are not an array, or are followed by more bytes. Nothing is
+    /// kept on error, so a later call decodes the args again.
+    pub fn args(&self) -> Result<&[Value], LazyArgsError>
+    {
+        if let Some(args) = self.args.get() {
+            return Ok(&args[..]);
+        }
+
+        let raw = &self.buf[self.args_start..];
+        if exceeds_depth(raw, MAX_DECODE_DEPTH).is_some() {
+            return Err(LazyArgsError::DepthLimitExceeded(MAX_DECODE_DEPTH));
+        }
+
+        let mut rd = raw;
+        let value = read_value(&mut rd).map_err(|e| LazyArgsError::Decode(e))?;
+        if !rd.is_empty() {
+            return Err(LazyArgsError::TrailingBytes(rd.len()));
+        }
+
+        let args = match value {
+            Value::Array(args) => args,
+            v => return Err(LazyArgsError::NotArray(value_type(&v))),
+        };
+        Ok(&self.args.get_or_init(|| args)[..])
+    }
+}
+
+
+// ===========================================================================
+//
+// ===========================================================================
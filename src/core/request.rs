@@ -95,15 +95,18 @@
 // Stdlib imports
 
 use std::marker::PhantomData;
+use std::mem;
 
 // Third-party imports
 
 use rmpv::Value;
+use rmpv::decode::{self, read_value};
 
 // Local imports
 
-use core::{check_int, value_type, CheckIntError, CodeConvert, FromMessage,
-           Message, MessageType, RpcMessage, RpcMessageType, ToMessageError};
+use core::{check_int, exceeds_depth, value_type, CheckIntError, CodeConvert,
+           FromMessage, Message, MessageType, RpcMessage, RpcMessageType,
+           ToMessageError, MAX_DECODE_DEPTH};
 
 
 // ===========================================================================
@@ -183,6 +186,67 @@ impl From<ToMessageError> for ToRequestError
 }
 
 
+#[derive(Debug, Fail)]
+pub enum FromRawArgsError
+{
+    #[fail(display = "Unable to decode raw request arguments")]
+    Decode(#[cause] decode::Error),
+
+    #[fail(display = "Raw request arguments nested deeper than {} levels",
+           _0)]
+    DepthLimitExceeded(usize),
+
+    #[fail(display = "Invalid raw request arguments")]
+    InvalidArgs(#[cause] RequestArgsError),
+
+    #[fail(display = "Found {} unused bytes after raw request arguments",
+           _0)]
+    TrailingBytes(usize),
+}
+
+
+#[derive(Debug, Fail)]
+#[fail(display = "Request message id {} is reserved", msgid)]
+pub struct ReservedIDError
+{
+    pub msgid: u32,
+}
+
+
+// ===========================================================================
+// Message id policy
+// ===========================================================================
+
+
+/// Decide which message ids may be given to a new request.
+///
+/// Some peers use message id 0 to mean "no request", eg for errors that
+/// cannot be matched to any request. Setting `reserve_zero_id` makes
+/// [`RequestMessage::new_with_policy`] refuse to create a request with that
+/// id. The default policy allows every id.
+///
+/// [`RequestMessage::new_with_policy`]:
+/// struct.RequestMessage.html#method.new_with_policy
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct IDPolicy
+{
+    pub reserve_zero_id: bool,
+}
+
+
+impl IDPolicy
+{
+    /// Return an error if the policy does not allow `msgid`.
+    pub fn check(&self, msgid: u32) -> Result<(), ReservedIDError>
+    {
+        if self.reserve_zero_id && msgid == 0 {
+            return Err(ReservedIDError { msgid: msgid });
+        }
+        Ok(())
+    }
+}
+
+
 // ===========================================================================
 // RequestMessage
 // ===========================================================================
@@ -241,6 +305,18 @@ where
         let msgargs = &self.as_vec()[3];
         msgargs.as_array().unwrap()
     }
+
+    /// Return the number of arguments.
+    ///
+    /// Unlike `message_args()`, this does not panic if the arguments are
+    /// missing or are not an array. 0 is returned instead.
+    fn arg_count(&self) -> usize
+    {
+        match self.as_vec().get(3).and_then(|v| v.as_array()) {
+            Some(msgargs) => msgargs.len(),
+            None => 0,
+        }
+    }
 }
 
 
@@ -331,6 +407,191 @@ where
         }
     }
 
+    // Wrap a message without validating it.
+    //
+    // Only used by tests that need a request breaking the usual invariants,
+    // eg one with the wrong message type.
+    #[cfg(test)]
+    pub fn from_msg_unchecked(msg: Message) -> Self
+    {
+        Self {
+            msg: msg,
+            codetype: PhantomData,
+        }
+    }
+
+    /// Start building a RequestMessage with room for `capacity` arguments.
+    ///
+    /// Arguments are added one at a time with `push_arg()` and the request is
+    /// created with `finish()`. Reserving space for all arguments up front
+    /// avoids growing the argument vector while a large request is built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// extern crate rmpv;
+    /// extern crate siminau_rpc;
+    ///
+    /// use rmpv::Value;
+    /// use siminau_rpc::core::MessageType;
+    /// use siminau_rpc::core::request::{RequestMessage, RpcRequest};
+    ///
+    /// # fn main() {
+    /// type Request = RequestMessage<MessageType>;
+    ///
+    /// let mut builder =
+    ///     Request::with_capacity(42, MessageType::Notification, 2);
+    /// builder.push_arg(Value::from(1));
+    /// builder.push_arg(Value::from(2));
+    /// let req = builder.finish();
+    /// assert_eq!(req.message_args(), &vec![Value::from(1), Value::from(2)]);
+    /// # }
+    /// ```
+    pub fn with_capacity(msgid: u32, msgmeth: C, capacity: usize)
+        -> RequestArgsBuilder<C>
+    {
+        RequestArgsBuilder {
+            msgid: msgid,
+            msgmeth: msgmeth,
+            args: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Create a new RequestMessage object from already serialized arguments.
+    ///
+    /// `raw` must hold exactly one msgpack array, which is decoded and used
+    /// as the request's arguments. This lets a caller that only has the
+    /// encoded arguments, eg a proxy, build a request without first decoding
+    /// them itself.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// extern crate rmpv;
+    /// extern crate siminau_rpc;
+    ///
+    /// use rmpv::Value;
+    /// use siminau_rpc::core::MessageType;
+    /// use siminau_rpc::core::request::{RequestMessage, RpcRequest};
+    ///
+    /// # fn main() {
+    /// type Request = RequestMessage<MessageType>;
+    ///
+    /// // Msgpack encoding of [42]
+    /// let raw = [0x91, 0x2a];
+    /// let req = Request::from_raw_args(42, MessageType::Notification, &raw)
+    ///     .unwrap();
+    /// assert_eq!(req.message_args(), &vec![Value::from(42)]);
+    /// # }
+    /// ```
+    pub fn from_raw_args(msgid: u32, msgmeth: C, raw: &[u8])
+        -> Result<Self, FromRawArgsError>
+    {
+        if exceeds_depth(raw, MAX_DECODE_DEPTH).is_some() {
+            return Err(FromRawArgsError::DepthLimitExceeded(MAX_DECODE_DEPTH));
+        }
+
+        let mut rd = raw;
+        let value = read_value(&mut rd).map_err(FromRawArgsError::Decode)?;
+        if !rd.is_empty() {
+            return Err(FromRawArgsError::TrailingBytes(rd.len()));
+        }
+
+        match value {
+            Value::Array(args) => Ok(Self::new(msgid, msgmeth, args)),
+            v => {
+                let err = RequestArgsError {
+                    value_type: value_type(&v),
+                };
+                Err(FromRawArgsError::InvalidArgs(err))
+            }
+        }
+    }
+
+    /// Create a new RequestMessage object if `msgid` is allowed by `policy`.
+    ///
+    /// This is the same as `new()`, except that a message id reserved by
+    /// the policy is returned as an error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// extern crate siminau_rpc;
+    ///
+    /// use siminau_rpc::core::MessageType;
+    /// use siminau_rpc::core::request::{IDPolicy, RequestMessage};
+    ///
+    /// # fn main() {
+    /// type Request = RequestMessage<MessageType>;
+    ///
+    /// let policy = IDPolicy { reserve_zero_id: true };
+    /// let code = MessageType::Notification;
+    /// let zero = Request::new_with_policy(0, code.clone(), vec![], &policy);
+    /// let one = Request::new_with_policy(1, code, vec![], &policy);
+    /// assert!(zero.is_err());
+    /// assert!(one.is_ok());
+    /// # }
+    /// ```
+    pub fn new_with_policy(
+        msgid: u32, msgmeth: C, args: Vec<Value>, policy: &IDPolicy
+    ) -> Result<Self, ReservedIDError>
+    {
+        policy.check(msgid)?;
+        Ok(Self::new(msgid, msgmeth, args))
+    }
+
+    /// Create a copy of this request with a different message id.
+    ///
+    /// The method code and arguments are unchanged. This is useful when a
+    /// request needs to be resent, eg after reconnecting.
+    pub fn rebuild_with_id(&self, new_id: u32) -> RequestMessage<C>
+    {
+        let args = self.message_args().clone();
+        Self::new(new_id, self.message_method(), args)
+    }
+
+    /// Move the string argument at index `i` out of the message.
+    ///
+    /// The argument is replaced with `Nil`. Returns `None`, leaving the
+    /// message untouched, if there is no argument at `i` or it is not a
+    /// valid utf-8 string.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// extern crate rmpv;
+    /// extern crate siminau_rpc;
+    ///
+    /// use rmpv::Value;
+    /// use siminau_rpc::core::MessageType;
+    /// use siminau_rpc::core::request::{RequestMessage, RpcRequest};
+    ///
+    /// # fn main() {
+    /// type Request = RequestMessage<MessageType>;
+    ///
+    /// let mut req = Request::new(42, MessageType::Notification,
+    ///                            vec![Value::from("hello")]);
+    ///
+    /// assert_eq!(req.take_string_arg(0), Some("hello".to_string()));
+    /// assert_eq!(req.message_args(), &vec![Value::Nil]);
+    /// # }
+    /// ```
+    pub fn take_string_arg(&mut self, i: usize) -> Option<String>
+    {
+        let args = match self.msg.as_vec_mut()[3] {
+            Value::Array(ref mut args) => args,
+            _ => unreachable!(),
+        };
+
+        // Only valid utf-8 strings can be taken
+        args.get(i).and_then(|v| v.as_str())?;
+
+        match mem::replace(&mut args[i], Value::Nil) {
+            Value::String(s) => s.into_str(),
+            _ => unreachable!(),
+        }
+    }
+
     // Checks that the message type parameter of a Request message is valid
     //
     // This is a private method used by the public from_msg() method
@@ -368,6 +629,11 @@ where
             check_int(msgmeth.as_u64(), C::max_number(), "a value".to_string())
                 .map_err(|e| RequestCodeError::InvalidValue(e))?;
 
+        // Reject values below the smallest code
+        if msgmeth < C::min_number() {
+            return Err(RequestCodeError::ToCode(msgmeth));
+        }
+
         // Convert msgmeth into a number that can be accepted by the CodeConvert
         // type
         let msgmeth_u64 = msgmeth as u64;
@@ -504,6 +770,43 @@ impl<C> From<RequestMessage<C>> for Value
 }
 
 
+// ===========================================================================
+// RequestArgsBuilder
+// ===========================================================================
+
+
+/// Builder adding a request's arguments one at a time.
+///
+/// Created by [`RequestMessage::with_capacity`].
+///
+/// [`RequestMessage::with_capacity`]: struct.RequestMessage.html#method.with_capacity
+#[derive(Debug)]
+pub struct RequestArgsBuilder<C>
+{
+    msgid: u32,
+    msgmeth: C,
+    args: Vec<Value>,
+}
+
+
+impl<C> RequestArgsBuilder<C>
+where
+    C: CodeConvert<C>,
+{
+    /// Append an argument to the request.
+    pub fn push_arg(&mut self, arg: Value)
+    {
+        self.args.push(arg);
+    }
+
+    /// Create the request from the arguments added so far.
+    pub fn finish(self) -> RequestMessage<C>
+    {
+        RequestMessage::new(self.msgid, self.msgmeth, self.args)
+    }
+}
+
+
 // ===========================================================================
 //
 // ===========================================================================
@@ -313,6 +313,42 @@ where
         }
     }
 
+    /// Replace the response's message id.
+    ///
+    /// The response is changed in place, so nothing else about it is
+    /// rebuilt. This is meant for serving a response to a request other than
+    /// the one it was originally created for.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// extern crate rmpv;
+    /// extern crate siminau_rpc;
+    ///
+    /// use rmpv::Value;
+    /// use siminau_rpc::core::MessageType;
+    /// use siminau_rpc::core::response::{ResponseMessage, RpcResponse};
+    ///
+    /// # fn main() {
+    /// type Response = ResponseMessage<MessageType>;
+    ///
+    /// let mut res = Response::new(42, MessageType::Notification,
+    ///                             Value::from(42));
+    /// res.set_id(9001);
+    /// assert_eq!(res.message_id(), 9001);
+    /// # }
+    /// ```
+    pub fn set_id(&mut self, id: u32)
+    {
+        self.msg.as_vec_mut()[1] = Value::from(id);
+    }
+
+    /// Replace the response's result in place.
+    pub fn set_result(&mut self, v: Value)
+    {
+        self.msg.as_vec_mut()[3] = v;
+    }
+
     // Checks that the message type parameter of a Response message is valid
     //
     // This is a private method used by the public from_msg() method
@@ -349,6 +385,11 @@ where
             check_int(errcode.as_u64(), C::max_number(), "a value".to_string())
                 .map_err(|e| ResponseCodeError::InvalidValue(e))?;
 
+        // Reject values below the smallest code
+        if errcode < C::min_number() {
+            return Err(ResponseCodeError::ToCode(errcode));
+        }
+
         // Convert errcode into a number that can be accepted by the
         // CodeConvert type
         let errcode_u64 = errcode as u64;
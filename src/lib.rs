@@ -39,6 +39,7 @@ extern crate failure_derive;
 extern crate failure;
 
 extern crate futures;
+extern crate libc;
 extern crate tokio_core;
 extern crate tokio_io;
 
@@ -50,6 +51,7 @@ extern crate proptest;
 #[macro_use]
 extern crate quickcheck;
 
+extern crate rmp;
 extern crate rmp_serde as rmps;
 extern crate rmpv;
 extern crate serde;
@@ -69,6 +71,7 @@ extern crate siminau_rpc_derive;
 pub mod core;
 pub mod future;
 pub mod message;
+pub mod session;
 pub mod util;
 
 #[cfg(test)]
@@ -94,7 +97,7 @@ pub use self::core::request::RequestMessage;
 
 // Traits
 
-pub use self::core::{CodeConvert, RpcMessage, RpcMessageType};
+pub use self::core::{CodeConvert, DynMessage, RpcMessage, RpcMessageType};
 // pub use self::core::notify::RpcNotice;
 
 pub use self::core::request::RpcRequest;
@@ -12,14 +12,17 @@
 
 // Third-party imports
 
+use bytes::Bytes;
+use failure::{Error, Fail};
 use rmpv::Value;
 
 // Local imports
 
-use core::{CodeConvert, CodeValueError};
-use core::notify::NotificationMessage;
+use core::{AsBytes, CodeConvert, CodeValueError, FromBytesError, FromMessage,
+           Message};
+use core::notify::{NotificationMessage, RpcNotice, ToNoticeError};
 use core::request::{RequestMessage, RpcRequest};
-use core::response::ResponseMessage;
+use core::response::{ResponseMessage, RpcResponse, ToResponseError};
 
 
 // ===========================================================================
@@ -30,6 +33,27 @@ use core::response::ResponseMessage;
 pub mod v1;
 
 
+// ===========================================================================
+// Protocol sets
+// ===========================================================================
+
+
+/// Tie a message code type to the protocol version it belongs to.
+///
+/// Generic code that only knows a message's code type can use this to find
+/// the protocol version and the kind of message the codes are used for.
+pub trait ProtocolSet
+{
+    /// The protocol version number negotiated with a Version request.
+    const VERSION: u32;
+
+    /// The [`MessageType`] number of the messages using these codes.
+    ///
+    /// [`MessageType`]: ../core/enum.MessageType.html
+    const CATEGORY: u8;
+}
+
+
 // ===========================================================================
 // Message codes
 // ===========================================================================
@@ -75,6 +99,32 @@ pub enum NotifyCode {
     //
     // No arguments
     Done = 0,
+
+    // Check that the other side of the connection is still alive. The
+    // receiver must answer with a Pong notification.
+    //
+    // The code is placed above every request and response code so it cannot
+    // be mistaken for one.
+    //
+    // Single argument:
+    // 1. u32 token that must be echoed back in the Pong notification
+    Ping = 28,
+
+    // Answer to a Ping notification
+    //
+    // Single argument:
+    // 1. u32 token of the Ping notification being answered
+    Pong = 29,
+}
+
+
+#[derive(Debug, PartialEq, Clone, CodeConvert)]
+pub enum BatchCode {
+    // Several notifications sent as one
+    //
+    // Any number of arguments:
+    // 1. Each argument is a complete notification message
+    Batch = 1,
 }
 
 
@@ -92,6 +142,9 @@ pub type Response = ResponseMessage<ResponseCode>;
 pub type Info = NotificationMessage<NotifyCode>;
 
 
+pub type Batch = NotificationMessage<BatchCode>;
+
+
 // ===========================================================================
 // Request builder
 // ===========================================================================
@@ -113,6 +166,14 @@ impl RequestBuilder {
         let ver = Value::from(version_number);
         Request::new(self.id, RequestCode::Version, vec![ver])
     }
+
+    // Request a protocol version along with the largest message size, in
+    // bytes, that the client wants to use
+    pub fn version_with(self, version_number: u32, max_size: u32) -> Request
+    {
+        let args = vec![Value::from(version_number), Value::from(max_size)];
+        Request::new(self.id, RequestCode::Version, args)
+    }
 }
 
 
@@ -127,6 +188,20 @@ pub fn request(msgid: u32) -> RequestBuilder
 // ===========================================================================
 
 
+#[derive(Debug, Fail)]
+pub enum BuildResponseError
+{
+    #[fail(display = "Unable to build response message: expected \
+                      RequestCode::{:?}, got RequestCode::{:?} instead",
+           expected, value)]
+    WrongCode
+    {
+        value: RequestCode,
+        expected: RequestCode,
+    },
+}
+
+
 pub struct ResponseBuilder<'request> {
     request: &'request Request,
 }
@@ -145,18 +220,45 @@ impl<'request> ResponseBuilder<'request> {
         Response::new(msgid, ResponseCode::Error, errmsg)
     }
 
-    pub fn version(self, num: u32) -> Response
+    // Accept a version request with the given version number
+    //
+    // An error is returned if the request is not a version request.
+    pub fn version(self, num: u32) -> Result<Response, BuildResponseError>
+    {
+        self.version_response(Value::from(num))
+    }
+
+    // Accept a version request, granting a max message size that may be
+    // smaller than the one requested
+    //
+    // The result is a [num, max_size] array instead of the plain version
+    // number returned by version(). An error is returned if the request is
+    // not a version request.
+    pub fn version_with(self, num: u32, max_size: u32)
+        -> Result<Response, BuildResponseError>
+    {
+        let result = Value::Array(vec![Value::from(num),
+                                       Value::from(max_size)]);
+        self.version_response(result)
+    }
+
+    // Build a Version response holding result, checking that the request is
+    // a version request
+    fn version_response(self, result: Value)
+        -> Result<Response, BuildResponseError>
     {
         let req = self.request;
-        match req.message_method() {
-            // If add any more variants to RequestCode, pls uncomment below
-            // _ => return BuildResponseError)
-            RequestCode::Version => {}
+        let code = req.message_method();
+        if code != RequestCode::Version {
+            let err = BuildResponseError::WrongCode {
+                value: code,
+                expected: RequestCode::Version,
+            };
+            return Err(err);
         }
 
-        let num = Value::from(num);
         let msgid = req.message_id();
-        Response::new(msgid, ResponseCode::Version, num)
+        Ok(Response::new(msgid, ResponseCode::Version, result))
     }
 }
 
@@ -167,6 +269,125 @@ pub fn response(request: &Request) -> ResponseBuilder
 }
 
 
+/// Build an Error response reporting that a request could not be decoded.
+///
+/// The error message describes the kind of decode failure without exposing
+/// any details of the error itself, eg the underlying io error or the
+/// validation error of an invalid message.
+pub fn error_from_decode<E>(req_id: u32, err: &FromBytesError<E>) -> Response
+where
+    E: Fail,
+{
+    let problem = match *err {
+        FromBytesError::InvalidMarkerRead(_) |
+        FromBytesError::InvalidDataRead(_) => "malformed or truncated data",
+        FromBytesError::TypeMismatch { .. } => "unexpected value type",
+        FromBytesError::OutOfRange { .. } => "value out of range",
+        FromBytesError::LengthMismatch { .. } => "length mismatch",
+        FromBytesError::Uncategorized { .. } |
+        FromBytesError::Syntax { .. } => "invalid data",
+        FromBytesError::Utf8Error { .. } => "string is not valid utf-8",
+        FromBytesError::DepthLimitExceeded { .. } => {
+            "data is nested too deeply"
+        }
+        FromBytesError::FrameTooLarge { .. } => "frame is too large",
+        FromBytesError::FrameMismatch(_) => "frame length mismatch",
+        FromBytesError::InvalidMessage(_) => "invalid request message",
+    };
+    let errmsg = format!("Unable to decode request: {}", problem);
+    Response::new(req_id, ResponseCode::Error, Value::from(errmsg))
+}
+
+
+/// Build an Error response from the error returned by a request handler.
+///
+/// The error message is the error's description followed by the description
+/// of each of its causes, separated by ": ".
+pub fn error_from_failure(req_id: u32, err: &Error) -> Response
+{
+    let chain: Vec<String> =
+        err.iter_chain().map(|e| e.to_string()).collect();
+    let errmsg = chain.join(": ");
+    Response::new(req_id, ResponseCode::Error, Value::from(errmsg))
+}
+
+
+// ===========================================================================
+// Response parsing
+// ===========================================================================
+
+
+/// Decode the reply to a Version request.
+///
+/// A Version request is answered with either a Version response or an Error
+/// response. The inner `Ok` holds the Version response while the inner `Err`
+/// holds the Error response.
+///
+/// # Errors
+///
+/// An error is returned if the message cannot be converted into a Response.
+pub fn parse_version_reply(
+    msg: Message
+) -> Result<Result<Response, Response>, ToResponseError>
+{
+    let resp = Response::from_msg(msg)?;
+    match resp.error_code() {
+        ResponseCode::Version => Ok(Ok(resp)),
+        ResponseCode::Error => Ok(Err(resp)),
+    }
+}
+
+
+/// Return the protocol version number granted by a Version response.
+///
+/// Both the plain version number result and the `[num, max_size]` result
+/// built by `ResponseBuilder::version_with()` are accepted. None is returned
+/// if the response is not a Version response or the number is not a u32.
+pub fn negotiated_version(resp: &Response) -> Option<u32>
+{
+    match resp.error_code() {
+        ResponseCode::Version => {}
+        ResponseCode::Error => return None,
+    }
+
+    match *resp.result() {
+        Value::Array(ref result) => result.first().and_then(value_u32),
+        ref num => value_u32(num),
+    }
+}
+
+
+/// Return the max message size negotiated by a Version request and its
+/// response.
+///
+/// The size granted by the response is returned if there is one, otherwise
+/// the size asked for by the request is returned. None is returned if
+/// neither message has a u32 size, or if the response is not a Version
+/// response.
+pub fn negotiated_max_size(req: &Request, resp: &Response) -> Option<u32>
+{
+    match resp.error_code() {
+        ResponseCode::Version => {}
+        ResponseCode::Error => return None,
+    }
+
+    match *resp.result() {
+        Value::Array(ref result) if result.len() > 1 => value_u32(&result[1]),
+        _ => req.message_args().get(1).and_then(value_u32),
+    }
+}
+
+
+// Return a value as a u32 if it holds an integer that fits
+fn value_u32(val: &Value) -> Option<u32>
+{
+    match val.as_u64() {
+        Some(n) if n <= u64::from(u32::max_value()) => Some(n as u32),
+        _ => None,
+    }
+}
+
+
 // ===========================================================================
 // Info builder
 // ===========================================================================
@@ -185,6 +406,16 @@ impl InfoBuilder {
     {
         Info::new(NotifyCode::Done, vec![])
     }
+
+    pub fn ping(self, token: u32) -> Info
+    {
+        Info::new(NotifyCode::Ping, vec![Value::from(token)])
+    }
+
+    pub fn pong(self, token: u32) -> Info
+    {
+        Info::new(NotifyCode::Pong, vec![Value::from(token)])
+    }
 }
 
 
@@ -194,6 +425,73 @@ pub fn info() -> InfoBuilder
 }
 
 
+/// Build a Done notification and serialize it into msgpack bytes.
+///
+/// This is a shortcut for `info().done().as_bytes()`, used by a client to
+/// cleanly close a session.
+pub fn done_bytes() -> Bytes
+{
+    info().done().as_bytes()
+}
+
+
+/// Build the Pong notification answering a Ping notification.
+///
+/// None is returned if the notification is not a Ping, or if its token is
+/// missing or is not a u32.
+pub fn reply_to_ping(notice: &Info) -> Option<Info>
+{
+    match notice.message_code() {
+        NotifyCode::Ping => {}
+        _ => return None,
+    }
+
+    let token = notice.message_args().first().and_then(|v| v.as_u64())?;
+    if token > u64::from(u32::max_value()) {
+        return None;
+    }
+    Some(info().pong(token as u32))
+}
+
+
+// ===========================================================================
+// Batch notifications
+// ===========================================================================
+
+
+/// Combine several notifications into a single Batch notification.
+///
+/// Each notification is stored whole as one argument of the batch, in the
+/// given order.
+pub fn batch_notifications<C>(notices: Vec<NotificationMessage<C>>) -> Batch
+where
+    C: CodeConvert<C>,
+{
+    let args = notices.into_iter().map(Value::from).collect();
+    Batch::new(BatchCode::Batch, args)
+}
+
+
+/// Split a Batch notification back into the notifications it holds.
+///
+/// # Errors
+///
+/// An error is returned if any argument of the batch is not a valid
+/// notification with a code of type `C`.
+pub fn unbatch_notifications<C>(
+    batch: &Batch
+) -> Result<Vec<NotificationMessage<C>>, ToNoticeError>
+where
+    C: CodeConvert<C>,
+{
+    batch
+        .message_args()
+        .iter()
+        .map(|arg| NotificationMessage::<C>::from_msg(arg.clone()))
+        .collect()
+}
+
+
 
 // ===========================================================================
 //
@@ -0,0 +1,127 @@
+// src/message/v1/describe.rs
+// Copyright (C) 2017 authors and contributors (see AUTHORS file)
+//
+// This file is released under the MIT License.
+
+// ===========================================================================
+// Imports
+// ===========================================================================
+
+
+// Stdlib imports
+
+// Third-party imports
+
+use rmpv::Value;
+
+// Local imports
+
+use core::CodeConvert;
+use core::request::RpcRequest;
+use core::response::RpcResponse;
+
+// Parent-module imports
+use super::{expected_response_args, Request, RequestCode, Response,
+            ResponseCode};
+
+
+// ===========================================================================
+// Argument names
+// ===========================================================================
+
+
+fn request_arg_names(code: &RequestCode) -> &'static [&'static str]
+{
+    match *code {
+        RequestCode::Auth => &["authfile_id", "username", "fsname"],
+        RequestCode::Flush => &["prev_msgid"],
+        RequestCode::Attach => {
+            &["rootdir_id", "authfile_id", "username", "fsname"]
+        }
+        RequestCode::Walk => &["file_id", "newfile_id", "path"],
+        RequestCode::Open => &["file_id", "mode"],
+        RequestCode::Create => &["file_id", "filename", "mode"],
+        RequestCode::Read => &["file_id", "offset", "count"],
+        RequestCode::Write => &["file_id", "offset", "count", "data"],
+        RequestCode::Clunk | RequestCode::Remove | RequestCode::Stat => {
+            &["file_id"]
+        }
+        RequestCode::WStat => &["file_id", "stat"],
+    }
+}
+
+
+fn response_arg_names(code: &ResponseCode) -> &'static [&'static str]
+{
+    match *code {
+        ResponseCode::Auth | ResponseCode::Attach => &["file_id"],
+        ResponseCode::Walk => &["file_ids"],
+        ResponseCode::Open | ResponseCode::Create => &["file_id", "max_size"],
+        ResponseCode::Read => &["count", "data"],
+        ResponseCode::Write => &["count"],
+        ResponseCode::Stat => &["stat"],
+        ResponseCode::Flush |
+        ResponseCode::Clunk |
+        ResponseCode::Remove |
+        ResponseCode::WStat => &[],
+    }
+}
+
+
+// Render args as a comma separated list of name=value pairs. Any args
+// without a name are rendered as a bare value.
+fn describe_args(names: &[&str], args: &[Value]) -> String
+{
+    let args: Vec<String> = args.iter()
+        .enumerate()
+        .map(|(i, arg)| match names.get(i) {
+            Some(name) => format!("{}={}", name, arg),
+            None => format!("{}", arg),
+        })
+        .collect();
+    args.join(", ")
+}
+
+
+// ===========================================================================
+// Describe
+// ===========================================================================
+
+
+/// Render a request and its response as a single line for debugging.
+///
+/// The output looks like `Read(file_id=1, offset=0, count=2) ->
+/// Read(count=2, data=[1, 2])`.
+pub fn describe_exchange(req: &Request, resp: &Response) -> String
+{
+    let reqcode = req.message_method();
+    let reqargs =
+        describe_args(request_arg_names(&reqcode), req.message_args());
+
+    // A response's result holds its args: Nil if there are no args, the
+    // arg itself if there is a single arg, and an array of args otherwise.
+    let respcode = resp.error_code();
+    let result = resp.result();
+    let respargs = match expected_response_args(respcode.clone()) {
+        0 => vec![],
+        1 => vec![result.clone()],
+        _ => match result.as_array() {
+            Some(a) => a.clone(),
+            None => vec![result.clone()],
+        },
+    };
+    let respargs = describe_args(response_arg_names(&respcode), &respargs);
+
+    format!(
+        "{}({}) -> {}({})",
+        reqcode.name(),
+        reqargs,
+        respcode.name(),
+        respargs
+    )
+}
+
+
+// ===========================================================================
+//
+// ===========================================================================
@@ -8,8 +8,10 @@
 // ===========================================================================
 
 
+mod describe;
 mod requestbuilder;
 mod responsebuilder;
+mod spec;
 mod util;
 
 
@@ -24,15 +26,32 @@ mod util;
 
 // Local imports
 
-use core::{CodeConvert, CodeValueError};
+use core::{CodeConvert, CodeValueError, MessageType};
 use core::request::RequestMessage;
 use core::response::ResponseMessage;
+use message::ProtocolSet;
 
 // Re-exports
-pub use self::requestbuilder::{request, BuildRequestError, RequestBuilder};
-pub use self::responsebuilder::{response, BuildResponseError, ResponseBuilder};
-pub use self::util::{openmode, FileID, FileKind, OpenFlag, OpenKind, OpenMode,
-                     OpenModeError};
+pub use self::describe::describe_exchange;
+pub use self::requestbuilder::{fixed_overhead, request, request_with_policy,
+                               require_distinct_ids, typed_request,
+                               ArgReason, BuildCreateError, BuildReadError,
+                               BuildRequestError, BuildWalkError,
+                               CheckNameError, CredentialsError,
+                               ProtocolRequest, RequestBuilder,
+                               TypedAuthBuilder, TypedAuthUserBuilder,
+                               TypedRequestBuilder};
+pub use self::responsebuilder::{response, AsAttachError, BuildResponseError,
+                                ProtocolResponse, ReadPartsError,
+                                ResponseBuilder};
+pub use self::spec::{expected_request_args, expected_response_args,
+                     file_id_arg_positions, legal_responses,
+                     response_code_for, validate_batch, validate_response,
+                     ArityError, ArityMode, ResponseValidationError,
+                     ShapeError, SpecError};
+pub use self::util::{can_open, openmode, FileID, FileIDError, FileKind,
+                     FileKindError, FileMode, FileModeError, OpenError,
+                     OpenFlag, OpenKind, OpenMode, OpenModeError};
 
 
 // ===========================================================================
@@ -173,6 +192,10 @@ pub enum ResponseCode
     // 1. Unique server identifier for the opened file
     // 2. Maximum number of bytes guaranteed to be read from or written to the
     //    file without a separate message. May be 0 which means no limit.
+    //
+    // An optional 3rd argument may be appended:
+    // 3. True if the client was granted exclusive access to the file. Absent
+    //    means false.
     Open = 13,
 
     // Create request succeeded
@@ -219,6 +242,25 @@ pub enum ResponseCode
 }
 
 
+// ===========================================================================
+// Protocol set
+// ===========================================================================
+
+
+impl ProtocolSet for RequestCode
+{
+    const VERSION: u32 = 1;
+    const CATEGORY: u8 = MessageType::Request as u8;
+}
+
+
+impl ProtocolSet for ResponseCode
+{
+    const VERSION: u32 = 1;
+    const CATEGORY: u8 = MessageType::Response as u8;
+}
+
+
 // ===========================================================================
 // New types
 // ===========================================================================
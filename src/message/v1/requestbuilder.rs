@@ -10,16 +10,20 @@
 
 // Stdlib imports
 
+use std::fmt;
+
 // Third-party imports
 
 use rmpv::Value;
 
 // Local imports
 
+use core::AsBytes;
+use core::request::{IDPolicy, ReservedIDError, RpcRequest};
 use util::is_printable;
 
 // Parent-module imports
-use super::{OpenMode, Request, RequestCode};
+use super::{OpenKind, OpenMode, Request, RequestCode};
 
 
 // ===========================================================================
@@ -27,16 +31,48 @@ use super::{OpenMode, Request, RequestCode};
 // ===========================================================================
 
 
-#[derive(Debug, Fail)]
-pub enum CheckNameError
+// Why a name arg was rejected
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArgReason
 {
-    #[fail(display = "{} is either empty, or contains control characters", _0)]
-    WSPrintable(String),
+    // Empty or contains control characters
+    WSPrintable,
 
-    #[fail(display = "{} is either empty, contains whitespace, or contains \
-                      control characters",
-           _0)]
-    WSNotPrintable(String),
+    // Empty, contains whitespace, or contains control characters
+    WSNotPrintable,
+
+    // Contains a path separator
+    PathSeparator,
+}
+
+
+impl fmt::Display for ArgReason
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        let msg = match *self {
+            ArgReason::WSPrintable => {
+                "either empty, or contains control characters"
+            }
+            ArgReason::WSNotPrintable => {
+                "either empty, contains whitespace, or contains control \
+                 characters"
+            }
+            ArgReason::PathSeparator => {
+                "not allowed to contain a path separator"
+            }
+        };
+        f.write_str(msg)
+    }
+}
+
+
+#[derive(Debug, Fail)]
+#[fail(display = "{} is {}", field, reason)]
+pub struct CheckNameError
+{
+    pub field: String,
+    pub reason: ArgReason,
 }
 
 
@@ -46,10 +82,34 @@ fn check_name(
 {
     // Name must not be empty and must not have any control characters
     if !is_printable(name, ws_printable) {
-        let err = if ws_printable {
-            CheckNameError::WSPrintable(var.to_owned())
+        let reason = if ws_printable {
+            ArgReason::WSPrintable
         } else {
-            CheckNameError::WSNotPrintable(var.to_owned())
+            ArgReason::WSNotPrintable
+        };
+        let err = CheckNameError {
+            field: var.to_owned(),
+            reason: reason,
+        };
+        return Err(err);
+    }
+
+    Ok(())
+}
+
+
+// Like check_name() but also reject names containing a path separator.
+//
+// Used for names of files within a directory, where a separator would refer
+// to some other directory.
+fn check_filename(var: &str, name: &str) -> Result<(), CheckNameError>
+{
+    check_name(var, name, false)?;
+
+    if name.contains(&['/', '\\'][..]) {
+        let err = CheckNameError {
+            field: var.to_owned(),
+            reason: ArgReason::PathSeparator,
         };
         return Err(err);
     }
@@ -58,6 +118,29 @@ fn check_name(
 }
 
 
+// Confirm that no file id appears more than once in ids.
+//
+// On error, the positions of the first pair of matching ids are returned
+// along with the id itself. The attach and walk builders map this error into
+// their own MatchingID errors.
+pub fn require_distinct_ids(ids: &[u32]) -> Result<(), BuildRequestError>
+{
+    for (first, id) in ids.iter().enumerate() {
+        let rest = &ids[first + 1..];
+        if let Some(n) = rest.iter().position(|other| other == id) {
+            let err = BuildRequestError::DuplicateID {
+                first: first,
+                second: first + 1 + n,
+                id: *id,
+            };
+            return Err(err);
+        }
+    }
+
+    Ok(())
+}
+
+
 // ===========================================================================
 // Request builder errors
 // ===========================================================================
@@ -75,6 +158,52 @@ pub enum BuildAttachError
 }
 
 
+#[derive(Debug, Fail)]
+pub enum BuildWalkError
+{
+    #[fail(display = "Invalid newfile_id value ({}): newfile_id matches \
+                      file_id",
+           _0)]
+    MatchingID(u32),
+
+    #[fail(display = "Invalid path element {} ({:?}): relative path \
+                      elements are not allowed",
+           index, element)]
+    RelativePath
+    {
+        index: usize, element: String
+    },
+}
+
+
+#[derive(Debug, Fail)]
+pub enum BuildCreateError
+{
+    #[fail(display = "{}", _0)] NameError(#[cause] CheckNameError),
+
+    #[fail(display = "Invalid mode ({:?}): a created file must be opened for \
+                      writing",
+           _0)]
+    ReadOnly(OpenKind),
+}
+
+
+#[derive(Debug, Fail)]
+pub enum BuildReadError
+{
+    #[fail(display = "Invalid count value (0): read would return no data")]
+    ZeroCount,
+
+    #[fail(display = "Invalid read range: offset ({}) + count ({}) overflows \
+                      u64",
+           offset, count)]
+    Overflow
+    {
+        offset: u64, count: u32
+    },
+}
+
+
 #[derive(Debug, Fail)]
 pub enum BuildRequestError
 {
@@ -89,18 +218,39 @@ pub enum BuildRequestError
     #[fail(display = "Unable to build attach request message")]
     Attach(#[cause] BuildAttachError),
 
-    #[fail(display = "Unable to build walk request message: newfile_id ({}) \
-                      has the same value as file_id",
-           _0)]
-    Walk(u32),
+    #[fail(display = "Unable to build walk request message")]
+    Walk(#[cause] BuildWalkError),
 
     #[fail(display = "Unable to build create request message")]
-    Create(#[cause] CheckNameError),
+    Create(#[cause] BuildCreateError),
+
+    #[fail(display = "Unable to build read request message")]
+    Read(#[cause] BuildReadError),
 
     #[fail(display = "Unable to build create request message: bytes to write \
                       ({}) does not match write count ({})",
            _0, _1)]
     Write(u32, usize),
+
+    #[fail(display = "Duplicate file id ({}): args {} and {} must be distinct",
+           id, first, second)]
+    DuplicateID
+    {
+        first: usize, second: usize, id: u32
+    },
+}
+
+
+#[derive(Debug, Fail)]
+pub enum CredentialsError
+{
+    #[fail(display = "Expected an auth request, got {:?} request", _0)]
+    WrongCode(RequestCode),
+
+    #[fail(display = "Invalid {} argument: expected a string", _0)]
+    NotString(String),
+
+    #[fail(display = "{}", _0)] NameError(#[cause] CheckNameError),
 }
 
 
@@ -112,6 +262,7 @@ pub enum BuildRequestError
 pub struct RequestBuilder
 {
     id: u32,
+    relative_path: bool,
 }
 
 
@@ -119,7 +270,22 @@ impl RequestBuilder
 {
     pub fn new(msgid: u32) -> RequestBuilder
     {
-        RequestBuilder { id: msgid }
+        RequestBuilder {
+            id: msgid,
+            relative_path: false,
+        }
+    }
+
+    // Allow walk paths to contain the relative path elements "." and "..".
+    //
+    // These are rejected by default since they make it possible to walk
+    // outside of a directory, and since the same file can then be reached
+    // by several different paths. Only servers that support relative
+    // navigation should allow them.
+    pub fn allow_relative_path(mut self) -> RequestBuilder
+    {
+        self.relative_path = true;
+        self
     }
 
     // Setup client authentication file.
@@ -178,10 +344,12 @@ impl RequestBuilder
         self, rootdir_id: u32, authfile_id: u32, username: &str, fsname: &str
     ) -> Result<Request, BuildRequestError>
     {
-        if rootdir_id == authfile_id {
-            let err = BuildAttachError::MatchingID(rootdir_id);
-            return Err(BuildRequestError::Attach(err));
-        }
+        require_distinct_ids(&[rootdir_id, authfile_id]).map_err(|e| match e {
+            BuildRequestError::DuplicateID { id, .. } => {
+                BuildRequestError::Attach(BuildAttachError::MatchingID(id))
+            }
+            e => e,
+        })?;
 
         check_name("username", username, false).map_err(|e| {
             BuildRequestError::Attach(BuildAttachError::NameError(e))
@@ -205,6 +373,9 @@ impl RequestBuilder
     //
     // Walk a directory hierarchy
     //
+    // Unless allow_relative_path() has been called, the path may not
+    // contain "." or "..".
+    //
     // 3 arguments:
     // 1. existing file id
     // 2. new file id of the walk result
@@ -214,8 +385,23 @@ impl RequestBuilder
     ) -> Result<Request, BuildRequestError>
     {
         // file_id cannot be the same value as newfile_id
-        if file_id == newfile_id {
-            return Err(BuildRequestError::Walk(newfile_id));
+        require_distinct_ids(&[file_id, newfile_id]).map_err(|e| match e {
+            BuildRequestError::DuplicateID { id, .. } => {
+                BuildRequestError::Walk(BuildWalkError::MatchingID(id))
+            }
+            e => e,
+        })?;
+
+        // Reject relative path elements unless explicitly allowed
+        if !self.relative_path {
+            let relative = path.iter().position(|p| *p == "." || *p == "..");
+            if let Some(n) = relative {
+                let err = BuildWalkError::RelativePath {
+                    index: n,
+                    element: path[n].to_owned(),
+                };
+                return Err(BuildRequestError::Walk(err));
+            }
         }
 
         // Convert Vec<&str> into Vec<Value>
@@ -250,6 +436,9 @@ impl RequestBuilder
 
     // Create a file and open it for I/O
     //
+    // The mode must allow writing to the new file, and the filename must
+    // not contain a path separator.
+    //
     // 3 arguments:
     // 1. existing file id
     // 2. name of the new file
@@ -258,8 +447,18 @@ impl RequestBuilder
         self, file_id: u32, filename: &str, mode: OpenMode
     ) -> Result<Request, BuildRequestError>
     {
-        check_name("filename", filename, false)
-            .map_err(|e| BuildRequestError::Create(e))?;
+        check_filename("filename", filename).map_err(|e| {
+            BuildRequestError::Create(BuildCreateError::NameError(e))
+        })?;
+
+        // The new file must be opened with write access
+        match mode.kind() {
+            OpenKind::Write | OpenKind::ReadWrite => {}
+            kind => {
+                let err = BuildCreateError::ReadOnly(kind);
+                return Err(BuildRequestError::Create(err));
+            }
+        }
 
         // Construct msg args
         let msgargs = vec![
@@ -290,6 +489,42 @@ impl RequestBuilder
         Request::new(self.id, RequestCode::Read, msgargs)
     }
 
+    // Request for a number of bytes from a file, rejecting a zero count
+    //
+    // Same arguments as read(). A count of 0 is not illegal, but since it
+    // always returns no data it is almost always a bug in the caller.
+    pub fn read_nonzero(
+        self, file_id: u32, offset: u64, count: u32
+    ) -> Result<Request, BuildRequestError>
+    {
+        if count == 0 {
+            let err = BuildReadError::ZeroCount;
+            return Err(BuildRequestError::Read(err));
+        }
+
+        Ok(self.read(file_id, offset, count))
+    }
+
+    // Request for a number of bytes from a file, rejecting a range that
+    // cannot be represented
+    //
+    // Same arguments as read(). The end position of the read, ie offset +
+    // count, must not overflow a u64.
+    pub fn read_checked(
+        self, file_id: u32, offset: u64, count: u32
+    ) -> Result<Request, BuildRequestError>
+    {
+        if offset.checked_add(count as u64).is_none() {
+            let err = BuildReadError::Overflow {
+                offset: offset,
+                count: count,
+            };
+            return Err(BuildRequestError::Read(err));
+        }
+
+        Ok(self.read(file_id, offset, count))
+    }
+
     // Request that a number of bytes be recorded to a file
     //
     // 4 arguments:
@@ -359,6 +594,196 @@ pub fn request(msgid: u32) -> RequestBuilder
 }
 
 
+// Same as request(), except that a message id reserved by the policy is
+// returned as an error
+pub fn request_with_policy(msgid: u32, policy: &IDPolicy)
+    -> Result<RequestBuilder, ReservedIDError>
+{
+    policy.check(msgid)?;
+    Ok(RequestBuilder::new(msgid))
+}
+
+
+// ===========================================================================
+// Typed request builder
+// ===========================================================================
+
+
+// Builder that takes a request's args one at a time, validating each arg as
+// soon as it is given.
+//
+// Each step returns the builder for the next arg, so a request cannot be
+// built until all of its args have been given, and an invalid arg is
+// reported before any later args are built, eg
+//
+//     let req = typed_request(42).auth(1).username(user)?.fsname(fs)?;
+pub struct TypedRequestBuilder
+{
+    id: u32,
+}
+
+
+impl TypedRequestBuilder
+{
+    pub fn new(msgid: u32) -> TypedRequestBuilder
+    {
+        TypedRequestBuilder { id: msgid }
+    }
+
+    // Start an auth request for the given auth file id
+    pub fn auth(self, authfile_id: u32) -> TypedAuthBuilder
+    {
+        TypedAuthBuilder {
+            id: self.id,
+            authfile_id: authfile_id,
+        }
+    }
+}
+
+
+pub fn typed_request(msgid: u32) -> TypedRequestBuilder
+{
+    TypedRequestBuilder::new(msgid)
+}
+
+
+// Auth request waiting for its user name
+pub struct TypedAuthBuilder
+{
+    id: u32,
+    authfile_id: u32,
+}
+
+
+impl TypedAuthBuilder
+{
+    // Set the user name, validated the same way as RequestBuilder::auth()
+    pub fn username(
+        self, username: &str
+    ) -> Result<TypedAuthUserBuilder, BuildRequestError>
+    {
+        check_name("username", username, false)
+            .map_err(|e| BuildRequestError::Auth(e))?;
+
+        let ret = TypedAuthUserBuilder {
+            id: self.id,
+            authfile_id: self.authfile_id,
+            username: username.to_owned(),
+        };
+        Ok(ret)
+    }
+}
+
+
+// Auth request waiting for its service name
+pub struct TypedAuthUserBuilder
+{
+    id: u32,
+    authfile_id: u32,
+    username: String,
+}
+
+
+impl TypedAuthUserBuilder
+{
+    // Set the service name, validated the same way as RequestBuilder::auth(),
+    // and build the request
+    pub fn fsname(self, fsname: &str) -> Result<Request, BuildRequestError>
+    {
+        check_name("filesystem name", fsname, false)
+            .map_err(|e| BuildRequestError::Auth(e))?;
+
+        let msgargs = vec![
+            Value::from(self.authfile_id),
+            Value::from(self.username),
+            Value::from(fsname),
+        ];
+        let ret = Request::new(self.id, RequestCode::Auth, msgargs);
+        Ok(ret)
+    }
+}
+
+
+// ===========================================================================
+// Request size
+// ===========================================================================
+
+
+// Size of the largest msgpack binary header (bin 32): marker plus a u32
+// length
+const MAX_BIN_HEADER_LEN: usize = 5;
+
+
+// Return the number of bytes a request takes up besides its binary payload.
+//
+// The request is made up of msgid, code, the fixed args, and a trailing
+// binary payload, eg the data of a Write request. Since the size of the
+// payload's binary header depends on the payload's length, the header is
+// counted at its largest size. The result is exact for payloads longer
+// than 65535 bytes and is at most 3 bytes too large otherwise, so
+// subtracting it from a frame budget always leaves room for the payload.
+pub fn fixed_overhead(msgid: u32, code: RequestCode, fixed_args: &[Value])
+    -> usize
+{
+    // Nil stands in for the payload; it is encoded as a single byte
+    let mut msgargs = fixed_args.to_vec();
+    msgargs.push(Value::Nil);
+
+    let req = Request::new(msgid, code, msgargs);
+    req.as_bytes().len() - 1 + MAX_BIN_HEADER_LEN
+}
+
+
+// ===========================================================================
+// Request accessors
+// ===========================================================================
+
+
+pub trait ProtocolRequest
+{
+    // Return the (username, fsname) pair of an Auth request.
+    //
+    // Both names are held to the same rules enforced by
+    // RequestBuilder::auth(): they must not be empty and must not contain
+    // whitespace or control characters.
+    fn credentials(&self) -> Result<(&str, &str), CredentialsError>;
+}
+
+
+impl ProtocolRequest for Request
+{
+    fn credentials(&self) -> Result<(&str, &str), CredentialsError>
+    {
+        // The request must have a code of RequestCode::Auth
+        let code = self.message_method();
+        if code != RequestCode::Auth {
+            return Err(CredentialsError::WrongCode(code));
+        }
+
+        // Username and fsname are the 2nd and 3rd args
+        let args = self.message_args();
+        let username = name_arg(args, 1, "username")?;
+        let fsname = name_arg(args, 2, "filesystem name")?;
+        Ok((username, fsname))
+    }
+}
+
+
+// Private helper that gets a name arg, validating it the same way
+// RequestBuilder does
+fn name_arg<'a>(
+    args: &'a [Value], index: usize, var: &str
+) -> Result<&'a str, CredentialsError>
+{
+    let name = args.get(index)
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| CredentialsError::NotString(var.to_owned()))?;
+    check_name(var, name, false)
+        .map_err(|e| CredentialsError::NameError(e))?;
+    Ok(name)
+}
+
+
 // ===========================================================================
 //
 // ===========================================================================
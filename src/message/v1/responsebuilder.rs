@@ -16,11 +16,14 @@ use rmpv::Value;
 
 // Local imports
 
+use core::{MessageType, RpcMessage};
 use core::request::RpcRequest;
 use core::response::RpcResponse;
 
 // Parent-module imports
-use super::{FileID, FileKind, Request, RequestCode, Response, ResponseCode};
+use super::{FileID, FileIDError, FileKind, Request, RequestCode, Response,
+            ResponseCode};
+
 
 // ===========================================================================
 // Errors
@@ -46,6 +49,11 @@ pub enum BuildResponseError
         expected: RequestCode,
     },
 
+    #[fail(display = "Unable to build response message: expected a Request \
+                      message, got a {:?} message instead",
+           _0)]
+    NotRequest(MessageType),
+
     #[fail(display = "Unable to build auth response message: file id has \
                       invalid kind {}",
            _0)]
@@ -64,6 +72,11 @@ pub enum BuildResponseError
         index: usize, kind: u8
     },
 
+    #[fail(display = "Unable to build walk response message: request walks \
+                      {} path elements but path_id is empty",
+           _0)]
+    WalkEmpty(usize),
+
     #[fail(display = "Unable to build open response message: file \
                       id has invalid kind {}",
            _0)]
@@ -81,6 +94,36 @@ pub enum BuildResponseError
 }
 
 
+#[derive(Debug, Fail)]
+pub enum ReadPartsError
+{
+    #[fail(display = "Expected a read response, got {:?} response", _0)]
+    WrongCode(ResponseCode),
+
+    #[fail(display = "Read response result is not a [count, bytes] array")]
+    Malformed,
+
+    #[fail(display = "Read response count ({}) does not match number of \
+                      bytes ({})",
+           _0, _1)]
+    CountMismatch(u32, usize),
+}
+
+
+#[derive(Debug, Fail)]
+pub enum AsAttachError
+{
+    #[fail(display = "Expected an attach response, got {:?} response", _0)]
+    WrongCode(ResponseCode),
+
+    #[fail(display = "Invalid root directory file id")]
+    FileID(#[cause] FileIDError),
+
+    #[fail(display = "Root directory file id has invalid kind {}", _0)]
+    InvalidKind(u8),
+}
+
+
 impl BuildResponseError
 {
     fn from_opencreate(tag: &OpenOrCreate, val: u8) -> BuildResponseError
@@ -101,6 +144,17 @@ impl BuildResponseError
 pub trait ProtocolResponse
 {
     fn as_fileid(&self) -> Option<FileID>;
+
+    // Return true if the response is an Open response that was granted
+    // exclusive access. Defaults to false if the flag is absent.
+    fn as_open_exclusive(&self) -> bool;
+
+    // Return the count and a borrowed slice of the data of a Read response,
+    // checking that the count matches the number of bytes.
+    fn read_parts(&self) -> Result<(u32, &[u8]), ReadPartsError>;
+
+    // Return the root directory file id of an Attach response.
+    fn as_attach(&self) -> Result<FileID, AsAttachError>;
 }
 
 
@@ -114,37 +168,72 @@ impl ProtocolResponse for Response
             _ => return None,
         }
 
-        // The result must be an array containing 3 items
+        // The result must be a valid file id
+        FileID::from_value(self.result()).ok()
+    }
+
+    fn as_open_exclusive(&self) -> bool
+    {
+        // The response must have a code of ResponseCode::Open
+        match self.error_code() {
+            ResponseCode::Open => {}
+            _ => return false,
+        }
+
+        // The optional exclusive flag is the 3rd item of the result
+        self.result()
+            .as_array()
+            .and_then(|r| r.get(2))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    }
+
+    fn read_parts(&self) -> Result<(u32, &[u8]), ReadPartsError>
+    {
+        // The response must have a code of ResponseCode::Read
+        let code = self.error_code();
+        if code != ResponseCode::Read {
+            return Err(ReadPartsError::WrongCode(code));
+        }
+
+        // The result must be a 2 item array of a u32 count and binary data
         let result = match self.result().as_array() {
-            Some(val) if val.len() == 3 => val,
-            _ => return None,
+            Some(r) if r.len() == 2 => r,
+            _ => return Err(ReadPartsError::Malformed),
         };
-
-        // Convert bits into FileKind
-        let kind = match result[0].as_u64() {
-            Some(v) if v <= u8::max_value() as u64 => {
-                match FileKind::from_bits(v as u8) {
-                    Some(kind) => kind,
-                    None => return None,
-                }
-            }
-            _ => return None,
+        let count = match result[0].as_u64() {
+            Some(c) if c <= u64::from(u32::max_value()) => c as u32,
+            _ => return Err(ReadPartsError::Malformed),
         };
-
-        // Ensure version is a u32
-        let version = match result[1].as_u64() {
-            Some(v) if v <= u32::max_value() as u64 => v as u32,
-            _ => return None,
+        let data = match result[1] {
+            Value::Binary(ref d) => &d[..],
+            _ => return Err(ReadPartsError::Malformed),
         };
 
-        // Ensure path is a u64
-        let path = match result[2].as_u64() {
-            Some(v) => v,
-            None => return None,
-        };
+        // The count must match the number of bytes
+        if count as usize != data.len() {
+            return Err(ReadPartsError::CountMismatch(count, data.len()));
+        }
+
+        Ok((count, data))
+    }
+
+    fn as_attach(&self) -> Result<FileID, AsAttachError>
+    {
+        // The response must have a code of ResponseCode::Attach
+        let code = self.error_code();
+        if code != ResponseCode::Attach {
+            return Err(AsAttachError::WrongCode(code));
+        }
+
+        // The result must be a file id with a valid kind
+        let fileid = FileID::from_value(self.result())
+            .map_err(|e| AsAttachError::FileID(e))?;
+        if !fileid.is_valid() {
+            return Err(AsAttachError::InvalidKind(fileid.kind.bits()));
+        }
 
-        // Create a FileID
-        Some(FileID::new(kind, version, path))
+        Ok(fileid)
     }
 }
 
@@ -182,10 +271,18 @@ impl<'request> ResponseBuilder<'request>
     }
 
     // Private helper that validates that a request's method is as expected
+    //
+    // The request's type is checked first: only requests are answered with a
+    // response, so anything else, eg a notification, is a logic error.
     fn check_request_method(
         &self, expected: RequestCode
     ) -> Result<(), BuildResponseError>
     {
+        let msgtype = self.request.message_type();
+        if msgtype != MessageType::Request {
+            return Err(BuildResponseError::NotRequest(msgtype));
+        }
+
         let code = self.request.message_method();
         if code != expected {
             let err = BuildResponseError::WrongCode {
@@ -207,8 +304,8 @@ impl<'request> ResponseBuilder<'request>
         // Make sure request message's code is RequestCode::Auth
         self.check_request_method(RequestCode::Auth)?;
 
-        // Make sure given FileID is valid
-        if !id.is_valid() {
+        // Make sure given FileID is a valid auth file
+        if !id.is_valid() || !id.kind.contains(FileKind::AUTH) {
             return Err(BuildResponseError::Auth(id.kind.bits()));
         }
 
@@ -270,18 +367,49 @@ impl<'request> ResponseBuilder<'request>
         Ok(ret)
     }
 
+    // Same as attach(), but the root directory's file id must also have the
+    // DIR kind
+    pub fn attach_dir(
+        self, rootdir_id: FileID
+    ) -> Result<Response, BuildResponseError>
+    {
+        // Make sure request message's code is RequestCode::Attach
+        self.check_request_method(RequestCode::Attach)?;
+
+        // Make sure given FileID is a directory
+        if !rootdir_id.kind.contains(FileKind::DIR) {
+            return Err(BuildResponseError::Attach(rootdir_id.kind.bits()));
+        }
+
+        self.attach(rootdir_id)
+    }
+
     // Walk request succeded
     //
     // Single argument:
     // 1. List of unique server identifiers for each path element specified in
     //    the request
+    //
+    // A walk of an empty path clones the request's file id, and so has an
+    // empty path_id. A walk of a non-empty path must resolve at least one
+    // path element.
     pub fn walk(
-        self, path_id: &Vec<FileID>
+        self, path_id: &[FileID]
     ) -> Result<Response, BuildResponseError>
     {
         // Make sure request message's code is RequestCode::Walk
         self.check_request_method(RequestCode::Walk)?;
 
+        // Only an empty path may have no file ids
+        let reqpath_len = self.request
+            .message_args()
+            .get(2)
+            .and_then(|p| p.as_array())
+            .map_or(0, |p| p.len());
+        if path_id.is_empty() && reqpath_len > 0 {
+            return Err(BuildResponseError::WalkEmpty(reqpath_len));
+        }
+
         // Setup result vec
         let mut result: Vec<Value> = Vec::with_capacity(path_id.len());
 
@@ -313,14 +441,40 @@ impl<'request> ResponseBuilder<'request>
         Ok(ret)
     }
 
+    // Same as walk(), but every file id in path_id must also have the DIR
+    // kind, ie the walk passes through and ends at directories
+    pub fn walk_dir(
+        self, path_id: &[FileID]
+    ) -> Result<Response, BuildResponseError>
+    {
+        // Make sure request message's code is RequestCode::Walk
+        self.check_request_method(RequestCode::Walk)?;
+
+        // Make sure all FileID objects in path_id are directories
+        let notdir = path_id
+            .iter()
+            .position(|fid| !fid.kind.contains(FileKind::DIR));
+        if let Some(n) = notdir {
+            return Err(BuildResponseError::Walk {
+                index: n,
+                kind: path_id[n].kind.bits(),
+            });
+        }
+
+        self.walk(path_id)
+    }
+
     // Open or create request succeeded
     //
     // 2 arguments:
     // 1. Unique server identifier for the opened file
     // 2. Maximum number of bytes guaranteed to be read from or written to the
     //    file without a separate message. May be 0 which means no limit.
+    //
+    // If exclusive is given, it is appended as a 3rd argument.
     fn open_or_create(
-        self, tag: OpenOrCreate, file_id: FileID, max_size: u32
+        self, tag: OpenOrCreate, file_id: FileID, max_size: u32,
+        exclusive: Option<bool>,
     ) -> Result<Response, BuildResponseError>
     {
         // Make sure request message's code matches tag
@@ -340,7 +494,10 @@ impl<'request> ResponseBuilder<'request>
             Value::from(file_id.path),
         ];
 
-        let result = vec![Value::Array(fileid), Value::from(max_size)];
+        let mut result = vec![Value::Array(fileid), Value::from(max_size)];
+        if let Some(exclusive) = exclusive {
+            result.push(Value::from(exclusive));
+        }
 
         // Create response message
         let msgid = self.request.message_id();
@@ -358,7 +515,22 @@ impl<'request> ResponseBuilder<'request>
         self, file_id: FileID, max_size: u32
     ) -> Result<Response, BuildResponseError>
     {
-        self.open_or_create(OpenOrCreate::Open, file_id, max_size)
+        self.open_or_create(OpenOrCreate::Open, file_id, max_size, None)
+    }
+
+    // Open request succeeded, recording whether exclusive access was granted
+    //
+    // 3 arguments:
+    // 1. Unique server identifier for the opened file
+    // 2. Maximum number of bytes guaranteed to be read from or written to the
+    //    file without a separate message. May be 0 which means no limit.
+    // 3. True if the client was granted exclusive access to the file
+    pub fn open_exclusive(
+        self, file_id: FileID, max_size: u32, exclusive: bool
+    ) -> Result<Response, BuildResponseError>
+    {
+        let tag = OpenOrCreate::Open;
+        self.open_or_create(tag, file_id, max_size, Some(exclusive))
     }
 
     // Create request succeeded
@@ -371,7 +543,7 @@ impl<'request> ResponseBuilder<'request>
         self, file_id: FileID, max_size: u32
     ) -> Result<Response, BuildResponseError>
     {
-        self.open_or_create(OpenOrCreate::Create, file_id, max_size)
+        self.open_or_create(OpenOrCreate::Create, file_id, max_size, None)
     }
 
     // Read request succeeded
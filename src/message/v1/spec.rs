@@ -0,0 +1,586 @@
+// src/message/v1/spec.rs
+// Copyright (C) 2017 authors and contributors (see AUTHORS file)
+//
+// This file is released under the MIT License.
+
+// ===========================================================================
+// Imports
+// ===========================================================================
+
+
+// Stdlib imports
+
+// Third-party imports
+
+use rmpv::Value;
+
+// Local imports
+
+use core::{check_int, value_type, CheckIntError, CodeConvert};
+use core::request::RpcRequest;
+use core::response::RpcResponse;
+use message;
+
+// Parent-module imports
+use super::{request, BuildRequestError, OpenMode, OpenModeError, Request,
+            RequestCode, Response, ResponseCode};
+
+
+// ===========================================================================
+// Argument counts
+// ===========================================================================
+
+
+// Number of arguments a request message of the given code must contain.
+//
+// These are the counts documented on each RequestCode variant.
+pub fn expected_request_args(code: RequestCode) -> usize
+{
+    match code {
+        RequestCode::Auth => 3,
+        RequestCode::Flush => 1,
+        RequestCode::Attach => 4,
+        RequestCode::Walk => 3,
+        RequestCode::Open => 2,
+        RequestCode::Create => 3,
+        RequestCode::Read => 3,
+        RequestCode::Write => 4,
+        RequestCode::Clunk => 1,
+        RequestCode::Remove => 1,
+        RequestCode::Stat => 1,
+        RequestCode::WStat => 2,
+    }
+}
+
+
+// Number of arguments a response message of the given code must contain.
+//
+// These are the counts documented on each ResponseCode variant.
+pub fn expected_response_args(code: ResponseCode) -> usize
+{
+    match code {
+        ResponseCode::Auth => 1,
+        ResponseCode::Flush => 0,
+        ResponseCode::Attach => 1,
+        ResponseCode::Walk => 1,
+        ResponseCode::Open => 2,
+        ResponseCode::Create => 2,
+        ResponseCode::Read => 2,
+        ResponseCode::Write => 1,
+        ResponseCode::Clunk => 0,
+        ResponseCode::Remove => 0,
+        ResponseCode::Stat => 1,
+        ResponseCode::WStat => 0,
+    }
+}
+
+
+// ===========================================================================
+// Response codes
+// ===========================================================================
+
+
+// Code of the response that answers a request of the given code.
+//
+// Every response code is one more than the code of the request it answers.
+pub fn response_code_for(code: RequestCode) -> ResponseCode
+{
+    ResponseCode::from_u64(code.to_u64() + 1)
+        .expect("every request code has a response code")
+}
+
+
+// Codes of every response that may legally answer a request of the given
+// code.
+//
+// Besides the response code matching the request, any request may be
+// answered with the session Error response. Codes are returned as numbers
+// since the Error code is not a v1 response code.
+pub fn legal_responses(code: RequestCode) -> Vec<u64>
+{
+    vec![
+        response_code_for(code).to_u64(),
+        message::ResponseCode::Error.to_u64(),
+    ]
+}
+
+
+// ===========================================================================
+// File id arguments
+// ===========================================================================
+
+
+// Positions of the args of a request message that hold client file ids.
+pub fn file_id_arg_positions(code: RequestCode) -> &'static [usize]
+{
+    match code {
+        RequestCode::Flush => &[],
+        RequestCode::Attach | RequestCode::Walk => &[0, 1],
+        RequestCode::Auth |
+        RequestCode::Open |
+        RequestCode::Create |
+        RequestCode::Read |
+        RequestCode::Write |
+        RequestCode::Clunk |
+        RequestCode::Remove |
+        RequestCode::Stat |
+        RequestCode::WStat => &[0],
+    }
+}
+
+
+// ===========================================================================
+// Arity validation
+// ===========================================================================
+
+
+#[derive(Debug, Fail)]
+#[fail(display = "Invalid number of arguments: expected {}, got {}", expected,
+       actual)]
+pub struct ArityError
+{
+    pub expected: usize,
+    pub actual: usize,
+}
+
+
+// How strictly the number of args of a message is checked
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ArityMode
+{
+    // The number of args must equal the expected count
+    Strict,
+
+    // The number of args must be at least the expected count. Extra trailing
+    // args are allowed for forward compatibility.
+    Lenient,
+}
+
+
+impl Request
+{
+    // Confirm the number of args matches the number required by the
+    // message's method code.
+    //
+    // This is the same as calling validate_arity_mode(ArityMode::Strict).
+    pub fn validate_arity(&self) -> Result<(), ArityError>
+    {
+        self.validate_arity_mode(ArityMode::Strict)
+    }
+
+    // Confirm the number of args is valid for the message's method code using
+    // the given mode.
+    pub fn validate_arity_mode(
+        &self, mode: ArityMode
+    ) -> Result<(), ArityError>
+    {
+        let expected = expected_request_args(self.message_method());
+        let actual = self.message_args().len();
+        let valid = match mode {
+            ArityMode::Strict => actual == expected,
+            ArityMode::Lenient => actual >= expected,
+        };
+        if !valid {
+            return Err(ArityError {
+                expected: expected,
+                actual: actual,
+            });
+        }
+        Ok(())
+    }
+}
+
+
+// ===========================================================================
+// Spec validation
+// ===========================================================================
+
+
+#[derive(Debug, Fail)]
+pub enum SpecError
+{
+    #[fail(display = "Request does not have the expected number of arguments")]
+    Arity(#[cause] ArityError),
+
+    #[fail(display = "Invalid argument {}: expected {} but got {}", index,
+           expected, actual)]
+    ArgType
+    {
+        index: usize, expected: String, actual: String
+    },
+
+    #[fail(display = "Invalid argument {}: integer out of range", index)]
+    ArgRange
+    {
+        index: usize,
+        #[cause]
+        err: CheckIntError,
+    },
+
+    #[fail(display = "Invalid open mode")]
+    InvalidMode(#[cause] OpenModeError),
+
+    #[fail(display = "Invalid argument {}: duplicate map key {}", index, key)]
+    DuplicateKey
+    {
+        index: usize, key: String
+    },
+
+    #[fail(display = "Request violates the v1 spec")]
+    Build(#[cause] BuildRequestError),
+}
+
+
+fn arg_type_error(args: &[Value], index: usize, expected: &str) -> SpecError
+{
+    SpecError::ArgType {
+        index: index,
+        expected: expected.to_owned(),
+        actual: value_type(&args[index]),
+    }
+}
+
+
+fn arg_int(
+    args: &[Value], index: usize, max_value: u64
+) -> Result<u64, SpecError>
+{
+    let arg = &args[index];
+    if !arg.is_u64() && !arg.is_i64() {
+        return Err(arg_type_error(args, index, "an integer"));
+    }
+    check_int(arg.as_u64(), max_value, "an unsigned integer".to_string())
+        .map_err(|e| SpecError::ArgRange { index: index, err: e })
+}
+
+
+fn arg_u32(args: &[Value], index: usize) -> Result<u32, SpecError>
+{
+    arg_int(args, index, u32::max_value() as u64).map(|v| v as u32)
+}
+
+
+fn arg_u64(args: &[Value], index: usize) -> Result<u64, SpecError>
+{
+    arg_int(args, index, u64::max_value())
+}
+
+
+fn arg_mode(args: &[Value], index: usize) -> Result<OpenMode, SpecError>
+{
+    let bits = arg_int(args, index, u8::max_value() as u64)? as u8;
+    OpenMode::from_bits(bits).map_err(|e| SpecError::InvalidMode(e))
+}
+
+
+fn arg_str(args: &[Value], index: usize) -> Result<&str, SpecError>
+{
+    args[index]
+        .as_str()
+        .ok_or_else(|| arg_type_error(args, index, "a string"))
+}
+
+
+// Confirm that no key appears more than once in the map arg at index.
+//
+// rmpv keeps every pair of a decoded map, so a map with a repeated key would
+// otherwise be read differently depending on which pair is looked at first.
+fn arg_unique_keys(args: &[Value], index: usize) -> Result<(), SpecError>
+{
+    let pairs = match args[index].as_map() {
+        Some(pairs) => pairs,
+        None => return Err(arg_type_error(args, index, "a map")),
+    };
+
+    for (i, pair) in pairs.iter().enumerate() {
+        if pairs[i + 1..].iter().any(|other| other.0 == pair.0) {
+            let key = match pair.0.as_str() {
+                Some(k) => k.to_owned(),
+                None => pair.0.to_string(),
+            };
+            return Err(SpecError::DuplicateKey {
+                index: index,
+                key: key,
+            });
+        }
+    }
+    Ok(())
+}
+
+
+impl Request
+{
+    // Run all v1 spec validations that apply to the request's method code,
+    // returning the first violation found.
+    //
+    // The following are checked:
+    // 1. the number of args is exactly the expected number
+    // 2. each arg has the expected type and fits into the expected integer
+    //    type
+    // 3. the args satisfy the same rules enforced by RequestBuilder, eg
+    //    names must be printable and file ids must not collide
+    // 4. map args do not repeat any key
+    pub fn validate_spec(&self) -> Result<(), SpecError>
+    {
+        self.validate_arity().map_err(|e| SpecError::Arity(e))?;
+
+        let msgid = self.message_id();
+        let args = &self.message_args()[..];
+        let result = match self.message_method() {
+            RequestCode::Auth => {
+                let (file_id, user, fs) =
+                    (arg_u32(args, 0)?, arg_str(args, 1)?, arg_str(args, 2)?);
+                request(msgid).auth(file_id, user, fs).map(|_| ())
+            }
+            RequestCode::Flush => {
+                request(msgid).flush(arg_u32(args, 0)?).map(|_| ())
+            }
+            RequestCode::Attach => {
+                let (rootdir_id, authfile_id) =
+                    (arg_u32(args, 0)?, arg_u32(args, 1)?);
+                let (user, fs) = (arg_str(args, 2)?, arg_str(args, 3)?);
+                request(msgid)
+                    .attach(rootdir_id, authfile_id, user, fs)
+                    .map(|_| ())
+            }
+            RequestCode::Walk => {
+                let (file_id, newfile_id) =
+                    (arg_u32(args, 0)?, arg_u32(args, 1)?);
+                let path = match args[2].as_array() {
+                    Some(p) => p,
+                    None => return Err(arg_type_error(args, 2, "an array")),
+                };
+                let path = path.iter()
+                    .map(|p| p.as_str())
+                    .collect::<Option<Vec<&str>>>()
+                    .ok_or_else(|| {
+                        arg_type_error(args, 2, "an array of strings")
+                    })?;
+                request(msgid).walk(file_id, newfile_id, path).map(|_| ())
+            }
+            RequestCode::Open => {
+                arg_u32(args, 0)?;
+                arg_mode(args, 1)?;
+                Ok(())
+            }
+            RequestCode::Create => {
+                let (file_id, filename) =
+                    (arg_u32(args, 0)?, arg_str(args, 1)?);
+                let mode = arg_mode(args, 2)?;
+                request(msgid).create(file_id, filename, mode).map(|_| ())
+            }
+            RequestCode::Read => {
+                arg_u32(args, 0)?;
+                arg_u64(args, 1)?;
+                arg_u32(args, 2)?;
+                Ok(())
+            }
+            RequestCode::Write => {
+                arg_u32(args, 0)?;
+                arg_u64(args, 1)?;
+                let count = arg_u32(args, 2)?;
+                let data = match args[3] {
+                    Value::Binary(ref d) => d,
+                    _ => return Err(arg_type_error(args, 3, "binary")),
+                };
+
+                // Avoid copying the data by not calling the builder
+                if count as u64 != data.len() as u64 {
+                    Err(BuildRequestError::Write(count, data.len()))
+                } else {
+                    Ok(())
+                }
+            }
+            RequestCode::Clunk | RequestCode::Remove | RequestCode::Stat => {
+                arg_u32(args, 0)?;
+                Ok(())
+            }
+            RequestCode::WStat => {
+                arg_u32(args, 0)?;
+                arg_unique_keys(args, 1)?;
+                Ok(())
+            }
+        };
+
+        result.map_err(|e| SpecError::Build(e))
+    }
+}
+
+
+// Run validate_spec() on every request of a batch, in order.
+//
+// This allows a pipelined batch to be rejected as a whole before any of its
+// requests are sent. The index of the first invalid request is returned
+// along with its error.
+pub fn validate_batch(reqs: &[Request]) -> Result<(), (usize, SpecError)>
+{
+    for (i, req) in reqs.iter().enumerate() {
+        req.validate_spec().map_err(|e| (i, e))?;
+    }
+    Ok(())
+}
+
+
+// ===========================================================================
+// Response result shape
+// ===========================================================================
+
+
+#[derive(Debug, Fail)]
+#[fail(display = "Invalid {:?} response result: expected {}, got {}", code,
+       expected, actual)]
+pub struct ShapeError
+{
+    pub code: ResponseCode,
+    pub expected: String,
+    pub actual: String,
+}
+
+
+// Return true if val is an array of 3 unsigned integers, ie a file id
+fn is_fileid(val: &Value) -> bool
+{
+    match val.as_array() {
+        Some(a) => a.len() == 3 && a.iter().all(|v| v.is_u64()),
+        None => false,
+    }
+}
+
+
+// Return true if val is a [file id, max size] array, optionally followed by
+// an exclusive flag
+fn is_open_result(val: &Value, allow_exclusive: bool) -> bool
+{
+    let a = match val.as_array() {
+        Some(a) => a,
+        None => return false,
+    };
+    let flag_ok = match a.len() {
+        2 => true,
+        3 => allow_exclusive && a[2].is_bool(),
+        _ => false,
+    };
+    flag_ok && is_fileid(&a[0]) && a[1].is_u64()
+}
+
+
+impl Response
+{
+    // Confirm the result has the shape documented for the response's code.
+    //
+    // Only the structure and value types are checked, eg a file id must be
+    // an array of 3 unsigned integers but its kind bits are not validated.
+    pub fn validate_result_shape(&self) -> Result<(), ShapeError>
+    {
+        let code = self.error_code();
+        let result = self.result();
+        let (valid, expected) = match code {
+            ResponseCode::Auth | ResponseCode::Attach => {
+                (is_fileid(result), "a file id")
+            }
+            ResponseCode::Walk => {
+                let valid = match result.as_array() {
+                    Some(a) => a.iter().all(is_fileid),
+                    None => false,
+                };
+                (valid, "an array of file ids")
+            }
+            ResponseCode::Open => (
+                is_open_result(result, true),
+                "a [file id, max size] array w/ an optional exclusive flag",
+            ),
+            ResponseCode::Create => {
+                (is_open_result(result, false), "a [file id, max size] array")
+            }
+            ResponseCode::Read => {
+                let valid = match result.as_array() {
+                    Some(a) if a.len() == 2 => match a[1] {
+                        Value::Binary(_) => a[0].is_u64(),
+                        _ => false,
+                    },
+                    _ => false,
+                };
+                (valid, "a [count, bytes] array")
+            }
+            ResponseCode::Write => (result.is_u64(), "a count"),
+            ResponseCode::Stat => (result.is_map(), "a map"),
+            ResponseCode::Flush |
+            ResponseCode::Clunk |
+            ResponseCode::Remove |
+            ResponseCode::WStat => (result.is_nil(), "nil"),
+        };
+
+        if !valid {
+            return Err(ShapeError {
+                code: code,
+                expected: expected.to_owned(),
+                actual: value_type(result),
+            });
+        }
+        Ok(())
+    }
+}
+
+
+// ===========================================================================
+// Response validation
+// ===========================================================================
+
+
+#[derive(Debug, Fail)]
+pub enum ResponseValidationError
+{
+    #[fail(display = "Response msg id ({}) does not match request msg id ({})",
+           actual, expected)]
+    IDMismatch
+    {
+        expected: u32, actual: u32
+    },
+
+    #[fail(display = "Expected {:?} response to {:?} request, got {:?}",
+           expected, request, actual)]
+    CodeMismatch
+    {
+        request: RequestCode,
+        expected: ResponseCode,
+        actual: ResponseCode,
+    },
+
+    #[fail(display = "Invalid response result")]
+    Shape(#[cause] ShapeError),
+}
+
+
+// Confirm that a response answers a request.
+//
+// The response must have the request's msg id, the response code matching
+// the request's code, and a result with the shape documented for that code.
+pub fn validate_response(
+    req: &Request, resp: &Response
+) -> Result<(), ResponseValidationError>
+{
+    if resp.message_id() != req.message_id() {
+        return Err(ResponseValidationError::IDMismatch {
+            expected: req.message_id(),
+            actual: resp.message_id(),
+        });
+    }
+
+    let code = req.message_method();
+    let expected = response_code_for(code.clone());
+    if resp.error_code() != expected {
+        return Err(ResponseValidationError::CodeMismatch {
+            request: code,
+            expected: expected,
+            actual: resp.error_code(),
+        });
+    }
+
+    resp.validate_result_shape()
+        .map_err(|e| ResponseValidationError::Shape(e))
+}
+
+
+// ===========================================================================
+//
+// ===========================================================================
@@ -12,9 +12,13 @@
 
 // Third-party imports
 
+#[cfg(unix)]
+use libc::{c_int, O_ACCMODE, O_RDONLY, O_RDWR, O_TRUNC, O_WRONLY};
+use rmpv::Value;
+
 // Local imports
 
-use core::{CodeConvert, CodeValueError};
+use core::{check_int, value_type, CheckIntError, CodeConvert, CodeValueError};
 
 // ===========================================================================
 // Server File ID
@@ -45,10 +49,68 @@ impl FileKind
         // Return false if any invalid bits are found in filekind
         !invalid.iter().any(|i| self.contains(*i))
     }
+
+    /// Convert bits decoded from a message into a valid FileKind.
+    ///
+    /// # Errors
+    ///
+    /// An `UnknownBits` error holding the unrecognized bits is returned if
+    /// any bit does not belong to a FileKind flag. Otherwise, an
+    /// `InvalidCombination` error is returned if the flags are not a valid
+    /// combination, eg DIR and AUTH.
+    pub fn validate(bits: u8) -> Result<FileKind, FileKindError>
+    {
+        let kind = match FileKind::from_bits(bits) {
+            Some(k) => k,
+            None => {
+                let unknown = bits & !FileKind::all().bits();
+                return Err(FileKindError::UnknownBits(unknown));
+            }
+        };
+
+        if !kind.is_valid() {
+            return Err(FileKindError::InvalidCombination(bits));
+        }
+        Ok(kind)
+    }
+}
+
+
+#[derive(Debug, Fail)]
+pub enum FileKindError
+{
+    #[fail(display = "Unknown file kind bits: {:b}", _0)]
+    UnknownBits(u8),
+
+    #[fail(display = "Invalid combination of file kind bits: {:b}", _0)]
+    InvalidCombination(u8),
+}
+
+
+#[derive(Debug, Fail)]
+pub enum FileIDError
+{
+    #[fail(display = "Expected array for file id but got {}", _0)]
+    NotArray(String),
+
+    #[fail(display = "Expected file id array length of 3, got {}", _0)]
+    ArrayLength(usize),
+
+    #[fail(display = "Invalid file id kind value")]
+    InvalidKind(#[cause] CheckIntError),
+
+    #[fail(display = "Unknown file id kind bits: {:b}", _0)]
+    UnknownKind(u8),
+
+    #[fail(display = "Invalid file id version")]
+    InvalidVersion(#[cause] CheckIntError),
+
+    #[fail(display = "Invalid file id path")]
+    InvalidPath(#[cause] CheckIntError),
 }
 
 
-#[derive(Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
 pub struct FileID
 {
     pub kind: FileKind,
@@ -68,6 +130,47 @@ impl FileID
         }
     }
 
+    /// Convert a [`rmpv::Value`] holding a `[kind, version, path]` array
+    /// into a FileID.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the value is not an array of 3 items, if the
+    /// kind is not a u8 made up of known FileKind bits, if the version does
+    /// not fit into a u32, or if the path does not fit into a u64.
+    ///
+    /// [`rmpv::Value`]: https://docs.rs/rmpv/0.4.0/rmpv/enum.Value.html
+    pub fn from_value(val: &Value) -> Result<FileID, FileIDError>
+    {
+        let array = match val.as_array() {
+            Some(a) if a.len() == 3 => a,
+            Some(a) => return Err(FileIDError::ArrayLength(a.len())),
+            None => return Err(FileIDError::NotArray(value_type(val))),
+        };
+
+        let kind = check_int(
+            array[0].as_u64(),
+            u8::max_value() as u64,
+            "u8".to_string(),
+        ).map_err(|e| FileIDError::InvalidKind(e))? as u8;
+        let kind = match FileKind::from_bits(kind) {
+            Some(k) => k,
+            None => return Err(FileIDError::UnknownKind(kind)),
+        };
+
+        let version = check_int(
+            array[1].as_u64(),
+            u32::max_value() as u64,
+            "u32".to_string(),
+        ).map_err(|e| FileIDError::InvalidVersion(e))? as u32;
+
+        let path =
+            check_int(array[2].as_u64(), u64::max_value(), "u64".to_string())
+                .map_err(|e| FileIDError::InvalidPath(e))?;
+
+        Ok(FileID::new(kind, version, path))
+    }
+
     pub fn is_valid(&self) -> bool
     {
         self.kind.is_valid()
@@ -128,6 +231,45 @@ impl OpenMode
     pub const INVALID_BITS: u8 = 0b00111100;
     const OPENKIND_BITS: u8 = 0b00000011;
 
+    /// Create an OpenMode from POSIX `open(2)` style flags.
+    ///
+    /// The flags are interpreted using the values of the platform the crate
+    /// is built for, since they differ between unix systems. This is only
+    /// available on unix.
+    ///
+    /// The access mode maps to the open kind: `O_RDONLY` to
+    /// `OpenKind::Read`, `O_WRONLY` to `OpenKind::Write`, and `O_RDWR` to
+    /// `OpenKind::ReadWrite`. `O_TRUNC` maps to `OpenFlag::OTRUNC`.
+    ///
+    /// Every other flag is ignored. Flags such as `O_CREAT` and `O_EXCL`
+    /// are expressed by sending a Create request instead of an Open request,
+    /// and the rest have no equivalent in this protocol.
+    ///
+    /// # Errors
+    ///
+    /// An error holding the access mode bits is returned if the access mode
+    /// is not one of `O_RDONLY`, `O_WRONLY`, or `O_RDWR`.
+    #[cfg(unix)]
+    pub fn from_posix(flags: c_int) -> Result<OpenMode, OpenModeError>
+    {
+        let kind = match flags & O_ACCMODE {
+            O_RDONLY => OpenKind::Read,
+            O_WRONLY => OpenKind::Write,
+            O_RDWR => OpenKind::ReadWrite,
+            accmode => {
+                return Err(OpenModeError {
+                    bits: accmode as u8,
+                })
+            }
+        };
+
+        let mut ret = openmode().kind(kind);
+        if flags & O_TRUNC != 0 {
+            ret = ret.flags(OpenFlag::OTRUNC);
+        }
+        Ok(ret.create())
+    }
+
     pub fn from_bits(bits: u8) -> Result<OpenMode, OpenModeError>
     {
         if OpenMode::INVALID_BITS & bits != 0 {
@@ -263,6 +405,115 @@ pub fn openmode() -> OpenModeBuilder
 }
 
 
+#[derive(Debug, Fail)]
+pub enum OpenError
+{
+    #[fail(display = "Unable to open directory: mode ({:?}) allows writing",
+           _0)]
+    DirWrite(OpenKind),
+
+    #[fail(display = "Unable to open directory: mode has the truncate flag set")]
+    DirTruncate,
+}
+
+
+/// Check that a file id can be opened with the given mode.
+///
+/// # Errors
+///
+/// An error is returned if the file id is a directory and the mode either
+/// allows writing or truncates the file.
+pub fn can_open(file_id: &FileID, mode: OpenMode) -> Result<(), OpenError>
+{
+    if !file_id.kind.contains(FileKind::DIR) {
+        return Ok(());
+    }
+
+    match mode.kind() {
+        kind @ OpenKind::Write | kind @ OpenKind::ReadWrite => {
+            return Err(OpenError::DirWrite(kind));
+        }
+        _ => {}
+    }
+
+    if mode.flags().contains(OpenFlag::OTRUNC) {
+        return Err(OpenError::DirTruncate);
+    }
+
+    Ok(())
+}
+
+
+// ===========================================================================
+// File mode
+// ===========================================================================
+
+
+bitflags! {
+    pub struct FileMode: u32 {
+        const DIR =         0x80000000;
+        const OWNER_READ =  0o400;
+        const OWNER_WRITE = 0o200;
+        const OWNER_EXEC =  0o100;
+        const GROUP_READ =  0o040;
+        const GROUP_WRITE = 0o020;
+        const GROUP_EXEC =  0o010;
+        const OTHER_READ =  0o004;
+        const OTHER_WRITE = 0o002;
+        const OTHER_EXEC =  0o001;
+    }
+}
+
+
+#[derive(Debug, Fail)]
+#[fail(display = "Reserved file mode bits set: {:b}", bits)]
+pub struct FileModeError
+{
+    bits: u32,
+}
+
+
+impl FileMode
+{
+    /// Convert raw mode bits into a FileMode.
+    ///
+    /// # Errors
+    ///
+    /// An error holding the offending bits is returned if any reserved bit
+    /// is set.
+    pub fn from_u32(bits: u32) -> Result<FileMode, FileModeError>
+    {
+        let reserved = bits & !FileMode::all().bits();
+        if reserved != 0 {
+            return Err(FileModeError { bits: reserved });
+        }
+        Ok(FileMode::from_bits_truncate(bits))
+    }
+
+    pub fn is_dir(&self) -> bool
+    {
+        self.contains(FileMode::DIR)
+    }
+
+    // The following permission checks are for the file's owner
+
+    pub fn is_readable(&self) -> bool
+    {
+        self.contains(FileMode::OWNER_READ)
+    }
+
+    pub fn is_writable(&self) -> bool
+    {
+        self.contains(FileMode::OWNER_WRITE)
+    }
+
+    pub fn is_executable(&self) -> bool
+    {
+        self.contains(FileMode::OWNER_EXEC)
+    }
+}
+
+
 // ===========================================================================
 //
 // ===========================================================================
@@ -0,0 +1,894 @@
+// src/session.rs
+// Copyright (C) 2017 authors and contributors (see AUTHORS file)
+//
+// This file is released under the MIT License.
+
+//! Types for keeping track of the state of an RPC session
+//!
+//! # Pending
+//!
+//! [`Pending`] records requests that have been sent but have not yet been
+//! answered, keyed by message id.
+//!
+//! # Sequential
+//!
+//! [`Sequential`] checks that a connection which does not pipeline requests
+//! only ever has a single outstanding request, and that each response
+//! answers it.
+//!
+//! # MonotonicGuard
+//!
+//! [`MonotonicGuard`] checks that the msg ids of requests on a connection
+//! are strictly increasing.
+//!
+//! # MessageIdGen
+//!
+//! [`MessageIdGen`] hands out increasing msg ids that [`MonotonicGuard`]
+//! accepts.
+//!
+//! # Exchange
+//!
+//! [`Exchange`] holds a v1 request and hands out response builders bound to
+//! it.
+//!
+//! For v1 sessions, [`Pending`] can also build requests that are checked
+//! against the file ids used by in-flight requests and live files, and
+//! [`plan_walk`] splits a deep path into several chained walk requests.
+//!
+//! # OpenFiles
+//!
+//! [`OpenFiles`] follows the v1 requests and responses of a connection to
+//! keep track of the client file ids that are currently live.
+//!
+//! # StatCache
+//!
+//! [`StatCache`] caches the results of v1 stat requests, dropping any result
+//! whose file has since been given a newer version.
+//!
+//! [`Exchange`]: struct.Exchange.html
+//! [`MessageIdGen`]: struct.MessageIdGen.html
+//! [`MonotonicGuard`]: struct.MonotonicGuard.html
+//! [`OpenFiles`]: struct.OpenFiles.html
+//! [`Pending`]: struct.Pending.html
+//! [`plan_walk`]: fn.plan_walk.html
+//! [`Sequential`]: struct.Sequential.html
+//! [`StatCache`]: struct.StatCache.html
+
+// ===========================================================================
+// Imports
+// ===========================================================================
+
+
+// Stdlib imports
+
+use std::collections::{HashMap, HashSet};
+
+// Third-party imports
+
+use rmpv::Value;
+
+// Local imports
+
+use core::CodeConvert;
+use core::request::{RequestMessage, RpcRequest};
+use core::response::{ResponseMessage, RpcResponse};
+use message::v1::{file_id_arg_positions, request, response, response_code_for,
+                  BuildRequestError, FileID, Request, RequestBuilder,
+                  RequestCode, Response, ResponseBuilder};
+
+
+// ===========================================================================
+// Pending
+// ===========================================================================
+
+
+/// Requests that are waiting for a response, keyed by message id.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate siminau_rpc;
+///
+/// use siminau_rpc::message::v1::{request, RequestCode};
+/// use siminau_rpc::session::Pending;
+///
+/// # fn main() {
+/// let mut pending = Pending::new();
+/// pending.register(request(1).clunk(42));
+/// pending.register(request(2).remove(42));
+///
+/// assert_eq!(pending.len(), 2);
+/// assert_eq!(pending.filter_by_code(RequestCode::Clunk), vec![1]);
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Pending<C>
+{
+    requests: HashMap<u32, RequestMessage<C>>,
+}
+
+
+impl<C> Pending<C>
+where
+    C: CodeConvert<C> + PartialEq,
+{
+    /// Create an empty set of pending requests.
+    pub fn new() -> Self
+    {
+        Self {
+            requests: HashMap::new(),
+        }
+    }
+
+    /// Register a request using its message id.
+    ///
+    /// If a request with the same message id was already registered, it is
+    /// replaced and returned.
+    pub fn register(
+        &mut self, req: RequestMessage<C>
+    ) -> Option<RequestMessage<C>>
+    {
+        self.requests.insert(req.message_id(), req)
+    }
+
+    /// Return the request registered with the given message id.
+    pub fn get(&self, msgid: u32) -> Option<&RequestMessage<C>>
+    {
+        self.requests.get(&msgid)
+    }
+
+    /// Remove and return the request registered with the given message id.
+    pub fn remove(&mut self, msgid: u32) -> Option<RequestMessage<C>>
+    {
+        self.requests.remove(&msgid)
+    }
+
+    /// Return the number of pending requests.
+    pub fn len(&self) -> usize
+    {
+        self.requests.len()
+    }
+
+    /// Return true if there are no pending requests.
+    pub fn is_empty(&self) -> bool
+    {
+        self.requests.is_empty()
+    }
+
+    /// Return the sorted message ids of all pending requests with the given
+    /// method code.
+    pub fn filter_by_code(&self, code: C) -> Vec<u32>
+    {
+        let mut ret: Vec<u32> = self.requests
+            .iter()
+            .filter(|&(_, req)| req.message_method() == code)
+            .map(|(msgid, _)| *msgid)
+            .collect();
+        ret.sort();
+        ret
+    }
+}
+
+
+impl<C> Default for Pending<C>
+where
+    C: CodeConvert<C> + PartialEq,
+{
+    fn default() -> Self
+    {
+        Self::new()
+    }
+}
+
+
+// ===========================================================================
+// Sequential
+// ===========================================================================
+
+
+#[derive(Debug, Fail)]
+pub enum SequenceError
+{
+    #[fail(display = "Unable to send request: request with msg id ({}) is \
+                      still outstanding",
+           _0)]
+    Outstanding(u32),
+
+    #[fail(display = "Unexpected response with msg id ({}): no request is \
+                      outstanding",
+           _0)]
+    Unsolicited(u32),
+
+    #[fail(display = "Unexpected response with msg id ({}): expected msg id \
+                      ({})",
+           actual, expected)]
+    IDMismatch
+    {
+        expected: u32, actual: u32
+    },
+}
+
+
+/// Guard for a connection where each request must be answered before the
+/// next one is sent.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate siminau_rpc;
+///
+/// use siminau_rpc::message::{request, response};
+/// use siminau_rpc::session::Sequential;
+///
+/// # fn main() {
+/// let mut guard = Sequential::new();
+/// let req = request(42).version(1);
+/// let resp = response(&req).version(1).unwrap();
+///
+/// guard.send(&req).unwrap();
+/// guard.recv(&resp).unwrap();
+///
+/// // No request is outstanding anymore
+/// assert!(guard.recv(&resp).is_err());
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct Sequential
+{
+    outstanding: Option<u32>,
+}
+
+
+impl Sequential
+{
+    /// Create a guard with no outstanding request.
+    pub fn new() -> Self
+    {
+        Self { outstanding: None }
+    }
+
+    /// Return the msg id of the outstanding request, if any.
+    pub fn outstanding(&self) -> Option<u32>
+    {
+        self.outstanding
+    }
+
+    /// Record a request as sent.
+    ///
+    /// An error is returned if a previous request has not been answered yet.
+    pub fn send<C>(
+        &mut self, req: &RequestMessage<C>
+    ) -> Result<(), SequenceError>
+    where
+        C: CodeConvert<C>,
+    {
+        if let Some(msgid) = self.outstanding {
+            return Err(SequenceError::Outstanding(msgid));
+        }
+        self.outstanding = Some(req.message_id());
+        Ok(())
+    }
+
+    /// Record a response as received.
+    ///
+    /// An error is returned if no request is outstanding or if the
+    /// response's msg id does not match the outstanding request's msg id.
+    /// The outstanding request is left in place on error.
+    pub fn recv<C>(
+        &mut self, resp: &ResponseMessage<C>
+    ) -> Result<(), SequenceError>
+    where
+        C: CodeConvert<C>,
+    {
+        let actual = resp.message_id();
+        match self.outstanding {
+            None => Err(SequenceError::Unsolicited(actual)),
+            Some(expected) if expected != actual => {
+                let err = SequenceError::IDMismatch {
+                    expected: expected,
+                    actual: actual,
+                };
+                Err(err)
+            }
+            Some(_) => {
+                self.outstanding = None;
+                Ok(())
+            }
+        }
+    }
+}
+
+// ===========================================================================
+// MonotonicGuard
+// ===========================================================================
+
+
+#[derive(Debug, Fail)]
+pub enum OrderError
+{
+    #[fail(display = "Invalid msg id ({}): must be greater than the previous \
+                      msg id ({})",
+           actual, previous)]
+    NotIncreasing
+    {
+        previous: u32, actual: u32
+    },
+
+    #[fail(display = "Invalid msg id ({}): msg ids have already wrapped past \
+                      u32::MAX once",
+           _0)]
+    AlreadyWrapped(u32),
+}
+
+
+/// Guard for a connection where request msg ids must be strictly increasing.
+///
+/// Msg ids are allowed to wrap exactly once, after the previous msg id
+/// reaches `u32::MAX`: the id following `u32::MAX` may be any smaller value,
+/// and ids must then increase from there. Once the ids have wrapped,
+/// `u32::MAX` is rejected so that they cannot wrap a second time.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate siminau_rpc;
+///
+/// use siminau_rpc::session::MonotonicGuard;
+///
+/// # fn main() {
+/// let mut guard = MonotonicGuard::new();
+///
+/// guard.check(1).unwrap();
+/// guard.check(2).unwrap();
+///
+/// // Repeated or decreasing ids are rejected
+/// assert!(guard.check(2).is_err());
+/// assert!(guard.check(1).is_err());
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct MonotonicGuard
+{
+    previous: Option<u32>,
+    wrapped: bool,
+}
+
+
+impl MonotonicGuard
+{
+    /// Create a guard that has not seen any msg id.
+    pub fn new() -> Self
+    {
+        Self {
+            previous: None,
+            wrapped: false,
+        }
+    }
+
+    /// Return the last msg id that was accepted, if any.
+    pub fn previous(&self) -> Option<u32>
+    {
+        self.previous
+    }
+
+    /// Return true if the msg ids have wrapped past `u32::MAX`.
+    pub fn wrapped(&self) -> bool
+    {
+        self.wrapped
+    }
+
+    /// Record the msg id of a request.
+    ///
+    /// An error is returned if the id is equal to the previous accepted id,
+    /// or is less than it unless the previous id was `u32::MAX`. Once the
+    /// ids have wrapped, an error is also returned for `u32::MAX`. The
+    /// previous id is left in place on error.
+    pub fn check(&mut self, id: u32) -> Result<(), OrderError>
+    {
+        let max = u32::max_value();
+        if self.wrapped && id == max {
+            return Err(OrderError::AlreadyWrapped(id));
+        }
+
+        if let Some(previous) = self.previous {
+            let wrap = previous == max && id < previous;
+            if id <= previous && !wrap {
+                let err = OrderError::NotIncreasing {
+                    previous: previous,
+                    actual: id,
+                };
+                return Err(err);
+            }
+            self.wrapped = self.wrapped || wrap;
+        }
+        self.previous = Some(id);
+        Ok(())
+    }
+}
+
+
+// ===========================================================================
+// MessageIdGen
+// ===========================================================================
+
+
+/// Generator of msg ids for new requests.
+///
+/// Ids increase by 1 each time, wrapping to 0 after `u32::MAX`, so the ids
+/// are accepted by a [`MonotonicGuard`] until they reach `u32::MAX` a second
+/// time.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate siminau_rpc;
+///
+/// use siminau_rpc::session::MessageIdGen;
+///
+/// # fn main() {
+/// let mut ids = MessageIdGen::new(u32::max_value());
+///
+/// assert_eq!(ids.next_id(), u32::max_value());
+/// assert_eq!(ids.next_id(), 0);
+/// assert_eq!(ids.next_id(), 1);
+/// # }
+/// ```
+///
+/// [`MonotonicGuard`]: struct.MonotonicGuard.html
+#[derive(Debug, Default)]
+pub struct MessageIdGen
+{
+    next: u32,
+}
+
+
+impl MessageIdGen
+{
+    /// Create a generator whose first id is `first`.
+    pub fn new(first: u32) -> Self
+    {
+        Self { next: first }
+    }
+
+    /// Return the next id.
+    pub fn next_id(&mut self) -> u32
+    {
+        let ret = self.next;
+        self.next = ret.wrapping_add(1);
+        ret
+    }
+}
+
+
+// ===========================================================================
+// v1 requests
+// ===========================================================================
+
+
+#[derive(Debug, Fail)]
+pub enum WalkError
+{
+    #[fail(display = "Unable to build walk request message")]
+    Build(#[cause] BuildRequestError),
+
+    #[fail(display = "Invalid newfile_id value ({}): file id is already in \
+                      use by a pending request or a live file",
+           _0)]
+    FileIDInUse(u32),
+}
+
+
+impl Pending<RequestCode>
+{
+    /// Return true if any pending request uses the given client file id.
+    pub fn file_id_in_use(&self, file_id: u32) -> bool
+    {
+        let file_id = Some(file_id as u64);
+        self.requests.values().any(|req| {
+            let args = req.message_args();
+            file_id_arg_positions(req.message_method())
+                .iter()
+                .any(|&i| args.get(i).and_then(|v| v.as_u64()) == file_id)
+        })
+    }
+
+    /// Build a walk request, checking that newfile_id is not already used
+    /// by a pending request or by a file that is live in `files`.
+    ///
+    /// Apart from the newfile_id check, this is the same as calling
+    /// `request(new_msg_id).walk(file_id, newfile_id, path)`.
+    pub fn build_walk(
+        &self, files: &OpenFiles, new_msg_id: u32, file_id: u32,
+        newfile_id: u32, path: Vec<&str>,
+    ) -> Result<Request, WalkError>
+    {
+        if self.file_id_in_use(newfile_id) ||
+            files.file_id_in_use(newfile_id)
+        {
+            return Err(WalkError::FileIDInUse(newfile_id));
+        }
+
+        request(new_msg_id)
+            .walk(file_id, newfile_id, path)
+            .map_err(|e| WalkError::Build(e))
+    }
+}
+
+
+/// Split a walk of a deep path into several walk requests of at most
+/// `max_elems` path elements each.
+///
+/// The first walk starts from `file_id`, and each following walk starts from
+/// the new file id of the walk before it, so the new file id of the last
+/// walk refers to the end of the path. An empty path is planned as a single
+/// walk of the empty path.
+///
+/// Every walk takes its msg id from `id_gen`. Its new file id is the lowest
+/// file id that is not in use in `files` and is not `file_id` or the new
+/// file id of an earlier walk of the plan. The walks are not recorded in
+/// `files`, so they must be observed before `files` is used to plan or
+/// build any other request.
+///
+/// The new file ids of every walk but the last stay allocated once the
+/// walks succeed. The caller is responsible for clunking them.
+///
+/// The path elements are not checked, so relative elements such as `..`
+/// are planned as given.
+///
+/// # Panics
+///
+/// Panics if `max_elems` is 0.
+pub fn plan_walk(
+    file_id: u32, path: &[&str], id_gen: &mut MessageIdGen, files: &OpenFiles,
+    max_elems: usize,
+) -> Vec<Request>
+{
+    assert!(max_elems > 0, "max_elems must be greater than 0");
+
+    let mut chunks: Vec<&[&str]> = path.chunks(max_elems).collect();
+    if chunks.is_empty() {
+        chunks.push(&[]);
+    }
+
+    let mut ret = Vec::with_capacity(chunks.len());
+    let mut taken = vec![file_id];
+    for chunk in chunks {
+        let cur_id = taken[taken.len() - 1];
+        let newfile_id = (0..u32::max_value())
+            .find(|id| !taken.contains(id) && !files.file_id_in_use(*id))
+            .expect("no unused file id left");
+        taken.push(newfile_id);
+
+        // The file ids are always distinct and relative path elements are
+        // allowed, so building the walk cannot fail
+        let req = request(id_gen.next_id())
+            .allow_relative_path()
+            .walk(cur_id, newfile_id, chunk.to_vec())
+            .expect("walk file ids must be distinct");
+        ret.push(req);
+    }
+    ret
+}
+
+
+// ===========================================================================
+// OpenFiles
+// ===========================================================================
+
+
+/// Set of live client file ids, built from observed v1 requests and
+/// responses.
+///
+/// A file id becomes live once a response confirms an Auth, Attach, Walk,
+/// Open, or Create request using it, and stops being live once a response
+/// confirms a Clunk or Remove request for it. Requests are remembered until
+/// their response is observed.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate siminau_rpc;
+///
+/// use siminau_rpc::message::v1::{openmode, request, response, FileID,
+///                                FileKind, OpenKind};
+/// use siminau_rpc::session::OpenFiles;
+///
+/// # fn main() {
+/// let mut files = OpenFiles::new();
+///
+/// let mode = openmode().kind(OpenKind::Read).create();
+/// let req = request(1).open(42, mode);
+/// let resp = response(&req)
+///     .open(FileID::new(FileKind::FILE, 0, 1), 0)
+///     .unwrap();
+/// files.observe_request(&req);
+/// files.observe_response(&resp);
+/// assert!(files.contains(42));
+///
+/// let req = request(2).clunk(42);
+/// let resp = response(&req).clunk().unwrap();
+/// files.observe_request(&req);
+/// files.observe_response(&resp);
+/// assert!(files.is_empty());
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct OpenFiles
+{
+    pending: Pending<RequestCode>,
+    live: HashSet<u32>,
+}
+
+
+impl OpenFiles
+{
+    /// Create a tracker with no live file ids.
+    pub fn new() -> Self
+    {
+        Self {
+            pending: Pending::new(),
+            live: HashSet::new(),
+        }
+    }
+
+    /// Record a request that has been sent.
+    pub fn observe_request(&mut self, req: &Request)
+    {
+        self.pending.register(req.clone());
+    }
+
+    /// Record a response that has been received.
+    ///
+    /// The live file ids are updated if the response answers a recorded
+    /// request. Returns false, changing nothing, if no recorded request has
+    /// the response's msg id or the response code does not answer the
+    /// request.
+    pub fn observe_response(&mut self, resp: &Response) -> bool
+    {
+        let req = match self.pending.remove(resp.message_id()) {
+            Some(req) => req,
+            None => return false,
+        };
+
+        let code = req.message_method();
+        if resp.error_code() != response_code_for(code.clone()) {
+            return false;
+        }
+
+        // The new file id is the 2nd arg of walk requests, and the 1st arg of
+        // every other request
+        let position = match code {
+            RequestCode::Walk => 1,
+            _ => 0,
+        };
+        let file_id = match req.message_args()
+            .get(position)
+            .and_then(|v| v.as_u64())
+        {
+            Some(id) => id as u32,
+            None => return false,
+        };
+
+        match code {
+            RequestCode::Auth |
+            RequestCode::Attach |
+            RequestCode::Walk |
+            RequestCode::Open |
+            RequestCode::Create => {
+                self.live.insert(file_id);
+            }
+            RequestCode::Clunk | RequestCode::Remove => {
+                self.live.remove(&file_id);
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// Return true if the file id is live.
+    pub fn contains(&self, file_id: u32) -> bool
+    {
+        self.live.contains(&file_id)
+    }
+
+    /// Return true if the file id is live or is used by an observed request
+    /// that has not been answered yet.
+    pub fn file_id_in_use(&self, file_id: u32) -> bool
+    {
+        self.contains(file_id) || self.pending.file_id_in_use(file_id)
+    }
+
+    /// Return the sorted live file ids.
+    pub fn file_ids(&self) -> Vec<u32>
+    {
+        let mut ret: Vec<u32> = self.live.iter().cloned().collect();
+        ret.sort();
+        ret
+    }
+
+    /// Return the number of live file ids.
+    pub fn len(&self) -> usize
+    {
+        self.live.len()
+    }
+
+    /// Return true if there are no live file ids.
+    pub fn is_empty(&self) -> bool
+    {
+        self.live.is_empty()
+    }
+}
+
+
+// ===========================================================================
+// Exchange
+// ===========================================================================
+
+
+/// A v1 request along with the means to build its response.
+///
+/// This replaces the pattern of building a request and then separately
+/// calling `response(&req)` to answer it.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate siminau_rpc;
+///
+/// use siminau_rpc::core::response::RpcResponse;
+/// use siminau_rpc::message::v1::{FileID, FileKind};
+/// use siminau_rpc::session::Exchange;
+///
+/// # fn main() {
+/// let exchange = Exchange::build(42, |b| b.auth(1, "hello", "world"))
+///     .unwrap();
+///
+/// let authfile = FileID::new(FileKind::AUTH, 0, 1);
+/// let resp = exchange.response().auth(authfile).unwrap();
+/// assert_eq!(resp.message_id(), 42);
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Exchange
+{
+    request: Request,
+}
+
+
+impl Exchange
+{
+    /// Create an exchange for an already built request.
+    pub fn new(request: Request) -> Self
+    {
+        Self { request: request }
+    }
+
+    /// Create an exchange for the request built by `build`.
+    ///
+    /// `build` is given a request builder for `msgid`. Any error it returns
+    /// is passed through unchanged.
+    pub fn build<F, E>(msgid: u32, build: F) -> Result<Self, E>
+    where
+        F: FnOnce(RequestBuilder) -> Result<Request, E>,
+    {
+        build(request(msgid)).map(Self::new)
+    }
+
+    /// Return the exchange's request.
+    pub fn request(&self) -> &Request
+    {
+        &self.request
+    }
+
+    /// Return a builder for a response to the exchange's request.
+    pub fn response<'a>(&'a self) -> ResponseBuilder<'a>
+    {
+        response(&self.request)
+    }
+
+    /// Consume the exchange, returning its request.
+    pub fn into_request(self) -> Request
+    {
+        self.request
+    }
+}
+
+
+// ===========================================================================
+// StatCache
+// ===========================================================================
+
+
+/// Cached stat results, keyed by file id.
+///
+/// Only the result for the newest known version of a file is kept: putting a
+/// result for a file id evicts the result cached for an older version of
+/// the same path, and a result for an older version than the one cached is
+/// ignored. A lookup only hits if the cached file id matches exactly.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate rmpv;
+/// extern crate siminau_rpc;
+///
+/// use rmpv::Value;
+/// use siminau_rpc::message::v1::{FileID, FileKind};
+/// use siminau_rpc::session::StatCache;
+///
+/// # fn main() {
+/// let mut cache = StatCache::new();
+/// let old = FileID::new(FileKind::FILE, 0, 42);
+/// let new = FileID::new(FileKind::FILE, 1, 42);
+/// let stat = Value::Map(vec![(Value::from("size"), Value::from(10))]);
+///
+/// cache.put(old, stat.clone());
+/// assert_eq!(cache.get(&old), Some(&stat));
+///
+/// // The file's version has changed, so the cached result is stale
+/// assert_eq!(cache.get(&new), None);
+///
+/// cache.put(new, stat.clone());
+/// assert_eq!(cache.get(&old), None);
+/// assert_eq!(cache.len(), 1);
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct StatCache
+{
+    entries: HashMap<u64, (FileID, Value)>,
+}
+
+
+impl StatCache
+{
+    /// Create an empty cache.
+    pub fn new() -> Self
+    {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Return the stat result cached for the file id.
+    pub fn get(&self, file_id: &FileID) -> Option<&Value>
+    {
+        match self.entries.get(&file_id.path) {
+            Some(entry) if entry.0 == *file_id => Some(&entry.1),
+            _ => None,
+        }
+    }
+
+    /// Cache a stat result for the file id.
+    ///
+    /// Any result cached for an older version of the file id's path is
+    /// evicted. If a newer version of the path is already cached, the result
+    /// is ignored.
+    pub fn put(&mut self, file_id: FileID, stat: Value)
+    {
+        let superseded = match self.entries.get(&file_id.path) {
+            Some(entry) => entry.0.version > file_id.version,
+            None => false,
+        };
+        if !superseded {
+            self.entries.insert(file_id.path, (file_id, stat));
+        }
+    }
+
+    /// Return the number of cached results.
+    pub fn len(&self) -> usize
+    {
+        self.entries.len()
+    }
+
+    /// Return true if no results are cached.
+    pub fn is_empty(&self) -> bool
+    {
+        self.entries.is_empty()
+    }
+}
+
+
+// ===========================================================================
+//
+// ===========================================================================
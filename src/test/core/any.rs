@@ -0,0 +1,113 @@
+// src/test/core/any.rs
+// Copyright (C) 2017 authors and contributors (see AUTHORS file)
+//
+// This file is released under the MIT License.
+
+// ===========================================================================
+// Imports
+// ===========================================================================
+
+
+// Stdlib imports
+
+// Third-party imports
+
+use rmpv::Value;
+
+// Local imports
+
+use core::{CodeConvert, FromMessage, Message, MessageType};
+use core::any::{into_typed, AnyMessage, ToAnyMessageError};
+use core::notify::{NotificationMessage, RpcNotice};
+use core::request::{RequestMessage, RpcRequest};
+
+// Helpers
+use super::TestEnum;
+
+
+// ===========================================================================
+// Tests
+// ===========================================================================
+
+
+#[test]
+fn typed_stream_in_order()
+{
+    // --------------------
+    // GIVEN
+    // a sequence of a request and a notification
+    // --------------------
+    let req = RequestMessage::new(42, TestEnum::One, vec![Value::from(1)]);
+    let notice = NotificationMessage::new(TestEnum::Two, vec![]);
+    let raw: Vec<Message> = vec![req.into(), notice.into()];
+
+    // --------------------
+    // WHEN
+    // into_typed() is called with the sequence
+    // --------------------
+    let result: Vec<_> = into_typed::<TestEnum, _>(raw).collect();
+
+    // --------------------
+    // THEN
+    // a request and then a notification are yielded
+    // --------------------
+    assert_eq!(result.len(), 2);
+    match result[0] {
+        Ok(AnyMessage::Request(ref r)) => {
+            assert_eq!(r.message_id(), 42);
+            assert_eq!(r.message_method(), TestEnum::One);
+        }
+        _ => panic!("expected a request"),
+    }
+    match result[1] {
+        Ok(AnyMessage::Notification(ref n)) => {
+            assert_eq!(n.message_code(), TestEnum::Two);
+        }
+        _ => panic!("expected a notification"),
+    }
+}
+
+
+#[test]
+fn typed_stream_invalid_code()
+{
+    // --------------------
+    // GIVEN
+    // a sequence of a request with an unknown code and
+    // a valid notification
+    // --------------------
+    let msgtype = Value::from(MessageType::Request.to_number());
+    let val = Value::Array(vec![msgtype, Value::from(1), Value::from(99),
+                                Value::Array(vec![])]);
+    let bad = Message::from_msg(val).unwrap();
+    let notice = NotificationMessage::new(TestEnum::Three, vec![]);
+    let raw: Vec<Message> = vec![bad, notice.into()];
+
+    // --------------------
+    // WHEN
+    // into_typed() is called with the sequence
+    // --------------------
+    let mut typed = into_typed::<TestEnum, _>(raw);
+
+    // --------------------
+    // THEN
+    // a request error is yielded and
+    // the notification following it is still yielded
+    // --------------------
+    match typed.next() {
+        Some(Err(ToAnyMessageError::Request(_))) => {}
+        _ => panic!("expected a request error"),
+    }
+    match typed.next() {
+        Some(Ok(ref m)) => {
+            assert_eq!(m.message_type(), MessageType::Notification)
+        }
+        _ => panic!("expected a notification"),
+    }
+    assert!(typed.next().is_none());
+}
+
+
+// ===========================================================================
+//
+// ===========================================================================
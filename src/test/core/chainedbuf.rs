@@ -0,0 +1,228 @@
+// src/test/core/chainedbuf.rs
+// Copyright (C) 2017 authors and contributors (see AUTHORS file)
+//
+// This file is released under the MIT License.
+
+// ===========================================================================
+// Imports
+// ===========================================================================
+
+
+// Stdlib imports
+
+use std::io::Read;
+
+// Third-party imports
+
+use bytes::Bytes;
+use rmpv::Value;
+
+// Local imports
+
+use core::{AsBytes, ChainedBuf, FromBytesError, Message, ToMessageError};
+use core::request::RequestMessage;
+
+// Helpers
+use super::TestEnum;
+
+
+// ===========================================================================
+// Helpers
+// ===========================================================================
+
+
+fn split_msgpack(msg: &Message, n: usize) -> Vec<Bytes>
+{
+    let mut msgpack = msg.as_bytes();
+    let size = msgpack.len() / n;
+    let mut ret = Vec::new();
+    for _ in 0..n - 1 {
+        ret.push(msgpack.split_to(size));
+    }
+    ret.push(msgpack);
+    ret
+}
+
+
+fn request_message() -> Message
+{
+    let args = vec![Value::from("hello world"), Value::from(9001)];
+    RequestMessage::new(42, TestEnum::One, args).into()
+}
+
+
+// ===========================================================================
+// Tests
+// ===========================================================================
+
+
+#[test]
+fn read_across_chunks()
+{
+    // --------------------
+    // GIVEN
+    // a ChainedBuf with 3 chunks
+    // --------------------
+    let chunks = vec![
+        Bytes::from(&b"ab"[..]),
+        Bytes::new(),
+        Bytes::from(&b"cde"[..]),
+        Bytes::from(&b"f"[..]),
+    ];
+    let mut buf = ChainedBuf::new(chunks);
+
+    // --------------------
+    // WHEN
+    // all bytes are read from the buffer
+    // --------------------
+    let len = buf.len();
+    let mut result = Vec::new();
+    buf.read_to_end(&mut result).unwrap();
+
+    // --------------------
+    // THEN
+    // the bytes of all chunks are read in order and
+    // the buffer is empty
+    // --------------------
+    assert_eq!(len, 6);
+    assert_eq!(&result[..], b"abcdef");
+    assert!(buf.is_empty());
+}
+
+
+#[test]
+fn decode_three_chunks()
+{
+    // --------------------
+    // GIVEN
+    // a message serialized into msgpack bytes and
+    // the bytes are split into 3 chunks and
+    // a ChainedBuf holding the chunks
+    // --------------------
+    let msg = request_message();
+    let expected = msg.clone();
+    let chunks = split_msgpack(&msg, 3);
+    assert_eq!(chunks.len(), 3);
+    let mut buf = ChainedBuf::new(chunks);
+
+    // --------------------
+    // WHEN
+    // ChainedBuf::decode() is called
+    // --------------------
+    let result = buf.decode::<Message, ToMessageError>();
+
+    // --------------------
+    // THEN
+    // the message is decoded and
+    // all bytes have been consumed
+    // --------------------
+    assert_eq!(result.unwrap(), Some(expected));
+    assert!(buf.is_empty());
+}
+
+
+#[test]
+fn decode_incomplete()
+{
+    // --------------------
+    // GIVEN
+    // a message serialized into msgpack bytes and
+    // the bytes are split into 3 chunks and
+    // a ChainedBuf holding only the first 2 chunks
+    // --------------------
+    let msg = request_message();
+    let expected = msg.clone();
+    let mut chunks = split_msgpack(&msg, 3);
+    let last = chunks.pop().unwrap();
+    let mut buf = ChainedBuf::new(chunks);
+    let len = buf.len();
+
+    // --------------------
+    // WHEN
+    // ChainedBuf::decode() is called and
+    // the last chunk is pushed and
+    // ChainedBuf::decode() is called again
+    // --------------------
+    let incomplete = buf.decode::<Message, ToMessageError>();
+    let unconsumed = buf.len();
+    buf.push(last);
+    let complete = buf.decode::<Message, ToMessageError>();
+
+    // --------------------
+    // THEN
+    // None is returned the first time without consuming any bytes and
+    // the message is returned the second time
+    // --------------------
+    assert_eq!(incomplete.unwrap(), None);
+    assert_eq!(unconsumed, len);
+    assert_eq!(complete.unwrap(), Some(expected));
+    assert!(buf.is_empty());
+}
+
+
+#[test]
+fn decode_truncated_huge_length()
+{
+    // --------------------
+    // GIVEN
+    // a ChainedBuf holding a bin header claiming u32::MAX bytes and
+    // a str header claiming u32::MAX bytes, each followed by a single byte
+    // --------------------
+    let bin = vec![Bytes::from(&[0xc6, 0xff][..]),
+                   Bytes::from(&[0xff, 0xff, 0xff, 0x00][..])];
+    let string = vec![Bytes::from(&[0xdb, 0xff, 0xff, 0xff, 0xff, 0x61][..])];
+
+    for chunks in [bin, string].iter() {
+        let mut buf = ChainedBuf::new(chunks.clone());
+        let len = buf.len();
+
+        // --------------------
+        // WHEN
+        // ChainedBuf::decode() is called
+        // --------------------
+        let result = buf.decode::<Message, ToMessageError>();
+
+        // --------------------
+        // THEN
+        // None is returned without allocating for the claimed length and
+        // no bytes are consumed
+        // --------------------
+        assert_eq!(result.unwrap(), None);
+        assert_eq!(buf.len(), len);
+    }
+}
+
+
+#[test]
+fn decode_too_deep()
+{
+    // --------------------
+    // GIVEN
+    // a ChainedBuf holding arrays nested 2048 levels deep, split over 2
+    // chunks
+    // --------------------
+    let nested = vec![0x91; 2048];
+    let chunks = vec![Bytes::from(&nested[..1024]),
+                      Bytes::from(&nested[1024..])];
+    let mut buf = ChainedBuf::new(chunks);
+
+    // --------------------
+    // WHEN
+    // ChainedBuf::decode() is called
+    // --------------------
+    let result = buf.decode::<Message, ToMessageError>();
+
+    // --------------------
+    // THEN
+    // a depth limit error is returned
+    // --------------------
+    match result {
+        Err(FromBytesError::DepthLimitExceeded { .. }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+
+// ===========================================================================
+//
+// ===========================================================================
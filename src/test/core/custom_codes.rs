@@ -0,0 +1,356 @@
+// src/test/core/custom_codes.rs
+// Copyright (C) 2017 authors and contributors (see AUTHORS file)
+//
+// This file is released under the MIT License.
+
+//! Tests for the contract between the generic message types and user code
+//! enums.
+//!
+//! A user of the crate defines their own code enum, derives `CodeConvert`
+//! for it, and must then be able to build, serialize, and decode messages
+//! using it the same way as with the crate's own codes.
+
+// ===========================================================================
+// Imports
+// ===========================================================================
+
+
+// Stdlib imports
+
+use std::convert::{TryFrom, TryInto};
+use std::error::Error;
+
+// Third-party imports
+
+use bytes::BytesMut;
+use rmpv::Value;
+
+// Local imports
+
+use core::{AsBytes, CodeConvert, CodeValueError, FromBytes, FromBytesError,
+           FromMessage, Message, MessageType, RpcMessage};
+use core::notify::{NotificationMessage, RpcNotice};
+use core::request::{RequestMessage, RpcRequest};
+use core::response::{ResponseMessage, RpcResponse};
+
+
+// ===========================================================================
+// Helpers
+// ===========================================================================
+
+
+// A user defined set of codes that does not start at 0
+#[derive(Debug, PartialEq, Clone, CodeConvert)]
+enum UserCode
+{
+    Fetch = 100,
+    Store = 101,
+    Evict = 102,
+}
+
+
+// A user defined set of codes declared out of order and with gaps
+#[derive(Debug, PartialEq, Clone, CodeConvert)]
+enum Unordered
+{
+    High = 9,
+    Low = 1,
+    Mid = 4,
+}
+
+
+// A user defined set of codes with a hand-written CodeConvert impl
+#[derive(Debug, PartialEq, Clone)]
+enum ManualCode
+{
+    Open = 1,
+    Close = 3,
+}
+
+
+impl CodeConvert<ManualCode> for ManualCode
+{
+    type int_type = u8;
+
+    fn from_number(num: u8) -> Result<ManualCode, CodeValueError>
+    {
+        Self::from_u64(num as u64)
+    }
+
+    fn from_u64(num: u64) -> Result<ManualCode, CodeValueError>
+    {
+        match num {
+            1 => Ok(ManualCode::Open),
+            3 => Ok(ManualCode::Close),
+            _ => Err(CodeValueError { code: num }),
+        }
+    }
+
+    fn to_number(&self) -> u8
+    {
+        self.clone() as u8
+    }
+
+    fn to_u64(&self) -> u64
+    {
+        self.clone() as u64
+    }
+
+    fn min_number() -> u64
+    {
+        1
+    }
+
+    fn max_number() -> u64
+    {
+        3
+    }
+
+    fn cast_number(n: u64) -> Option<u8>
+    {
+        if n <= u8::max_value() as u64 {
+            Some(n as u8)
+        } else {
+            None
+        }
+    }
+}
+
+
+type UserRequest = RequestMessage<UserCode>;
+
+
+type UserResponse = ResponseMessage<UserCode>;
+
+
+type UserNotice = NotificationMessage<UserCode>;
+
+
+// ===========================================================================
+// Tests
+// ===========================================================================
+
+
+#[test]
+fn code_convert()
+{
+    // --------------------
+    // GIVEN
+    // the user code enum
+    // --------------------
+    // --------------------
+    // WHEN
+    // the CodeConvert methods are called
+    // THEN
+    // the enum's own discriminants are used
+    // --------------------
+    assert_eq!(UserCode::min_number(), 100);
+    assert_eq!(UserCode::max_number(), 102);
+    assert_eq!(UserCode::Store.to_u64(), 101);
+    assert_eq!(UserCode::from_u64(102).unwrap(), UserCode::Evict);
+    assert!(UserCode::from_u64(99).is_err());
+}
+
+
+#[test]
+fn all()
+{
+    // --------------------
+    // GIVEN
+    // user code enums
+    // --------------------
+    // --------------------
+    // WHEN
+    // CodeConvert::all() is called
+    // THEN
+    // exactly the defined variants are returned ordered by value
+    // --------------------
+    let expected = vec![UserCode::Fetch, UserCode::Store, UserCode::Evict];
+    assert_eq!(UserCode::all(), expected);
+
+    let expected = vec![Unordered::Low, Unordered::Mid, Unordered::High];
+    assert_eq!(Unordered::all(), expected);
+}
+
+
+#[test]
+fn name()
+{
+    // --------------------
+    // GIVEN
+    // the user code enum
+    // --------------------
+    // --------------------
+    // WHEN
+    // CodeConvert::name() is called on each variant
+    // THEN
+    // the variant's identifier is returned
+    // --------------------
+    assert_eq!(UserCode::Fetch.name(), "Fetch");
+    assert_eq!(UserCode::Store.name(), "Store");
+    assert_eq!(UserCode::Evict.name(), "Evict");
+    assert_eq!(Unordered::High.name(), "High");
+}
+
+
+#[test]
+fn hand_written_defaults()
+{
+    // --------------------
+    // GIVEN
+    // a code enum with a hand-written CodeConvert impl that only provides
+    // the required methods
+    // --------------------
+    // --------------------
+    // WHEN
+    // CodeConvert::all() and CodeConvert::name() are called
+    // THEN
+    // all() returns the defined variants ordered by value and
+    // name() returns an empty string
+    // --------------------
+    assert_eq!(ManualCode::all(), vec![ManualCode::Open, ManualCode::Close]);
+    assert_eq!(ManualCode::Open.name(), "");
+    assert_eq!(ManualCode::Close.name(), "");
+}
+
+
+#[test]
+fn try_from()
+{
+    // --------------------
+    // GIVEN
+    // the user code enum, whose numbers fit into a u8
+    // --------------------
+    // --------------------
+    // WHEN
+    // numbers are converted into codes w/ TryFrom and TryInto
+    // THEN
+    // the numbers of variants are converted into codes and
+    // any other number is a CodeValueError
+    // --------------------
+    assert_eq!(UserCode::try_from(101u64).unwrap(), UserCode::Store);
+    assert_eq!(UserCode::try_from(102u8).unwrap(), UserCode::Evict);
+    let fetch: Result<UserCode, _> = 100u64.try_into();
+    assert_eq!(fetch.unwrap(), UserCode::Fetch);
+    assert_eq!(UserCode::try_from(99u64).unwrap_err().code, 99);
+    assert_eq!(UserCode::try_from(103u8).unwrap_err().code, 103);
+
+    // --------------------
+    // WHEN
+    // a message type number is converted w/ the ? operator into a boxed
+    // std error
+    // THEN
+    // the CodeValueError is usable as a std error
+    // --------------------
+    fn msgtype(num: u64) -> Result<MessageType, Box<dyn Error>>
+    {
+        Ok(MessageType::try_from(num)?)
+    }
+    assert_eq!(msgtype(1).unwrap(), MessageType::Response);
+    let err = msgtype(42).unwrap_err();
+    assert_eq!(err.to_string(), "Unknown code value: 42");
+}
+
+
+#[test]
+fn request_roundtrip()
+{
+    // --------------------
+    // GIVEN
+    // a request built with a user code and
+    // the request serialized into bytes
+    // --------------------
+    let args = vec![Value::from("key"), Value::from(42)];
+    let req = UserRequest::new(1, UserCode::Store, args);
+    let mut buf = BytesMut::from(&req.as_bytes()[..]);
+
+    // --------------------
+    // WHEN
+    // the bytes are decoded back into a request
+    // --------------------
+    let result = UserRequest::from_bytes(&mut buf);
+
+    // --------------------
+    // THEN
+    // the decoded request is the same as the original request and
+    // every byte has been consumed
+    // --------------------
+    let decoded = result.unwrap().unwrap();
+    assert_eq!(decoded.message_type(), MessageType::Request);
+    assert_eq!(decoded.message_id(), 1);
+    assert_eq!(decoded.message_method(), UserCode::Store);
+    assert_eq!(decoded.as_value(), req.as_value());
+    assert!(buf.is_empty());
+}
+
+
+#[test]
+fn response_and_notice_roundtrip()
+{
+    // --------------------
+    // GIVEN
+    // a response and a notification built with user codes and
+    // both messages serialized into bytes
+    // --------------------
+    let resp = UserResponse::new(1, UserCode::Fetch, Value::from("value"));
+    let notice = UserNotice::new(UserCode::Evict, vec![Value::from("key")]);
+    let mut respbuf = BytesMut::from(&resp.as_bytes()[..]);
+    let mut noticebuf = BytesMut::from(&notice.as_bytes()[..]);
+
+    // --------------------
+    // WHEN
+    // the bytes are decoded back into a response and a notification
+    // --------------------
+    let decoded_resp = UserResponse::from_bytes(&mut respbuf);
+    let decoded_notice = UserNotice::from_bytes(&mut noticebuf);
+
+    // --------------------
+    // THEN
+    // the decoded messages are the same as the original messages
+    // --------------------
+    let decoded_resp = decoded_resp.unwrap().unwrap();
+    assert_eq!(decoded_resp.error_code(), UserCode::Fetch);
+    assert_eq!(decoded_resp.as_value(), resp.as_value());
+
+    let decoded_notice = decoded_notice.unwrap().unwrap();
+    assert_eq!(decoded_notice.message_code(), UserCode::Evict);
+    assert_eq!(decoded_notice.as_value(), notice.as_value());
+}
+
+
+#[test]
+fn unknown_code()
+{
+    // --------------------
+    // GIVEN
+    // the bytes of a request whose code is not a user code
+    // --------------------
+    let val = Value::Array(vec![
+        Value::from(MessageType::Request.to_number()),
+        Value::from(1),
+        Value::from(99),
+        Value::Array(vec![]),
+    ]);
+    let msg = Message::from_msg(val).unwrap();
+    let mut buf = BytesMut::from(&msg.as_bytes()[..]);
+
+    // --------------------
+    // WHEN
+    // the bytes are decoded into a user request
+    // --------------------
+    let result = UserRequest::from_bytes(&mut buf);
+
+    // --------------------
+    // THEN
+    // an invalid message error is returned
+    // --------------------
+    match result {
+        Err(FromBytesError::InvalidMessage(_)) => {}
+        _ => panic!("expected an invalid message error"),
+    }
+}
+
+
+// ===========================================================================
+//
+// ===========================================================================
@@ -0,0 +1,137 @@
+// src/test/core/decodeoutcome.rs
+// Copyright (C) 2017 authors and contributors (see AUTHORS file)
+//
+// This file is released under the MIT License.
+
+// ===========================================================================
+// Imports
+// ===========================================================================
+
+
+// Stdlib imports
+
+// Third-party imports
+
+use bytes::BytesMut;
+use rmps::Serializer;
+use rmpv::Value;
+use serde::Serialize;
+
+// Local imports
+
+use core::{decode_outcome, AsBytes, DecodeOutcome, FromBytesError,
+           FromMessage, Message, ToMessageError};
+
+
+// ===========================================================================
+// Helpers
+// ===========================================================================
+
+
+fn valid_message() -> Message
+{
+    let array = Value::Array(vec![
+        Value::from(1),
+        Value::from(42),
+        Value::from(42),
+    ]);
+    Message::from_msg(array).unwrap()
+}
+
+
+// ===========================================================================
+// Tests
+// ===========================================================================
+
+
+#[test]
+fn complete()
+{
+    // --------------------
+    // GIVEN
+    // a valid message serialized into a BytesMut buffer
+    // --------------------
+    let msg = valid_message();
+    let expected = msg.clone();
+    let mut buf = msg.as_bytes().try_mut().unwrap();
+
+    // --------------------
+    // WHEN
+    // decode_outcome() is called with the buffer
+    // --------------------
+    let result: DecodeOutcome<Message, ToMessageError> =
+        decode_outcome(&mut buf);
+
+    // --------------------
+    // THEN
+    // DecodeOutcome::Complete is returned with the message
+    // --------------------
+    match result {
+        DecodeOutcome::Complete(m) => assert_eq!(m, expected),
+        other => panic!("unexpected outcome: {:?}", other),
+    }
+}
+
+
+#[test]
+fn need_more()
+{
+    // --------------------
+    // GIVEN
+    // an empty BytesMut buffer
+    // --------------------
+    let mut buf = BytesMut::new();
+
+    // --------------------
+    // WHEN
+    // decode_outcome() is called with the buffer
+    // --------------------
+    let result: DecodeOutcome<Message, ToMessageError> =
+        decode_outcome(&mut buf);
+
+    // --------------------
+    // THEN
+    // DecodeOutcome::NeedMore is returned
+    // --------------------
+    match result {
+        DecodeOutcome::NeedMore => {}
+        other => panic!("unexpected outcome: {:?}", other),
+    }
+}
+
+
+#[test]
+fn error()
+{
+    // --------------------
+    // GIVEN
+    // a value that is not an array serialized into a BytesMut buffer
+    // --------------------
+    let mut msgpack = Vec::new();
+    Value::from(42).serialize(&mut Serializer::new(&mut msgpack)).unwrap();
+    let mut buf = BytesMut::from(msgpack);
+
+    // --------------------
+    // WHEN
+    // decode_outcome() is called with the buffer
+    // --------------------
+    let result: DecodeOutcome<Message, ToMessageError> =
+        decode_outcome(&mut buf);
+
+    // --------------------
+    // THEN
+    // DecodeOutcome::Error is returned with an invalid message error
+    // --------------------
+    let val = match result {
+        DecodeOutcome::Error(FromBytesError::InvalidMessage(
+            ToMessageError::NotArray(_),
+        )) => true,
+        _ => false,
+    };
+    assert!(val);
+}
+
+
+// ===========================================================================
+//
+// ===========================================================================
@@ -0,0 +1,70 @@
+// src/test/core/dynmessage.rs
+// Copyright (C) 2017 authors and contributors (see AUTHORS file)
+//
+// This file is released under the MIT License.
+
+// ===========================================================================
+// Imports
+// ===========================================================================
+
+
+// Stdlib imports
+
+// Third-party imports
+
+use rmpv::Value;
+
+// Local imports
+
+use core::{DynMessage, MessageType, RpcMessage};
+use core::notify::NotificationMessage;
+use core::request::RequestMessage;
+
+// Helpers
+use super::TestEnum;
+
+
+// ===========================================================================
+// Tests
+// ===========================================================================
+
+
+#[test]
+fn mixed_messages()
+{
+    // --------------------
+    // GIVEN
+    // a request message and
+    // a notification message
+    // --------------------
+    let req: RequestMessage<TestEnum> =
+        RequestMessage::new(42, TestEnum::One, vec![Value::from(42)]);
+    let notice: NotificationMessage<TestEnum> =
+        NotificationMessage::new(TestEnum::Two, vec![]);
+    let expected = vec![req.as_value().clone(), notice.as_value().clone()];
+
+    // --------------------
+    // WHEN
+    // both messages are stored in a single vec of DynMessage trait objects
+    // --------------------
+    let messages: Vec<Box<dyn DynMessage>> =
+        vec![Box::new(req), Box::new(notice)];
+
+    // --------------------
+    // THEN
+    // each message's type is as expected and
+    // each message's value is as expected
+    // --------------------
+    let msgtypes: Vec<MessageType> =
+        messages.iter().map(|m| m.dyn_message_type()).collect();
+    assert_eq!(msgtypes, vec![MessageType::Request, MessageType::Notification]);
+
+    let values: Vec<Value> =
+        messages.iter().map(|m| m.dyn_as_value().clone()).collect();
+    assert_eq!(values, expected);
+}
+
+
+// ===========================================================================
+//
+// ===========================================================================
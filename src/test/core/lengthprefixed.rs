@@ -0,0 +1,208 @@
+// src/test/core/lengthprefixed.rs
+// Copyright (C) 2017 authors and contributors (see AUTHORS file)
+//
+// This file is released under the MIT License.
+
+// ===========================================================================
+// Imports
+// ===========================================================================
+
+
+// Stdlib imports
+
+// Third-party imports
+
+use bytes::{BufMut, BytesMut};
+use rmpv::Value;
+
+// Local imports
+
+use core::{from_length_prefixed, AsBytes, FromBytesError, FromMessage,
+           Message, RpcMessage, ToMessageError};
+
+
+// ===========================================================================
+// Helpers
+// ===========================================================================
+
+
+fn valid_message() -> Message
+{
+    let array = Value::Array(vec![
+        Value::from(1),
+        Value::from(42),
+        Value::from(42),
+    ]);
+    Message::from_msg(array).unwrap()
+}
+
+
+// Build a frame holding body, prefixed with the given length
+fn frame(len: u32, body: &[u8]) -> BytesMut
+{
+    let mut buf = BytesMut::with_capacity(4 + body.len());
+    buf.put_u32_be(len);
+    buf.put_slice(body);
+    buf
+}
+
+
+// ===========================================================================
+// Tests
+// ===========================================================================
+
+
+#[test]
+fn complete_frame()
+{
+    // --------------------
+    // GIVEN
+    // a frame holding a valid message followed by the start of another
+    // frame
+    // --------------------
+    let msg = valid_message();
+    let body = msg.as_bytes();
+    let mut buf = frame(body.len() as u32, &body[..]);
+    buf.extend_from_slice(&[0, 0]);
+
+    // --------------------
+    // WHEN
+    // from_length_prefixed() is called with the buffer
+    // --------------------
+    let result: Result<Option<Message>, FromBytesError<ToMessageError>> =
+        from_length_prefixed(&mut buf, 1024);
+
+    // --------------------
+    // THEN
+    // the message is returned and
+    // only the frame is consumed from the buffer
+    // --------------------
+    assert_eq!(result.unwrap(), Some(msg));
+    assert_eq!(&buf[..], &[0, 0]);
+}
+
+
+#[test]
+fn missing_body_bytes()
+{
+    // --------------------
+    // GIVEN
+    // a frame missing the last byte of its message
+    // --------------------
+    let body = valid_message().as_bytes();
+    let mut buf = frame(body.len() as u32, &body[..body.len() - 1]);
+    let expected = buf.clone();
+
+    // --------------------
+    // WHEN
+    // from_length_prefixed() is called with the buffer
+    // --------------------
+    let result: Result<Option<Message>, FromBytesError<ToMessageError>> =
+        from_length_prefixed(&mut buf, 1024);
+
+    // --------------------
+    // THEN
+    // None is returned and
+    // nothing is consumed from the buffer
+    // --------------------
+    assert!(result.unwrap().is_none());
+    assert_eq!(buf, expected);
+}
+
+
+#[test]
+fn length_too_large()
+{
+    // --------------------
+    // GIVEN
+    // a frame whose length is larger than the maximum length
+    // --------------------
+    let mut buf = frame(1025, &[]);
+
+    // --------------------
+    // WHEN
+    // from_length_prefixed() is called with the buffer
+    // --------------------
+    let result: Result<Option<Message>, FromBytesError<ToMessageError>> =
+        from_length_prefixed(&mut buf, 1024);
+
+    // --------------------
+    // THEN
+    // a frame too large error is returned
+    // --------------------
+    match result {
+        Err(FromBytesError::FrameTooLarge { len: 1025, max: 1024 }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+
+#[test]
+fn length_mismatch()
+{
+    // --------------------
+    // GIVEN
+    // a frame holding a valid message followed by an extra byte
+    // --------------------
+    let mut body = valid_message().as_bytes().to_vec();
+    body.push(0);
+    let mut buf = frame(body.len() as u32, &body[..]);
+
+    // --------------------
+    // WHEN
+    // from_length_prefixed() is called with the buffer
+    // --------------------
+    let result: Result<Option<Message>, FromBytesError<ToMessageError>> =
+        from_length_prefixed(&mut buf, 1024);
+
+    // --------------------
+    // THEN
+    // a frame mismatch error is returned and
+    // the frame is consumed from the buffer
+    // --------------------
+    match result {
+        Err(FromBytesError::FrameMismatch(len)) => {
+            assert_eq!(len as usize, body.len())
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert!(buf.is_empty());
+}
+
+
+#[test]
+fn as_length_prefixed_roundtrip()
+{
+    // --------------------
+    // GIVEN
+    // a valid message
+    // --------------------
+    let msg = valid_message();
+
+    // --------------------
+    // WHEN
+    // RpcMessage::as_length_prefixed() is called and
+    // the result is decoded w/ from_length_prefixed()
+    // --------------------
+    let framed = msg.as_length_prefixed();
+    let mut buf = BytesMut::from(&framed[..]);
+    let result: Result<Option<Message>, FromBytesError<ToMessageError>> =
+        from_length_prefixed(&mut buf, 1024);
+
+    // --------------------
+    // THEN
+    // the frame is the message's length followed by its bytes and
+    // the decoded message equals the original message and
+    // the whole frame is consumed
+    // --------------------
+    let body = msg.as_bytes();
+    assert_eq!(&framed[..4], &frame(body.len() as u32, &[])[..]);
+    assert_eq!(&framed[4..], &body[..]);
+    assert_eq!(result.unwrap(), Some(msg));
+    assert!(buf.is_empty());
+}
+
+
+// ===========================================================================
+//
+// ===========================================================================
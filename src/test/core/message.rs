@@ -153,6 +153,35 @@ mod from
         };
         assert!(ret)
     }
+
+    // A message type that is not an integer is an error rather than a panic
+    #[test]
+    fn non_integer_messagetype()
+    {
+        // GIVEN
+        // array with a string in the message type slot
+        let array = Value::from(vec![
+            Value::from("hello"),
+            Value::from(42),
+            Value::from(42),
+        ]);
+
+        // WHEN
+        // creating a message via Message::from_msg()
+        let result = Message::from_msg(array);
+
+        // THEN
+        // MessageError::InvalidType error is returned with a missing value
+        // cause
+        let val = match result {
+            Err(e @ ToMessageError::InvalidType(_)) => {
+                let cause = e.cause().unwrap();
+                cause.to_string() == "Expected u8 but got None"
+            }
+            _ => false,
+        };
+        assert!(val)
+    }
 }
 
 
@@ -462,6 +491,636 @@ mod convert_bytes {
 // }
 
 
+mod wire_eq
+{
+
+    // Stdlib imports
+
+    // Third-party imports
+
+    use bytes::Bytes;
+    use proptest::prelude::*;
+    use rmpv::Value;
+
+    // Local imports
+
+    use core::{AsBytes, CodeConvert, FromBytes, FromMessage, Message,
+               MessageType};
+
+    proptest! {
+        #[test]
+        fn reencoded_message(
+            msgid in prop::num::u32::ANY,
+            ref args in prop::collection::vec(prop::num::i64::ANY, 0..10))
+        {
+            // --------------------
+            // GIVEN
+            // a request message with integer arguments and
+            // the message is serialized into msgpack bytes
+            // --------------------
+            let args: Vec<Value> = args.iter().map(|v| Value::from(*v)).collect();
+            let msgtype = Value::from(MessageType::Request.to_number());
+            let val = Value::Array(vec![msgtype, Value::from(msgid),
+                                        Value::from(0), Value::Array(args)]);
+            let msg = Message::from_msg(val).unwrap();
+            let bytes: Bytes = msg.as_bytes();
+
+            // --------------------
+            // WHEN
+            // the bytes are deserialized into a new message
+            // --------------------
+            let mut buf = bytes.try_mut().unwrap();
+            let result = Message::from_bytes(&mut buf).unwrap();
+
+            // --------------------
+            // THEN
+            // the new message is wire equal to the original message
+            // --------------------
+            let decoded = result.unwrap();
+            prop_assert!(msg.wire_eq(&decoded));
+            prop_assert!(decoded.wire_eq(&msg));
+        }
+    }
+
+    #[test]
+    fn integer_width_ignored()
+    {
+        // --------------------
+        // GIVEN
+        // a message with an argument stored as a u8 and
+        // a message with the same argument stored as an i64
+        // --------------------
+        let msgtype = Value::from(MessageType::Request.to_number());
+        let mk_msg = |arg: Value| {
+            let val = Value::Array(vec![msgtype.clone(), Value::from(42),
+                                        Value::from(0),
+                                        Value::Array(vec![arg])]);
+            Message::from_msg(val).unwrap()
+        };
+        let small = mk_msg(Value::from(42u8));
+        let big = mk_msg(Value::from(42i64));
+
+        // --------------------
+        // WHEN
+        // Message::wire_eq() is called
+        // --------------------
+        let result = small.wire_eq(&big);
+
+        // --------------------
+        // THEN
+        // the messages are wire equal
+        // --------------------
+        assert!(result);
+    }
+
+    #[test]
+    fn different_values()
+    {
+        // --------------------
+        // GIVEN
+        // two messages with different argument values
+        // --------------------
+        let msgtype = Value::from(MessageType::Request.to_number());
+        let mk_msg = |arg: Value| {
+            let val = Value::Array(vec![msgtype.clone(), Value::from(42),
+                                        Value::from(0),
+                                        Value::Array(vec![arg])]);
+            Message::from_msg(val).unwrap()
+        };
+        let first = mk_msg(Value::from(42));
+        let second = mk_msg(Value::from(-42));
+
+        // --------------------
+        // WHEN
+        // Message::wire_eq() is called
+        // --------------------
+        let result = first.wire_eq(&second);
+
+        // --------------------
+        // THEN
+        // the messages are not wire equal
+        // --------------------
+        assert!(!result);
+    }
+}
+
+
+mod canonical_bytes
+{
+    // Stdlib imports
+
+    // Third-party imports
+
+    use bytes::Bytes;
+    use proptest::prelude::*;
+    use rmpv::Value;
+
+    // Local imports
+
+    use core::{AsBytes, CodeConvert, FromBytes, FromMessage, Message,
+               MessageType, RpcMessage};
+
+    proptest! {
+        #[test]
+        fn wire_eq_messages_identical(
+            msgid in prop::num::u32::ANY,
+            ref args in prop::collection::vec(prop::num::u8::ANY, 0..10))
+        {
+            // --------------------
+            // GIVEN
+            // a request message with arguments stored as u8 values and
+            // a request message with the same arguments stored as i64 values
+            // --------------------
+            let msgtype = Value::from(MessageType::Request.to_number());
+            let mk_msg = |args: Vec<Value>| {
+                let val = Value::Array(vec![msgtype.clone(),
+                                            Value::from(msgid),
+                                            Value::from(0),
+                                            Value::Array(args)]);
+                Message::from_msg(val).unwrap()
+            };
+            let small = mk_msg(args.iter().map(|v| Value::from(*v)).collect());
+            let big = mk_msg(
+                args.iter().map(|v| Value::from(i64::from(*v))).collect(),
+            );
+            prop_assert!(small.wire_eq(&big));
+
+            // --------------------
+            // WHEN
+            // canonical_bytes() is called on both messages
+            // --------------------
+            let small_bytes = small.canonical_bytes();
+            let big_bytes = big.canonical_bytes();
+
+            // --------------------
+            // THEN
+            // the bytes are identical
+            // --------------------
+            prop_assert_eq!(small_bytes, big_bytes);
+        }
+
+        #[test]
+        fn reencoded_message_identical(
+            msgid in prop::num::u32::ANY,
+            ref args in prop::collection::vec(prop::num::i64::ANY, 0..10))
+        {
+            // --------------------
+            // GIVEN
+            // a request message with integer arguments and
+            // the message decoded from its msgpack bytes
+            // --------------------
+            let args: Vec<Value> = args.iter().map(|v| Value::from(*v)).collect();
+            let msgtype = Value::from(MessageType::Request.to_number());
+            let val = Value::Array(vec![msgtype, Value::from(msgid),
+                                        Value::from(0), Value::Array(args)]);
+            let msg = Message::from_msg(val).unwrap();
+            let bytes: Bytes = msg.as_bytes();
+            let mut buf = bytes.try_mut().unwrap();
+            let decoded = Message::from_bytes(&mut buf).unwrap().unwrap();
+
+            // --------------------
+            // WHEN
+            // canonical_bytes() is called on both messages
+            // --------------------
+            let expected = msg.canonical_bytes();
+            let result = decoded.canonical_bytes();
+
+            // --------------------
+            // THEN
+            // the bytes are identical
+            // --------------------
+            prop_assert_eq!(result, expected);
+        }
+    }
+}
+
+
+mod as_bytes_split {
+    // Third-party imports
+
+    use bytes::BytesMut;
+    use rmpv::Value;
+
+    // Local imports
+
+    use core::{AsBytes, FromMessage, Message, RpcMessage};
+    use message::v1::{request, response};
+
+    #[test]
+    fn write_request()
+    {
+        // --------------------
+        // GIVEN
+        // a write request with a data payload
+        // --------------------
+        let data = vec![42u8; 300];
+        let req = request(42).write(1, 0, 300, &data).unwrap();
+
+        // --------------------
+        // WHEN
+        // RpcMessage::as_bytes_split() is called
+        // --------------------
+        let (header, payload) = req.as_bytes_split();
+
+        // --------------------
+        // THEN
+        // the payload is the raw data and
+        // the header and payload together equal the inline bytes
+        // --------------------
+        assert_eq!(&payload[..], &data[..]);
+
+        let mut reassembled = BytesMut::from(&header[..]);
+        reassembled.extend_from_slice(&payload[..]);
+        assert_eq!(reassembled.freeze(), req.as_bytes());
+    }
+
+    #[test]
+    fn read_response()
+    {
+        // --------------------
+        // GIVEN
+        // a read response with a data payload
+        // --------------------
+        let data = vec![1u8, 2, 3, 4];
+        let req = request(42).read(1, 0, 4);
+        let resp = response(&req).read(4, &data).unwrap();
+
+        // --------------------
+        // WHEN
+        // RpcMessage::as_bytes_split() is called
+        // --------------------
+        let (header, payload) = resp.as_bytes_split();
+
+        // --------------------
+        // THEN
+        // the payload is the raw data and
+        // the header and payload together equal the inline bytes
+        // --------------------
+        assert_eq!(&payload[..], &data[..]);
+
+        let mut reassembled = BytesMut::from(&header[..]);
+        reassembled.extend_from_slice(&payload[..]);
+        assert_eq!(reassembled.freeze(), resp.as_bytes());
+    }
+
+    #[test]
+    fn no_payload()
+    {
+        // --------------------
+        // GIVEN
+        // a message that does not end with a binary value
+        // --------------------
+        let array = Value::Array(vec![
+            Value::from(2),
+            Value::from(42),
+            Value::Array(vec![Value::Binary(vec![1, 2, 3]), Value::Nil]),
+        ]);
+        let msg = Message::from_msg(array).unwrap();
+
+        // --------------------
+        // WHEN
+        // RpcMessage::as_bytes_split() is called
+        // --------------------
+        let (header, payload) = msg.as_bytes_split();
+
+        // --------------------
+        // THEN
+        // the payload is empty and
+        // the header equals the inline bytes
+        // --------------------
+        assert!(payload.is_empty());
+        assert_eq!(header, msg.as_bytes());
+    }
+}
+
+mod template
+{
+    // Local imports
+
+    use core::{FromMessage, Message, MessageType, RpcMessage};
+    use core::notify::NotificationMessage;
+    use core::request::RequestMessage;
+    use core::response::ResponseMessage;
+
+    // Helpers
+    use test::core::TestEnum;
+
+    #[test]
+    fn message_type_matches()
+    {
+        let all = [
+            MessageType::Request,
+            MessageType::Response,
+            MessageType::Notification,
+        ];
+        for msgtype in all.iter() {
+            // --------------------
+            // GIVEN
+            // a message type
+            // --------------------
+            // --------------------
+            // WHEN
+            // Message::template() is called w/ the message type
+            // --------------------
+            let msg = Message::template(msgtype.clone());
+
+            // --------------------
+            // THEN
+            // the message's type matches the given type
+            // --------------------
+            assert_eq!(msg.message_type(), *msgtype);
+        }
+    }
+
+    #[test]
+    fn converts_to_typed_message()
+    {
+        // --------------------
+        // GIVEN
+        // a template of each message type
+        // --------------------
+        let req = Message::template(MessageType::Request);
+        let resp = Message::template(MessageType::Response);
+        let notice = Message::template(MessageType::Notification);
+
+        // --------------------
+        // WHEN
+        // each template is converted into its typed message w/ a code enum
+        // that has a variant numbered 0
+        // --------------------
+        let req = RequestMessage::<TestEnum>::from_msg(req);
+        let resp = ResponseMessage::<TestEnum>::from_msg(resp);
+        let notice = NotificationMessage::<TestEnum>::from_msg(notice);
+
+        // --------------------
+        // THEN
+        // every conversion succeeds
+        // --------------------
+        assert!(req.is_ok());
+        assert!(resp.is_ok());
+        assert!(notice.is_ok());
+    }
+}
+
+
+mod to_json_string
+{
+    // Third-party imports
+
+    use rmpv::Value;
+
+    // Local imports
+
+    use core::RpcMessage;
+    use message::v1::{Request, RequestCode};
+
+    #[test]
+    fn int_string_binary_args()
+    {
+        // --------------------
+        // GIVEN
+        // a request with an int arg, a string arg, and a binary arg
+        // --------------------
+        let args = vec![
+            Value::from(7),
+            Value::from("hello"),
+            Value::Binary(b"abc".to_vec()),
+        ];
+        let req = Request::new(42, RequestCode::Write, args);
+
+        // --------------------
+        // WHEN
+        // RpcMessage::to_json_string() is called
+        // --------------------
+        let result = req.to_json_string();
+
+        // --------------------
+        // THEN
+        // the message is a JSON array holding the msg type, msg id, code,
+        //    and args and
+        // the string arg is a JSON string and
+        // the binary arg is a base64 string
+        // --------------------
+        assert_eq!(result, "[0,42,18,[7,\"hello\",\"YWJj\"]]");
+    }
+}
+
+
+mod fast_ne
+{
+    // Third-party imports
+
+    use rmpv::Value;
+
+    // Local imports
+
+    use core::{CodeConvert, FromMessage, Message, MessageType};
+
+    // Build a request message holding a single deeply nested arg
+    fn deep_request(msgid: u32, code: u8, leaf: u8) -> Message
+    {
+        let mut arg = Value::from(leaf);
+        for _ in 0..10 {
+            arg = Value::Array(vec![arg]);
+        }
+        let msgtype = Value::from(MessageType::Request.to_number());
+        let args = Value::Array(vec![arg]);
+        let val = Value::Array(vec![msgtype, Value::from(msgid),
+                                    Value::from(code), args]);
+        Message::from_msg(val).unwrap()
+    }
+
+    #[test]
+    fn differ_in_deep_arg()
+    {
+        // --------------------
+        // GIVEN
+        // 2 requests with the same id and code and
+        // args differing only in a deeply nested value
+        // --------------------
+        let first = deep_request(42, 1, 1);
+        let second = deep_request(42, 1, 2);
+
+        // --------------------
+        // WHEN
+        // Message::fast_ne() is called on the requests
+        // --------------------
+        let result = first.fast_ne(&second);
+
+        // --------------------
+        // THEN
+        // the pre-filter does not report a difference and
+        // full equality does
+        // --------------------
+        assert!(!result);
+        assert!(first != second);
+        assert!(!first.fast_ne(&deep_request(42, 1, 1)));
+        assert!(first == deep_request(42, 1, 1));
+    }
+
+    #[test]
+    fn differ_in_prefix()
+    {
+        // --------------------
+        // GIVEN
+        // a request and
+        // requests differing from it in id, code, or type
+        // --------------------
+        let msg = deep_request(42, 1, 1);
+        let others = [
+            deep_request(43, 1, 1),
+            deep_request(42, 2, 1),
+            Message::template(MessageType::Notification),
+        ];
+
+        // --------------------
+        // WHEN
+        // Message::fast_ne() is called with each other message
+        // THEN
+        // the pre-filter reports a difference
+        // --------------------
+        for other in others.iter() {
+            assert!(msg.fast_ne(other));
+            assert!(msg != *other);
+        }
+    }
+}
+
+
+mod diff
+{
+    // Third-party imports
+
+    use rmpv::Value;
+
+    // Local imports
+
+    use core::{CodeConvert, FromMessage, Message, MessageType};
+
+    // Build a request message with the given args
+    fn request(msgid: u32, args: Vec<Value>) -> Message
+    {
+        let msgtype = Value::from(MessageType::Request.to_number());
+        let val = Value::Array(vec![msgtype, Value::from(msgid),
+                                    Value::from(1), Value::Array(args)]);
+        Message::from_msg(val).unwrap()
+    }
+
+    #[test]
+    fn one_arg_differs()
+    {
+        // --------------------
+        // GIVEN
+        // 2 requests differing only in their 3rd arg
+        // --------------------
+        let first = request(42, vec![Value::from("a"), Value::Nil,
+                                     Value::from(42)]);
+        let second = request(42, vec![Value::from("a"), Value::Nil,
+                                      Value::from(43)]);
+
+        // --------------------
+        // WHEN
+        // Message::diff() is called on the requests
+        // --------------------
+        let result = first.diff(&second);
+
+        // --------------------
+        // THEN
+        // a single entry naming the 3rd arg is returned
+        // --------------------
+        assert_eq!(result, vec!["arg[2]: int(42) != int(43)".to_owned()]);
+    }
+
+    #[test]
+    fn several_differences()
+    {
+        // --------------------
+        // GIVEN
+        // 2 requests with different ids and
+        // a different number of args
+        // --------------------
+        let first = request(1, vec![Value::from("a")]);
+        let second = request(2, vec![Value::from("a"), Value::from(true)]);
+
+        // --------------------
+        // WHEN
+        // Message::diff() is called on the requests
+        // --------------------
+        let result = first.diff(&second);
+
+        // --------------------
+        // THEN
+        // the id and the missing arg are reported
+        // --------------------
+        let expected = vec![
+            "id: int(1) != int(2)".to_owned(),
+            "arg[1]: missing != bool(true)".to_owned(),
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn equal_or_different_type()
+    {
+        // --------------------
+        // GIVEN
+        // a request and
+        // an equal request and
+        // a notification
+        // --------------------
+        let req = request(42, vec![Value::from(1)]);
+        let same = request(42, vec![Value::from(1)]);
+        let notice = Message::template(MessageType::Notification);
+
+        // --------------------
+        // WHEN
+        // Message::diff() is called w/ the equal request and the
+        //    notification
+        // --------------------
+        let same_diff = req.diff(&same);
+        let notice_diff = req.diff(&notice);
+
+        // --------------------
+        // THEN
+        // there is no difference with the equal request and
+        // only the type is reported for the notification
+        // --------------------
+        assert!(same_diff.is_empty());
+        assert_eq!(notice_diff, vec!["type: int(0) != int(2)".to_owned()]);
+    }
+
+    #[test]
+    fn missing_args()
+    {
+        // --------------------
+        // GIVEN
+        // a valid 3 item request without args and
+        // a request with args
+        // --------------------
+        let msgtype = Value::from(MessageType::Request.to_number());
+        let val = Value::Array(vec![msgtype, Value::from(42), Value::from(1)]);
+        let short = Message::from_msg(val).unwrap();
+        let req = request(42, vec![Value::from(1)]);
+
+        // --------------------
+        // WHEN
+        // Message::diff() is called w/ the short request on either side
+        // --------------------
+        let left = short.diff(&req);
+        let right = req.diff(&short);
+        let same = short.diff(&short);
+
+        // --------------------
+        // THEN
+        // the args are reported as missing and
+        // there is no difference between the short request and itself
+        // --------------------
+        assert_eq!(left, vec!["args: missing != array([1])".to_owned()]);
+        assert_eq!(right, vec!["args: array([1]) != missing".to_owned()]);
+        assert!(same.is_empty());
+    }
+}
+
 // ===========================================================================
 //
 // ===========================================================================
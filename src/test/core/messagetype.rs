@@ -110,6 +110,66 @@ mod to_number
 }
 
 
+mod min_number
+{
+    // Local imports
+
+    use core::{CodeConvert, MessageType};
+
+    #[test]
+    fn starts_at_zero()
+    {
+        // --------------------
+        // GIVEN
+        // the MessageType enum whose first variant is 0
+        // --------------------
+        // --------------------
+        // WHEN
+        // MessageType::min_number() is called
+        // --------------------
+        let result = MessageType::min_number();
+
+        // --------------------
+        // THEN
+        // 0 is returned
+        // --------------------
+        assert_eq!(result, 0);
+    }
+}
+
+
+mod all
+{
+    // Local imports
+
+    use core::{CodeConvert, MessageType};
+
+    #[test]
+    fn every_variant_in_order()
+    {
+        // --------------------
+        // GIVEN
+        // the MessageType enum
+        // --------------------
+        // --------------------
+        // WHEN
+        // MessageType::all() is called
+        // --------------------
+        let result = MessageType::all();
+
+        // --------------------
+        // THEN
+        // every variant is returned ordered by value
+        // --------------------
+        let expected = vec![
+            MessageType::Request,
+            MessageType::Response,
+            MessageType::Notification,
+        ];
+        assert_eq!(result, expected);
+    }
+}
+
 // ===========================================================================
 //
 // ===========================================================================
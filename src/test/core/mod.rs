@@ -8,10 +8,18 @@
 // ===========================================================================
 
 
+mod any;
+mod chainedbuf;
 mod check_int;
+mod custom_codes;
+mod decodeoutcome;
+mod dynmessage;
+mod lengthprefixed;
 mod message;
 mod messagetype;
 mod notify;
+mod raw;
+mod readone;
 mod request;
 mod response;
 mod rpcmessage;
@@ -32,7 +40,7 @@ use rmpv::Value;
 use serde::Deserialize;
 
 // Local imports
-use core::{CodeConvert, CodeValueError};
+use core::{CodeConvert, CodeValueError, Message};
 
 
 // ===========================================================================
@@ -75,6 +83,20 @@ fn decode(buf: &mut BytesMut) -> Result<Value, decode::Error> {
 }
 
 
+// Return the value of msg with its message type slot overwritten by t.
+//
+// t does not need to be a valid message type, so this can be used to build
+// malformed input for tests of the error paths.
+fn with_type_byte(msg: Message, t: u8) -> Value
+{
+    let mut val = Value::from(msg);
+    if let Value::Array(ref mut items) = val {
+        items[0] = Value::from(t);
+    }
+    val
+}
+
+
 // ===========================================================================
 //
 // ===========================================================================
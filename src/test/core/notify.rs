@@ -80,6 +80,98 @@ mod new {
 }
 
 
+mod with_ref {
+    // Stdlib imports
+
+    // Third-party imports
+
+    use quickcheck::TestResult;
+    use rmpv::Value;
+
+    // Local imports
+
+    use core::notify::RpcNotice;
+
+    // Helpers
+    use super::{Notice, TestCode};
+
+    quickcheck! {
+        fn ref_is_first_arg(ref_id: u32, args: Vec<u8>) -> TestResult {
+            // --------------------
+            // GIVEN
+            // a u32 request id and
+            // a vec of arguments
+            // --------------------
+            let args: Vec<Value> =
+                args.iter().map(|v| Value::from(*v)).collect();
+
+            // --------------------
+            // WHEN
+            // NotificationMessage::with_ref() is called
+            // --------------------
+            let notice = Notice::with_ref(TestCode::One, ref_id, args.clone());
+
+            // --------------------
+            // THEN
+            // the notice's ref is the request id and
+            // the notice's args are the request id followed by the args
+            // --------------------
+            let msgargs = notice.message_args();
+            let val = notice.message_ref() == Some(ref_id) &&
+                msgargs.len() == args.len() + 1 &&
+                msgargs[0] == Value::from(ref_id) &&
+                msgargs[1..] == args[..];
+            TestResult::from_bool(val)
+        }
+    }
+
+    #[test]
+    fn no_args_no_ref()
+    {
+        // --------------------
+        // GIVEN
+        // a notice created without a ref and without arguments
+        // --------------------
+        let notice = Notice::new(TestCode::One, vec![]);
+
+        // --------------------
+        // WHEN
+        // RpcNotice::message_ref() is called
+        // --------------------
+        let result = notice.message_ref();
+
+        // --------------------
+        // THEN
+        // None is returned
+        // --------------------
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn non_id_first_arg_no_ref()
+    {
+        // --------------------
+        // GIVEN
+        // a notice created without a ref and
+        // the first argument is not an integer
+        // --------------------
+        let notice = Notice::new(TestCode::One, vec![Value::from("hello")]);
+
+        // --------------------
+        // WHEN
+        // RpcNotice::message_ref() is called
+        // --------------------
+        let result = notice.message_ref();
+
+        // --------------------
+        // THEN
+        // None is returned
+        // --------------------
+        assert_eq!(result, None);
+    }
+}
+
+
 mod from {
     // Stdlib imports
 
@@ -498,7 +590,8 @@ mod rpcnotice {
 
     // // Local imports
 
-    use core::{CodeConvert, FromMessage, Message, MessageType, RpcMessage};
+    use core::{CodeConvert, FromMessage, Message, MessageType, RpcMessage,
+               ToMessageError};
     use core::notify::RpcNotice;
 
     // Helpers
@@ -566,6 +659,75 @@ mod rpcnotice {
         // The contained value is as expected
         assert_eq!(result, expected)
     }
+
+    #[test]
+    fn arg_count() {
+        // --------------------
+        // GIVEN
+        // --------------------
+        // A notification message with 2 arguments
+        let args = vec![Value::from(1), Value::from(2)];
+        let notice = Notice::new(TestCode::One, args);
+
+        // --------------------
+        // WHEN
+        // --------------------
+        // NotificationMessage::arg_count() method is called
+        let result = notice.arg_count();
+
+        // --------------------
+        // THEN
+        // --------------------
+        // The number of arguments is returned
+        assert_eq!(result, 2);
+        assert_eq!(result, notice.message_args().len());
+    }
+
+    #[test]
+    fn arg_count_malformed() {
+        // A notification whose args slot is not guaranteed to be valid
+        struct RawNotice(Value);
+
+        impl RpcMessage for RawNotice {
+            type Err = ToMessageError;
+
+            fn as_vec(&self) -> &Vec<Value> {
+                self.0.as_array().unwrap()
+            }
+
+            fn as_value(&self) -> &Value {
+                &self.0
+            }
+        }
+
+        impl RpcNotice<TestCode> for RawNotice {}
+
+        // --------------------
+        // GIVEN
+        // --------------------
+        // A notification with a missing args slot and
+        // a notification with a non-array args slot
+        let msgtype = Value::from(MessageType::Notification.to_number());
+        let msgcode = Value::from(TestCode::One.to_number());
+        let missing =
+            RawNotice(Value::Array(vec![msgtype.clone(), msgcode.clone()]));
+        let notarray =
+            RawNotice(Value::Array(vec![msgtype, msgcode, Value::from(5)]));
+
+        // --------------------
+        // WHEN
+        // --------------------
+        // arg_count() is called on both notifications
+        let missing_count = missing.arg_count();
+        let notarray_count = notarray.arg_count();
+
+        // --------------------
+        // THEN
+        // --------------------
+        // 0 is returned for both
+        assert_eq!(missing_count, 0);
+        assert_eq!(notarray_count, 0);
+    }
 }
 
 
@@ -744,6 +906,222 @@ mod convert_bytes {
     }
 }
 
+mod parse_notification {
+    // Third-party imports
+
+    use rmpv::Value;
+
+    // Local imports
+
+    use core::{CodeConvert, FromMessage, Message, MessageType};
+    use core::notify::{parse_notification, NotifyPolicy, RpcNotice,
+                       ToNoticeError};
+
+    // Helpers
+    use super::{Notice, TestCode};
+
+    fn notice_msg(code: Value) -> Message {
+        let msgtype = Value::from(MessageType::Notification.to_number());
+        let val = Value::Array(vec![msgtype, code, Value::Array(vec![])]);
+        Message::from_msg(val).unwrap()
+    }
+
+    #[test]
+    fn known_code() {
+        // --------------------
+        // GIVEN
+        // a notification message with a known code and
+        // a policy that does not ignore unknown codes
+        // --------------------
+        let msg = notice_msg(Value::from(TestCode::Two.to_number()));
+        let policy = NotifyPolicy::default();
+
+        // --------------------
+        // WHEN
+        // parse_notification() is called
+        // --------------------
+        let result: Result<Option<Notice>, _> =
+            parse_notification(msg, &policy);
+
+        // --------------------
+        // THEN
+        // the notification is returned
+        // --------------------
+        let val = match result {
+            Ok(Some(notice)) => notice.message_code() == TestCode::Two,
+            _ => false,
+        };
+        assert!(val);
+    }
+
+    #[test]
+    fn unknown_code_ignored() {
+        // --------------------
+        // GIVEN
+        // notification messages with codes unknown to the code type and
+        // a policy that ignores unknown codes
+        // --------------------
+        let policy = NotifyPolicy {
+            ignore_unknown: true,
+        };
+
+        for code in &[3u64, 255, 9001] {
+            let msg = notice_msg(Value::from(*code));
+
+            // --------------------
+            // WHEN
+            // parse_notification() is called
+            // --------------------
+            let result: Result<Option<Notice>, _> =
+                parse_notification(msg, &policy);
+
+            // --------------------
+            // THEN
+            // no notification and no error is returned
+            // --------------------
+            let val = match result {
+                Ok(None) => true,
+                _ => false,
+            };
+            assert!(val);
+        }
+    }
+
+    #[test]
+    fn unknown_code_error() {
+        // --------------------
+        // GIVEN
+        // notification messages with codes unknown to the code type and
+        // a policy that does not ignore unknown codes
+        // --------------------
+        let policy = NotifyPolicy {
+            ignore_unknown: false,
+        };
+
+        for code in &[3u64, 255, 9001] {
+            let msg = notice_msg(Value::from(*code));
+
+            // --------------------
+            // WHEN
+            // parse_notification() is called
+            // --------------------
+            let result: Result<Option<Notice>, _> =
+                parse_notification(msg, &policy);
+
+            // --------------------
+            // THEN
+            // an invalid code error is returned
+            // --------------------
+            let val = match result {
+                Err(ToNoticeError::InvalidCode(_)) => true,
+                _ => false,
+            };
+            assert!(val);
+        }
+    }
+
+    #[test]
+    fn malformed_code_error() {
+        // --------------------
+        // GIVEN
+        // a notification message with a string code and
+        // a policy that ignores unknown codes
+        // --------------------
+        let msg = notice_msg(Value::from("one"));
+        let policy = NotifyPolicy {
+            ignore_unknown: true,
+        };
+
+        // --------------------
+        // WHEN
+        // parse_notification() is called
+        // --------------------
+        let result: Result<Option<Notice>, _> =
+            parse_notification(msg, &policy);
+
+        // --------------------
+        // THEN
+        // an invalid code error is returned
+        // --------------------
+        let val = match result {
+            Err(ToNoticeError::InvalidCode(_)) => true,
+            _ => false,
+        };
+        assert!(val);
+    }
+}
+
+
+mod notification_origin {
+    // Local imports
+
+    use core::{CodeConvert, CodeValueError};
+    use core::notify::{NotificationMessage, Origin, RpcNotice};
+
+    // Codes spanning the client, server, and unknown ranges
+    #[derive(Debug, PartialEq, Clone, CodeConvert)]
+    enum RangeCode {
+        FirstClient = 0,
+        LastClient = 127,
+        FirstServer = 128,
+        LastServer = 255,
+        Beyond = 256,
+    }
+
+    type RangeNotice = NotificationMessage<RangeCode>;
+
+    #[test]
+    fn code_ranges() {
+        // --------------------
+        // GIVEN
+        // notifications with codes at the edges of each range
+        // --------------------
+        let cases = vec![
+            (RangeCode::FirstClient, Origin::Client),
+            (RangeCode::LastClient, Origin::Client),
+            (RangeCode::FirstServer, Origin::Server),
+            (RangeCode::LastServer, Origin::Server),
+            (RangeCode::Beyond, Origin::Unknown),
+        ];
+
+        for (code, expected) in cases {
+            let notice = RangeNotice::new(code, vec![]);
+
+            // --------------------
+            // WHEN
+            // NotificationMessage::notification_origin() is called
+            // --------------------
+            let result = notice.notification_origin();
+
+            // --------------------
+            // THEN
+            // the origin for the code's range is returned
+            // --------------------
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test]
+    fn from_code() {
+        // --------------------
+        // GIVEN
+        // raw code numbers in each range
+        // --------------------
+        // --------------------
+        // WHEN
+        // Origin::from_code() is called
+        // --------------------
+        // --------------------
+        // THEN
+        // the origin for the code's range is returned
+        // --------------------
+        assert_eq!(Origin::from_code(42), Origin::Client);
+        assert_eq!(Origin::from_code(200), Origin::Server);
+        assert_eq!(Origin::from_code(u64::max_value()), Origin::Unknown);
+    }
+}
+
+
 // ===========================================================================
 //
 // ===========================================================================
@@ -0,0 +1,521 @@
+// src/test/core/raw.rs
+// Copyright (C) 2017 authors and contributors (see AUTHORS file)
+//
+// This file is released under the MIT License.
+
+// ===========================================================================
+// Imports
+// ===========================================================================
+
+
+// Stdlib imports
+
+// Third-party imports
+
+use bytes::Bytes;
+use quickcheck::TestResult;
+use rmpv::Value;
+
+// Local imports
+
+use core::{AsBytes, CodeConvert, FromMessage, Message, MessageType,
+           RpcMessage};
+use core::raw::{decode_prefix, tally_types, AnyRawMessage, LazyArgsError,
+                LazyMessage, PrefixError, TallyError, ToRawMessageError};
+
+
+// ===========================================================================
+// Tests
+// ===========================================================================
+
+
+quickcheck! {
+    fn request_raw_parts(msgid: u32, code: u64) -> TestResult
+    {
+        // --------------------
+        // GIVEN
+        // a request message with an arbitrary id and code
+        // --------------------
+        let msgtype = Value::from(MessageType::Request.to_number());
+        let val = Value::Array(vec![msgtype, Value::from(msgid),
+                                    Value::from(code), Value::Array(vec![])]);
+        let msg = Message::from_msg(val).unwrap();
+        let expected = msg.clone();
+
+        // --------------------
+        // WHEN
+        // AnyRawMessage::from_msg() is called with the message
+        // --------------------
+        let result = AnyRawMessage::from_msg(msg);
+
+        // --------------------
+        // THEN
+        // a raw request is returned with the message's id and code
+        // --------------------
+        let val = match result {
+            Ok(raw @ AnyRawMessage::Request { .. }) => {
+                raw.message_type() == MessageType::Request
+                    && raw.message_id() == Some(msgid)
+                    && raw.code() == code
+                    && raw.message() == &expected
+            }
+            _ => false,
+        };
+        TestResult::from_bool(val)
+    }
+
+    fn notification_raw_parts(code: u64) -> TestResult
+    {
+        // --------------------
+        // GIVEN
+        // a notification message with an arbitrary code
+        // --------------------
+        let msgtype = Value::from(MessageType::Notification.to_number());
+        let val = Value::Array(vec![msgtype, Value::from(code),
+                                    Value::Array(vec![])]);
+        let msg = Message::from_msg(val).unwrap();
+
+        // --------------------
+        // WHEN
+        // AnyRawMessage::from_msg() is called with the message
+        // --------------------
+        let result = AnyRawMessage::from_msg(msg);
+
+        // --------------------
+        // THEN
+        // a raw notification is returned with the message's code and
+        // no id
+        // --------------------
+        let val = match result {
+            Ok(raw @ AnyRawMessage::Notification { .. }) => {
+                raw.message_type() == MessageType::Notification
+                    && raw.message_id().is_none()
+                    && raw.code() == code
+            }
+            _ => false,
+        };
+        TestResult::from_bool(val)
+    }
+}
+
+
+#[test]
+fn response_into_message()
+{
+    // --------------------
+    // GIVEN
+    // a raw response
+    // --------------------
+    let msgtype = Value::from(MessageType::Response.to_number());
+    let val = Value::Array(vec![msgtype, Value::from(42), Value::from(5),
+                                Value::Nil]);
+    let msg = Message::from_msg(val).unwrap();
+    let expected = msg.clone();
+    let raw = AnyRawMessage::from_msg(msg).unwrap();
+    assert_eq!(raw.message_type(), MessageType::Response);
+
+    // --------------------
+    // WHEN
+    // the raw response is converted back into a Message
+    // --------------------
+    let result = Message::from(raw);
+
+    // --------------------
+    // THEN
+    // the original message is returned
+    // --------------------
+    assert_eq!(result, expected);
+}
+
+
+#[test]
+fn length_mismatch()
+{
+    // --------------------
+    // GIVEN
+    // a request message with only 3 items
+    // --------------------
+    let msgtype = Value::from(MessageType::Request.to_number());
+    let val = Value::Array(vec![msgtype, Value::from(42), Value::from(5)]);
+    let msg = Message::from_msg(val).unwrap();
+
+    // --------------------
+    // WHEN
+    // AnyRawMessage::from_msg() is called with the message
+    // --------------------
+    let result = AnyRawMessage::from_msg(msg);
+
+    // --------------------
+    // THEN
+    // an array length error is returned
+    // --------------------
+    match result {
+        Err(ToRawMessageError::ArrayLength {
+            expected: 4, len: 3, ..
+        }) => {}
+        _ => panic!("expected an array length error"),
+    }
+}
+
+
+#[test]
+fn id_too_big()
+{
+    // --------------------
+    // GIVEN
+    // a request message with an id that does not fit in a u32
+    // --------------------
+    let msgtype = Value::from(MessageType::Request.to_number());
+    let msgid = Value::from(u64::from(u32::max_value()) + 1);
+    let val = Value::Array(vec![msgtype, msgid, Value::from(5),
+                                Value::Array(vec![])]);
+    let msg = Message::from_msg(val).unwrap();
+
+    // --------------------
+    // WHEN
+    // AnyRawMessage::from_msg() is called with the message
+    // --------------------
+    let result = AnyRawMessage::from_msg(msg);
+
+    // --------------------
+    // THEN
+    // an invalid id error is returned
+    // --------------------
+    match result {
+        Err(ToRawMessageError::InvalidID(_)) => {}
+        _ => panic!("expected an invalid id error"),
+    }
+}
+
+
+#[test]
+fn prefix_truncated_args()
+{
+    // --------------------
+    // GIVEN
+    // the bytes of a request with a code of 256 and
+    // an args array claiming u32::MAX items that is cut off after its header
+    // --------------------
+    let buf = [0x94, 0x00, 0x2a, 0xcd, 0x01, 0x00, 0xdd, 0xff, 0xff, 0xff,
+               0xff];
+
+    // --------------------
+    // WHEN
+    // decode_prefix() is called with the bytes
+    // --------------------
+    let result = decode_prefix(&buf[..]);
+
+    // --------------------
+    // THEN
+    // the request type and code are returned
+    // --------------------
+    let (msgtype, code) = result.unwrap();
+    assert_eq!(msgtype, MessageType::Request);
+    assert_eq!(code, 256);
+}
+
+
+#[test]
+fn prefix_notification()
+{
+    // --------------------
+    // GIVEN
+    // the bytes of a notification with a code of 7 and
+    // no args bytes
+    // --------------------
+    let buf = [0x93, 0x02, 0x07];
+
+    // --------------------
+    // WHEN
+    // decode_prefix() is called with the bytes
+    // --------------------
+    let result = decode_prefix(&buf[..]);
+
+    // --------------------
+    // THEN
+    // the notification type and code are returned
+    // --------------------
+    let (msgtype, code) = result.unwrap();
+    assert_eq!(msgtype, MessageType::Notification);
+    assert_eq!(code, 7);
+}
+
+
+#[test]
+fn prefix_errors()
+{
+    // --------------------
+    // GIVEN
+    // bytes that are not an array and
+    // bytes of an empty array and
+    // bytes with an unknown message type and
+    // bytes of a request array of the wrong length and
+    // bytes of a request with an id that does not fit in a u32 and
+    // bytes of a response cut off before its code
+    // --------------------
+    let not_array = [0x2a];
+    let empty = [0x90];
+    let unknown = [0x94, 0x03, 0x2a, 0x01];
+    let wrong_len = [0x93, 0x00, 0x2a, 0x01];
+    let big_id = [0x94, 0x00, 0xcf, 0, 0, 0, 0x01, 0, 0, 0, 0, 0x01];
+    let no_code = [0x94, 0x01, 0x2a];
+
+    // --------------------
+    // WHEN
+    // decode_prefix() is called with each set of bytes
+    // THEN
+    // the matching error is returned
+    // --------------------
+    match decode_prefix(&not_array[..]) {
+        Err(PrefixError::ArrayHeader(_)) => {}
+        _ => panic!("expected an array header error"),
+    }
+    match decode_prefix(&empty[..]) {
+        Err(PrefixError::EmptyArray) => {}
+        _ => panic!("expected an empty array error"),
+    }
+    match decode_prefix(&unknown[..]) {
+        Err(PrefixError::UnknownType(_)) => {}
+        _ => panic!("expected an unknown type error"),
+    }
+    match decode_prefix(&wrong_len[..]) {
+        Err(PrefixError::ArrayLength {
+            msgtype: MessageType::Request,
+            expected: 4,
+            len: 3,
+        }) => {}
+        _ => panic!("expected an array length error"),
+    }
+    match decode_prefix(&big_id[..]) {
+        Err(PrefixError::InvalidID(_)) => {}
+        _ => panic!("expected an invalid id error"),
+    }
+    match decode_prefix(&no_code[..]) {
+        Err(PrefixError::InvalidCode(_)) => {}
+        _ => panic!("expected an invalid code error"),
+    }
+}
+
+
+quickcheck! {
+    fn prefix_matches_raw_message(msgtype: u8, msgid: u32, code: u64)
+        -> bool
+    {
+        // --------------------
+        // GIVEN
+        // the encoded bytes of a message with a valid type, id, and code
+        // --------------------
+        let msgtype = MessageType::from_number(msgtype % 3).unwrap();
+        let mut items = vec![Value::from(msgtype.to_number())];
+        if msgtype != MessageType::Notification {
+            items.push(Value::from(msgid));
+        }
+        items.push(Value::from(code));
+        items.push(Value::Array(vec![Value::from(42)]));
+        let msg = Message::from_msg(Value::Array(items)).unwrap();
+        let buf = msg.canonical_bytes();
+
+        // --------------------
+        // WHEN
+        // decode_prefix() is called with the bytes
+        // --------------------
+        let result = decode_prefix(&buf[..]).unwrap();
+
+        // --------------------
+        // THEN
+        // the type and code match the message's AnyRawMessage view
+        // --------------------
+        let raw = AnyRawMessage::from_msg(msg).unwrap();
+        result == (raw.message_type(), raw.code())
+    }
+}
+
+
+
+#[test]
+fn lazy_header_without_args()
+{
+    // --------------------
+    // GIVEN
+    // the bytes of a request with a code of 256 and
+    // an args array that is cut off after its header
+    // --------------------
+    let buf = Bytes::from(&[0x94, 0x00, 0x2a, 0xcd, 0x01, 0x00, 0x93][..]);
+
+    // --------------------
+    // WHEN
+    // a LazyMessage is created from the bytes
+    // --------------------
+    let result = LazyMessage::new(buf);
+
+    // --------------------
+    // THEN
+    // the request type, id, and code are available and
+    // the args have not been decoded and
+    // decoding the args fails
+    // --------------------
+    let msg = result.unwrap();
+    assert_eq!(msg.message_type(), MessageType::Request);
+    assert_eq!(msg.message_id(), Some(42));
+    assert_eq!(msg.code(), 256);
+    assert!(!msg.args_decoded());
+
+    match msg.args() {
+        Err(LazyArgsError::Decode(_)) => {}
+        _ => panic!("expected a decode error"),
+    }
+    assert!(!msg.args_decoded());
+}
+
+
+#[test]
+fn lazy_args_decoded_once()
+{
+    // --------------------
+    // GIVEN
+    // the bytes of a notification with args
+    // --------------------
+    let args = vec![Value::from("hello"), Value::Array(vec![Value::from(42)])];
+    let notice = Value::Array(vec![
+        Value::from(MessageType::Notification.to_number()),
+        Value::from(7),
+        Value::Array(args.clone()),
+    ]);
+    let buf = Message::from_msg(notice).unwrap().as_bytes();
+
+    // --------------------
+    // WHEN
+    // a LazyMessage is created from the bytes and
+    // LazyMessage::args() is called twice
+    // --------------------
+    let msg = LazyMessage::new(buf).unwrap();
+    let first = msg.args().unwrap().as_ptr();
+    let second = msg.args().unwrap();
+
+    // --------------------
+    // THEN
+    // the notification's type and code are available and
+    // the args are decoded and
+    // the 2nd call returns the args kept by the 1st call
+    // --------------------
+    assert_eq!(msg.message_type(), MessageType::Notification);
+    assert_eq!(msg.message_id(), None);
+    assert_eq!(msg.code(), 7);
+    assert!(msg.args_decoded());
+    assert_eq!(second, &args[..]);
+    assert_eq!(first, second.as_ptr());
+}
+
+
+#[test]
+fn lazy_args_not_array()
+{
+    // --------------------
+    // GIVEN
+    // the bytes of a response whose result is not an array
+    // --------------------
+    let buf = Bytes::from(&[0x94, 0x01, 0x2a, 0x05, 0xc0][..]);
+
+    // --------------------
+    // WHEN
+    // LazyMessage::args() is called
+    // --------------------
+    let msg = LazyMessage::new(buf).unwrap();
+    let result = msg.args();
+
+    // --------------------
+    // THEN
+    // a not array error is returned
+    // --------------------
+    match result {
+        Err(LazyArgsError::NotArray(_)) => {}
+        _ => panic!("expected a not array error"),
+    }
+}
+
+
+#[test]
+fn tally_message_types()
+{
+    // --------------------
+    // GIVEN
+    // a buffer holding 2 requests and 1 notification, one after the other
+    // --------------------
+    let args = vec![Value::from("hello"), Value::Array(vec![Value::from(42)])];
+    let messages = vec![
+        Value::Array(vec![
+            Value::from(MessageType::Request.to_number()),
+            Value::from(1),
+            Value::from(9),
+            Value::Array(args.clone()),
+        ]),
+        Value::Array(vec![
+            Value::from(MessageType::Notification.to_number()),
+            Value::from(7),
+            Value::Array(args.clone()),
+        ]),
+        Value::Array(vec![
+            Value::from(MessageType::Request.to_number()),
+            Value::from(2),
+            Value::from(9),
+            Value::Array(vec![]),
+        ]),
+    ];
+    let mut buf = Vec::new();
+    for val in messages {
+        let msg = Message::from_msg(val).unwrap();
+        buf.extend_from_slice(&msg.as_bytes()[..]);
+    }
+
+    // --------------------
+    // WHEN
+    // tally_types() is called with the buffer
+    // --------------------
+    let result = tally_types(&buf[..]);
+
+    // --------------------
+    // THEN
+    // 2 requests and 1 notification are counted and
+    // no responses are counted
+    // --------------------
+    let tally = result.unwrap();
+    assert_eq!(tally.len(), 2);
+    assert_eq!(tally[&MessageType::Request], 2);
+    assert_eq!(tally[&MessageType::Notification], 1);
+    assert!(!tally.contains_key(&MessageType::Response));
+}
+
+
+#[test]
+fn tally_errors()
+{
+    // --------------------
+    // GIVEN
+    // a notification followed by a request whose args are cut off and
+    // a notification followed by a message with an unknown type
+    // --------------------
+    let truncated = [0x93, 0x02, 0x01, 0x90, 0x94, 0x00, 0x2a, 0x09, 0x91];
+    let unknown = [0x93, 0x02, 0x01, 0x90, 0x93, 0x05, 0x01, 0x90];
+
+    // --------------------
+    // WHEN
+    // tally_types() is called with each buffer
+    // THEN
+    // the error holds the offset of the 2nd message
+    // --------------------
+    match tally_types(&truncated[..]) {
+        Err(TallyError::Truncated(4)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    match tally_types(&unknown[..]) {
+        Err(TallyError::Prefix {
+            err: PrefixError::UnknownType(_),
+            offset: 4,
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+// ===========================================================================
+//
+// ===========================================================================
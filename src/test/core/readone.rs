@@ -0,0 +1,189 @@
+// src/test/core/readone.rs
+// Copyright (C) 2017 authors and contributors (see AUTHORS file)
+//
+// This file is released under the MIT License.
+
+// ===========================================================================
+// Imports
+// ===========================================================================
+
+
+// Stdlib imports
+
+use std::io::{self, Cursor};
+
+// Third-party imports
+
+use rmpv::Value;
+
+// Local imports
+
+use core::{read_one, AsBytes, FromBytesError, FromMessage, Message,
+           RpcMessage};
+use core::request::{RequestMessage, RpcRequest, ToRequestError};
+
+// Helpers
+use super::TestEnum;
+
+
+// ===========================================================================
+// Helpers
+// ===========================================================================
+
+
+type Request = RequestMessage<TestEnum>;
+
+
+// ===========================================================================
+// Tests
+// ===========================================================================
+
+
+#[test]
+fn request_from_cursor()
+{
+    // --------------------
+    // GIVEN
+    // a cursor holding the bytes of a request message
+    // --------------------
+    let req = Request::new(42, TestEnum::Two, vec![Value::from("hello")]);
+    let mut cursor = Cursor::new(req.as_bytes().to_vec());
+
+    // --------------------
+    // WHEN
+    // read_one() is called with the cursor
+    // --------------------
+    let result: Result<Request, _> = read_one(&mut cursor);
+
+    // --------------------
+    // THEN
+    // the request is returned and
+    // every byte of the cursor has been read
+    // --------------------
+    let result = result.unwrap();
+    assert_eq!(result.message_id(), 42);
+    assert_eq!(result.message_method(), TestEnum::Two);
+    assert_eq!(result.as_value(), req.as_value());
+    assert_eq!(cursor.position() as usize, cursor.get_ref().len());
+}
+
+
+#[test]
+fn trailing_data_left_unread()
+{
+    // --------------------
+    // GIVEN
+    // a cursor holding the bytes of two request messages
+    // --------------------
+    let first = Request::new(1, TestEnum::One, vec![]);
+    let second = Request::new(2, TestEnum::Three, vec![Value::from(42)]);
+    let mut data = first.as_bytes().to_vec();
+    data.extend_from_slice(&second.as_bytes()[..]);
+    let mut cursor = Cursor::new(data);
+
+    // --------------------
+    // WHEN
+    // read_one() is called twice with the cursor
+    // --------------------
+    let result1: Request = read_one(&mut cursor).unwrap();
+    let result2: Request = read_one(&mut cursor).unwrap();
+
+    // --------------------
+    // THEN
+    // each call returns one request in order
+    // --------------------
+    assert_eq!(result1.as_value(), first.as_value());
+    assert_eq!(result2.as_value(), second.as_value());
+}
+
+
+#[test]
+fn truncated_message()
+{
+    // --------------------
+    // GIVEN
+    // a cursor holding all but the last byte of a request message
+    // --------------------
+    let req = Request::new(42, TestEnum::Two, vec![Value::from("hello")]);
+    let mut data = req.as_bytes().to_vec();
+    data.pop();
+    let mut cursor = Cursor::new(data);
+
+    // --------------------
+    // WHEN
+    // read_one() is called with the cursor
+    // --------------------
+    let result: Result<Request, _> = read_one(&mut cursor);
+
+    // --------------------
+    // THEN
+    // an unexpected eof io error is returned
+    // --------------------
+    match result {
+        Err(FromBytesError::InvalidDataRead(e)) => {
+            assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof)
+        }
+        _ => panic!("expected an io error"),
+    }
+}
+
+
+#[test]
+fn wrong_message_type()
+{
+    // --------------------
+    // GIVEN
+    // a cursor holding the bytes of a notification message
+    // --------------------
+    let msgtype = Value::from(2);
+    let val = Value::Array(vec![msgtype, Value::from(0), Value::Array(vec![])]);
+    let msg = Message::from_msg(val).unwrap();
+    let mut cursor = Cursor::new(msg.as_bytes().to_vec());
+
+    // --------------------
+    // WHEN
+    // read_one() is called with the cursor expecting a request
+    // --------------------
+    let result: Result<Request, _> = read_one(&mut cursor);
+
+    // --------------------
+    // THEN
+    // an invalid message error is returned
+    // --------------------
+    match result {
+        Err(FromBytesError::InvalidMessage(ToRequestError::ArrayLength(3))) => {
+        }
+        _ => panic!("expected an invalid message error"),
+    }
+}
+
+
+#[test]
+fn nesting_too_deep()
+{
+    // --------------------
+    // GIVEN
+    // a cursor holding deeply nested arrays
+    // --------------------
+    let mut cursor = Cursor::new(vec![0x91; 2048]);
+
+    // --------------------
+    // WHEN
+    // read_one() is called with the cursor
+    // --------------------
+    let result: Result<Request, _> = read_one(&mut cursor);
+
+    // --------------------
+    // THEN
+    // a depth limit error is returned
+    // --------------------
+    match result {
+        Err(FromBytesError::DepthLimitExceeded { .. }) => {}
+        _ => panic!("expected a depth limit error"),
+    }
+}
+
+
+// ===========================================================================
+//
+// ===========================================================================
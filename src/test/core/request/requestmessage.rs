@@ -90,6 +90,7 @@ mod from
 
     // Helpers
     use super::TestEnum;
+    use test::core::with_type_byte;
 
     #[test]
     fn invalid_arraylen()
@@ -134,15 +135,13 @@ mod from
         // --------------------
         // GIVEN
         // --------------------
-        // Message with MessageType::Notification
+        // Request message with its type changed to
+        // MessageType::Notification
 
         // Create message
-        let msgtype = Value::from(MessageType::Notification.to_number());
-        let msgid = Value::from(42);
-        let msgmeth = Value::from(TestEnum::One.to_number());
-        let msgval = Value::from(42);
-
-        let val = Value::Array(vec![msgtype, msgid, msgmeth, msgval]);
+        let req = RequestMessage::new(42, TestEnum::One, vec![]);
+        let msgtype = MessageType::Notification.to_number();
+        let val = with_type_byte(req.into(), msgtype);
         let msg = Message::from_msg(val).unwrap();
 
         // --------------------
@@ -682,8 +681,501 @@ mod convert_bytes {
 
         assert!(val);
     }
+
+    #[test]
+    fn deserialize_oversized_length() {
+        // --------------------
+        // GIVEN
+        // a BytesMut buffer holding a str32 marker that claims 4GiB of data
+        // followed by a single byte
+        // --------------------
+        let data = vec![0xdb, 0xff, 0xff, 0xff, 0xff, 0x61];
+        let mut buf = BytesMut::from(data);
+
+        // --------------------
+        // WHEN
+        // RequestMessage::from_bytes() is called with the buffer
+        // --------------------
+        let result = Request::from_bytes(&mut buf);
+
+        // --------------------
+        // THEN
+        // None is returned
+        // --------------------
+        let val = match result {
+            Ok(None) => true,
+            _ => false,
+        };
+
+        assert!(val);
+    }
+
+    #[test]
+    fn deserialize_error_offset() {
+        // --------------------
+        // GIVEN
+        // a BytesMut buffer holding a request whose method code slot, at
+        // byte 3, holds the reserved 0xc1 marker
+        // --------------------
+        let data = vec![0x94, 0x00, 0x2a, 0xc1, 0x90];
+        let mut buf = BytesMut::from(data);
+
+        // --------------------
+        // WHEN
+        // RequestMessage::from_bytes() is called with the buffer
+        // --------------------
+        let result = Request::from_bytes(&mut buf);
+
+        // --------------------
+        // THEN
+        // a type mismatch error is returned and
+        // the error reports the decoder offset just past the bad marker
+        // --------------------
+        let err = result.unwrap_err();
+        assert_eq!(err.offset(), Some(4));
+        let val = match err {
+            FromBytesError::TypeMismatch { offset: 4, .. } => true,
+            _ => false,
+        };
+        assert!(val);
+        assert_eq!(err.to_string(), "MsgPack error at byte 4: type mismatch");
+    }
+
+    #[test]
+    fn deserialize_nesting_too_deep() {
+        // --------------------
+        // GIVEN
+        // a BytesMut buffer holding 1000 nested single item arrays
+        // --------------------
+        let mut data = vec![0x91; 1000];
+        data.push(0xc0);
+        let mut buf = BytesMut::from(data);
+
+        // --------------------
+        // WHEN
+        // RequestMessage::from_bytes() is called with the buffer
+        // --------------------
+        let result = Request::from_bytes(&mut buf);
+
+        // --------------------
+        // THEN
+        // a depth limit error is returned
+        // --------------------
+        let val = match result {
+            Err(FromBytesError::DepthLimitExceeded { .. }) => true,
+            _ => false,
+        };
+
+        assert!(val);
+    }
+}
+
+
+mod take_string_arg {
+    // Third-party imports
+
+    use rmpv::Value;
+
+    // Local imports
+
+    use core::request::{RequestMessage, RpcRequest};
+
+    // Helpers
+    use super::TestEnum;
+
+    type Request = RequestMessage<TestEnum>;
+
+    #[test]
+    fn take_username()
+    {
+        // --------------------
+        // GIVEN
+        // a request with a username string argument
+        // --------------------
+        let args = vec![Value::from(1), Value::from("username")];
+        let mut req = Request::new(42, TestEnum::One, args);
+
+        // --------------------
+        // WHEN
+        // RequestMessage::take_string_arg() is called with the index of the
+        //    username argument
+        // --------------------
+        let result = req.take_string_arg(1);
+
+        // --------------------
+        // THEN
+        // the username is returned and
+        // the argument is replaced with Nil and
+        // the other arguments are unchanged
+        // --------------------
+        assert_eq!(result, Some("username".to_string()));
+        assert_eq!(req.message_args(), &vec![Value::from(1), Value::Nil]);
+    }
+
+    #[test]
+    fn not_a_string()
+    {
+        // --------------------
+        // GIVEN
+        // a request with a single integer argument
+        // --------------------
+        let args = vec![Value::from(1)];
+        let mut req = Request::new(42, TestEnum::One, args);
+        let expected = req.clone();
+
+        // --------------------
+        // WHEN
+        // RequestMessage::take_string_arg() is called with the index of the
+        //    integer argument and
+        //    with an index past the last argument
+        // --------------------
+        let result = req.take_string_arg(0);
+        let missing = req.take_string_arg(1);
+
+        // --------------------
+        // THEN
+        // None is returned both times and
+        // the request is unchanged
+        // --------------------
+        assert_eq!(result, None);
+        assert_eq!(missing, None);
+        assert_eq!(req, expected);
+    }
+}
+
+mod rebuild_with_id {
+    // Third-party imports
+
+    use quickcheck::TestResult;
+    use rmpv::Value;
+
+    // Local imports
+
+    use core::request::{RequestMessage, RpcRequest};
+
+    // Helpers
+    use super::TestEnum;
+
+    type Request = RequestMessage<TestEnum>;
+
+    quickcheck! {
+        fn new_id(msgid: u32, new_id: u32, args: Vec<u64>) -> TestResult
+        {
+            // --------------------
+            // GIVEN
+            // a request with a message id and
+            // a new message id
+            // --------------------
+            let args: Vec<Value> = args.into_iter().map(Value::from).collect();
+            let req = Request::new(msgid, TestEnum::Two, args);
+
+            // --------------------
+            // WHEN
+            // RequestMessage::rebuild_with_id() is called with the new id
+            // --------------------
+            let result = req.rebuild_with_id(new_id);
+
+            // --------------------
+            // THEN
+            // the rebuilt request has the new id and
+            // the same code and args as the original request
+            // --------------------
+            let val = result.message_id() == new_id
+                && result.message_method() == req.message_method()
+                && result.message_args() == req.message_args();
+            TestResult::from_bool(val)
+        }
+    }
+}
+
+mod min_number
+{
+    // Third-party imports
+
+    use rmpv::Value;
+
+    // Local imports
+
+    use core::{CodeConvert, FromMessage, Message, MessageType};
+    use core::request::{RequestCodeError, ToRequestError};
+    use message::v1::{Request, RequestCode, ResponseCode};
+
+    #[test]
+    fn v1_codes()
+    {
+        // --------------------
+        // GIVEN
+        // the v1 request and response codes
+        // --------------------
+        // --------------------
+        // WHEN
+        // min_number() is called on each
+        // --------------------
+        // --------------------
+        // THEN
+        // the smallest discriminant is returned
+        // --------------------
+        assert_eq!(RequestCode::min_number(), 4);
+        assert_eq!(ResponseCode::min_number(), 5);
+    }
+
+    #[test]
+    fn code_below_min()
+    {
+        // --------------------
+        // GIVEN
+        // a message with a v1 request method below RequestCode::min_number()
+        // --------------------
+        let msgtype = Value::from(MessageType::Request.to_number());
+        let msgid = Value::from(42);
+        let msgmeth = Value::from(RequestCode::min_number() - 1);
+        let msgargs = Value::Array(vec![]);
+        let val = Value::Array(vec![msgtype, msgid, msgmeth, msgargs]);
+        let msg = Message::from_msg(val).unwrap();
+
+        // --------------------
+        // WHEN
+        // the message is converted into a v1 request
+        // --------------------
+        let result = Request::from_msg(msg);
+
+        // --------------------
+        // THEN
+        // an invalid code error is returned
+        // --------------------
+        let val = match result {
+            Err(ToRequestError::InvalidCode(RequestCodeError::ToCode(3))) => {
+                true
+            }
+            _ => false,
+        };
+        assert!(val);
+    }
+}
+
+mod from_raw_args
+{
+    // Third-party imports
+
+    use quickcheck::TestResult;
+    use rmpv::Value;
+    use rmpv::encode::write_value;
+
+    // Local imports
+
+    use core::RpcMessage;
+    use core::request::{FromRawArgsError, RequestMessage};
+
+    // Helpers
+    use super::TestEnum;
+
+    type Request = RequestMessage<TestEnum>;
+
+    quickcheck! {
+        fn same_as_new(msgid: u32, args: Vec<u64>) -> TestResult
+        {
+            // --------------------
+            // GIVEN
+            // a vector of args and
+            // the same args serialized as a msgpack array
+            // --------------------
+            let args: Vec<Value> = args.into_iter().map(Value::from).collect();
+            let mut raw = Vec::new();
+            write_value(&mut raw, &Value::Array(args.clone())).unwrap();
+
+            // --------------------
+            // WHEN
+            // RequestMessage::from_raw_args() is called with the raw bytes
+            // --------------------
+            let result = Request::from_raw_args(msgid, TestEnum::Two, &raw);
+
+            // --------------------
+            // THEN
+            // the request equals one built from the vector of args
+            // --------------------
+            let expected = Request::new(msgid, TestEnum::Two, args);
+            let val = match result {
+                Ok(req) => req.as_value() == expected.as_value(),
+                Err(_) => false,
+            };
+            TestResult::from_bool(val)
+        }
+    }
+
+    #[test]
+    fn not_array()
+    {
+        // --------------------
+        // GIVEN
+        // raw bytes holding an integer
+        // --------------------
+        let raw = [0x2a];
+
+        // --------------------
+        // WHEN
+        // RequestMessage::from_raw_args() is called with the raw bytes
+        // --------------------
+        let result = Request::from_raw_args(42, TestEnum::One, &raw);
+
+        // --------------------
+        // THEN
+        // an invalid args error is returned
+        // --------------------
+        let val = match result {
+            Err(FromRawArgsError::InvalidArgs(_)) => true,
+            _ => false,
+        };
+        assert!(val);
+    }
+
+    #[test]
+    fn truncated()
+    {
+        // --------------------
+        // GIVEN
+        // raw bytes holding an array header promising 2 items and
+        // only 1 item
+        // --------------------
+        let raw = [0x92, 0x2a];
+
+        // --------------------
+        // WHEN
+        // RequestMessage::from_raw_args() is called with the raw bytes
+        // --------------------
+        let result = Request::from_raw_args(42, TestEnum::One, &raw);
+
+        // --------------------
+        // THEN
+        // a decode error is returned
+        // --------------------
+        let val = match result {
+            Err(FromRawArgsError::Decode(_)) => true,
+            _ => false,
+        };
+        assert!(val);
+    }
+
+    #[test]
+    fn trailing_bytes()
+    {
+        // --------------------
+        // GIVEN
+        // raw bytes holding an array followed by 2 extra bytes
+        // --------------------
+        let raw = [0x91, 0x2a, 0x01, 0x02];
+
+        // --------------------
+        // WHEN
+        // RequestMessage::from_raw_args() is called with the raw bytes
+        // --------------------
+        let result = Request::from_raw_args(42, TestEnum::One, &raw);
+
+        // --------------------
+        // THEN
+        // a trailing bytes error is returned
+        // --------------------
+        let val = match result {
+            Err(FromRawArgsError::TrailingBytes(2)) => true,
+            _ => false,
+        };
+        assert!(val);
+    }
+
+    #[test]
+    fn nesting_too_deep()
+    {
+        // --------------------
+        // GIVEN
+        // raw bytes holding deeply nested arrays
+        // --------------------
+        let raw = vec![0x91; 2048];
+
+        // --------------------
+        // WHEN
+        // RequestMessage::from_raw_args() is called with the raw bytes
+        // --------------------
+        let result = Request::from_raw_args(42, TestEnum::One, &raw);
+
+        // --------------------
+        // THEN
+        // a depth limit error is returned
+        // --------------------
+        let val = match result {
+            Err(FromRawArgsError::DepthLimitExceeded(_)) => true,
+            _ => false,
+        };
+        assert!(val);
+    }
 }
 
+mod with_capacity
+{
+    // Third-party imports
+
+    use rmpv::Value;
+
+    // Local imports
+
+    use core::RpcMessage;
+    use core::request::RequestMessage;
+
+    // Helpers
+    use super::TestEnum;
+
+    type Request = RequestMessage<TestEnum>;
+
+    #[test]
+    fn same_as_new()
+    {
+        // --------------------
+        // GIVEN
+        // 1000 arguments and
+        // a request builder with capacity for 1000 arguments
+        // --------------------
+        let args: Vec<Value> = (0..1000).map(Value::from).collect();
+        let mut builder = Request::with_capacity(42, TestEnum::Three, 1000);
+
+        // --------------------
+        // WHEN
+        // each argument is pushed and
+        // RequestArgsBuilder::finish() is called
+        // --------------------
+        for arg in &args {
+            builder.push_arg(arg.clone());
+        }
+        let result = builder.finish();
+
+        // --------------------
+        // THEN
+        // the request equals one built with RequestMessage::new()
+        // --------------------
+        let expected = Request::new(42, TestEnum::Three, args);
+        assert_eq!(result.as_value(), expected.as_value());
+    }
+
+    #[test]
+    fn no_args()
+    {
+        // --------------------
+        // GIVEN
+        // a request builder with capacity for 10 arguments
+        // --------------------
+        let builder = Request::with_capacity(42, TestEnum::One, 10);
+
+        // --------------------
+        // WHEN
+        // RequestArgsBuilder::finish() is called without pushing any args
+        // --------------------
+        let result = builder.finish();
+
+        // --------------------
+        // THEN
+        // the request has no arguments
+        // --------------------
+        let expected = Request::new(42, TestEnum::One, vec![]);
+        assert_eq!(result.as_value(), expected.as_value());
+    }
+}
 
 // ===========================================================================
 //
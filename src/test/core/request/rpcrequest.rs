@@ -15,7 +15,8 @@
 use rmpv::Value;
 
 // Local imports
-use core::{CodeConvert, FromMessage, Message, MessageType, RpcMessage};
+use core::{CodeConvert, FromMessage, Message, MessageType, RpcMessage,
+           ToMessageError};
 use core::request::{RequestMessage, RpcRequest};
 
 // Helpers
@@ -130,6 +131,93 @@ fn message_args()
 }
 
 
+// A request whose args slot is not guaranteed to be valid
+struct RawRequest(Value);
+
+
+impl RpcMessage for RawRequest
+{
+    type Err = ToMessageError;
+
+    fn as_vec(&self) -> &Vec<Value>
+    {
+        self.0.as_array().unwrap()
+    }
+
+    fn as_value(&self) -> &Value
+    {
+        &self.0
+    }
+}
+
+
+impl RpcRequest<TestEnum> for RawRequest {}
+
+
+#[test]
+fn arg_count()
+{
+    // --------------------
+    // GIVEN
+    // --------------------
+    // A request message with 3 arguments
+    let args = vec![Value::from(1), Value::from(2), Value::from(3)];
+    let req = RequestMessage::new(42, TestEnum::One, args);
+
+    // --------------------
+    // WHEN
+    // --------------------
+    // RequestMessage::arg_count() method is called
+    let result = req.arg_count();
+
+    // --------------------
+    // THEN
+    // --------------------
+    // The number of arguments is returned
+    assert_eq!(result, 3);
+    assert_eq!(result, req.message_args().len());
+}
+
+
+#[test]
+fn arg_count_malformed()
+{
+    // --------------------
+    // GIVEN
+    // --------------------
+    // A request with a missing args slot and
+    // a request with a non-array args slot
+    let msgtype = Value::from(MessageType::Request.to_number());
+    let msgid = Value::from(42);
+    let msgmeth = Value::from(TestEnum::One.to_number());
+    let missing = RawRequest(Value::Array(vec![
+        msgtype.clone(),
+        msgid.clone(),
+        msgmeth.clone(),
+    ]));
+    let notarray = RawRequest(Value::Array(vec![
+        msgtype,
+        msgid,
+        msgmeth,
+        Value::from(42),
+    ]));
+
+    // --------------------
+    // WHEN
+    // --------------------
+    // arg_count() is called on both requests
+    let missing_count = missing.arg_count();
+    let notarray_count = notarray.arg_count();
+
+    // --------------------
+    // THEN
+    // --------------------
+    // 0 is returned for both
+    assert_eq!(missing_count, 0);
+    assert_eq!(notarray_count, 0);
+}
+
+
 // ===========================================================================
 //
 // ===========================================================================
@@ -80,6 +80,71 @@ mod new {
 }
 
 
+mod set {
+    // Third-party imports
+
+    use rmpv::Value;
+
+    // Local imports
+
+    use core::response::RpcResponse;
+
+    // Helpers
+    use super::{Response, TestError};
+
+    #[test]
+    fn set_id()
+    {
+        // --------------------
+        // GIVEN
+        // a response with msg id 42
+        // --------------------
+        let mut res = Response::new(42, TestError::Two, Value::from("hello"));
+
+        // --------------------
+        // WHEN
+        // ResponseMessage::set_id() is called with a new id
+        // --------------------
+        res.set_id(9001);
+
+        // --------------------
+        // THEN
+        // the response's msg id is the new id and
+        // the response's code and result are unchanged
+        // --------------------
+        assert_eq!(res.message_id(), 9001);
+        assert_eq!(res.error_code(), TestError::Two);
+        assert_eq!(res.result(), &Value::from("hello"));
+    }
+
+    #[test]
+    fn set_result()
+    {
+        // --------------------
+        // GIVEN
+        // a response with a string result
+        // --------------------
+        let mut res = Response::new(42, TestError::Two, Value::from("hello"));
+
+        // --------------------
+        // WHEN
+        // ResponseMessage::set_result() is called with a new result
+        // --------------------
+        let result = Value::Array(vec![Value::from(1), Value::Nil]);
+        res.set_result(result.clone());
+
+        // --------------------
+        // THEN
+        // the response's result is the new result and
+        // the response's msg id and code are unchanged
+        // --------------------
+        assert_eq!(res.result(), &result);
+        assert_eq!(res.message_id(), 42);
+        assert_eq!(res.error_code(), TestError::Two);
+    }
+}
+
+
 mod from {
     // Stdlib imports
 
@@ -781,6 +846,82 @@ mod convert_bytes {
     }
 }
 
+
+mod into_value {
+    // Third-party imports
+
+    use rmpv::Value;
+
+    // Local imports
+
+    use core::{CodeConvert, FromMessage, MessageType, RpcMessage};
+    use core::notify::RpcNotice;
+    use message::{Batch, BatchCode};
+
+    // Helpers
+
+    use super::{Response, TestError};
+
+    #[test]
+    fn wire_array() {
+        // --------------------
+        // GIVEN
+        // a response message
+        // --------------------
+        let resp = Response::new(42, TestError::Two, Value::from("hello"));
+
+        // --------------------
+        // WHEN
+        // the response is converted into a Value
+        // --------------------
+        let result = Value::from(resp);
+
+        // --------------------
+        // THEN
+        // the value is the response's wire array
+        // --------------------
+        let expected = Value::Array(vec![
+            Value::from(MessageType::Response.to_number()),
+            Value::from(42),
+            Value::from(TestError::Two.to_number()),
+            Value::from("hello"),
+        ]);
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn batch_element() {
+        // --------------------
+        // GIVEN
+        // 2 response messages
+        // --------------------
+        let first = Response::new(1, TestError::One, Value::Nil);
+        let second = Response::new(2, TestError::Three, Value::from(9001));
+
+        // --------------------
+        // WHEN
+        // the responses are embedded as the args of a batch notification
+        // --------------------
+        let args = vec![first.clone().into(), second.clone().into()];
+        let batch = Batch::new(BatchCode::Batch, args);
+
+        // --------------------
+        // THEN
+        // each arg is the wire array of a response and
+        // each arg converts back into the same response
+        // --------------------
+        let args = batch.message_args();
+        assert_eq!(args.len(), 2);
+        assert_eq!(&args[0], first.as_value());
+        assert_eq!(&args[1], second.as_value());
+
+        let decoded: Vec<Response> = args.iter()
+            .map(|arg| Response::from_msg(arg.clone()).unwrap())
+            .collect();
+        assert_eq!(decoded, vec![first, second]);
+    }
+}
+
 // ===========================================================================
 //
 // ===========================================================================
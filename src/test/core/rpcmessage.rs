@@ -16,7 +16,8 @@ use rmpv::Value;
 
 // Local imports
 
-use core::{CodeConvert, FromMessage, Message, MessageType, RpcMessage};
+use core::{AsBytes, CodeConvert, FromMessage, Message, MessageType, Role,
+           RpcMessage};
 use core::request::RequestMessage;
 
 // Helpers
@@ -96,6 +97,98 @@ fn as_value()
 }
 
 
+
+#[test]
+fn validate_role()
+{
+    let cases = [
+        (Role::Client, MessageType::Request, false),
+        (Role::Client, MessageType::Response, true),
+        (Role::Client, MessageType::Notification, true),
+        (Role::Server, MessageType::Request, true),
+        (Role::Server, MessageType::Response, false),
+        (Role::Server, MessageType::Notification, true),
+    ];
+    for &(role, ref msgtype, allowed) in cases.iter() {
+        // --------------------
+        // GIVEN
+        // a message of a given type and
+        // a connection role
+        // --------------------
+        let msg = Message::template(msgtype.clone());
+
+        // --------------------
+        // WHEN
+        // RpcMessage::validate_role() is called w/ the role
+        // --------------------
+        let result = msg.validate_role(role);
+
+        // --------------------
+        // THEN
+        // clients only accept responses and notifications and
+        // servers only accept requests and notifications and
+        // a rejected message's error holds the role and message type
+        // --------------------
+        match result {
+            Ok(()) => assert!(allowed),
+            Err(e) => {
+                assert!(!allowed);
+                assert_eq!(e.role, role);
+                assert_eq!(&e.msgtype, msgtype);
+            }
+        }
+    }
+}
+
+
+#[test]
+fn serialize_into()
+{
+    // --------------------
+    // GIVEN
+    // a message and
+    // the message serialized with as_bytes()
+    // --------------------
+    let val = Value::Array(vec![
+        Value::from(MessageType::Request.to_number()),
+        Value::from(42),
+        Value::from(TestEnum::One.to_number()),
+        Value::Array(vec![Value::from("hello"), Value::from(vec![1u8; 32])]),
+    ]);
+    let msg = Message::from_msg(val).unwrap();
+    let expected = msg.as_bytes();
+    let len = expected.len();
+
+    // --------------------
+    // WHEN
+    // RpcMessage::serialize_into() is called with an exactly sized buffer,
+    // an oversized buffer, and an undersized buffer
+    // --------------------
+    let mut exact = vec![0u8; len];
+    let mut oversized = vec![0u8; len + 10];
+    let mut undersized = vec![0u8; len - 1];
+    let exact_result = msg.serialize_into(&mut exact[..]);
+    let oversized_result = msg.serialize_into(&mut oversized[..]);
+    let undersized_result = msg.serialize_into(&mut undersized[..]);
+
+    // --------------------
+    // THEN
+    // the exactly sized and oversized buffers hold the message's bytes and
+    // the message's length is returned for both and
+    // the undersized buffer returns an error
+    // --------------------
+    assert_eq!(exact_result.unwrap(), len);
+    assert_eq!(&exact[..], &expected[..]);
+
+    assert_eq!(oversized_result.unwrap(), len);
+    assert_eq!(&oversized[..len], &expected[..]);
+
+    let err = undersized_result.unwrap_err();
+    assert_eq!(err.needed, len);
+    assert_eq!(err.capacity, len - 1);
+}
+
+
 // ===========================================================================
 //
 // ===========================================================================
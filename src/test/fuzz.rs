@@ -0,0 +1,175 @@
+// src/test/fuzz.rs
+// Copyright (C) 2017 authors and contributors (see AUTHORS file)
+//
+// This file is released under the MIT License.
+
+// ===========================================================================
+// Imports
+// ===========================================================================
+
+
+// Stdlib imports
+
+// Third-party imports
+
+use bytes::{Bytes, BytesMut};
+
+// Local imports
+
+use core::{ChainedBuf, FromBytes, Message, ToMessageError};
+use message::v1::{Request, Response};
+
+
+// ===========================================================================
+// Helpers
+// ===========================================================================
+
+
+// Feed data through the full decode and validate pipeline.
+//
+// Every message that can be decoded from the data is decoded as a generic
+// message, a v1 request, and a v1 response, and as a generic message from a
+// ChainedBuf holding the data split into 2 chunks. Any decoded request is
+// also checked against the v1 spec. The results are discarded: the only
+// thing being tested is that nothing panics.
+fn decode_pipeline(data: &[u8])
+{
+    let mut msgbuf = BytesMut::from(data);
+    while let Ok(Some(_)) = Message::from_bytes(&mut msgbuf) {}
+
+    let mut reqbuf = BytesMut::from(data);
+    while let Ok(Some(req)) = Request::from_bytes(&mut reqbuf) {
+        let _ = req.validate_spec();
+    }
+
+    let mut respbuf = BytesMut::from(data);
+    while let Ok(Some(_)) = Response::from_bytes(&mut respbuf) {}
+
+    let (head, tail) = data.split_at(data.len() / 2);
+    let mut chained = ChainedBuf::new(vec![Bytes::from(head),
+                                           Bytes::from(tail)]);
+    while let Ok(Some(_)) = chained.decode::<Message, ToMessageError>() {}
+}
+
+
+// Hand-picked inputs covering the malformed shapes most likely to trip up
+// the decoder
+fn seed_corpus() -> Vec<Vec<u8>>
+{
+    vec![
+        // Empty input
+        vec![],
+
+        // Truncated arrays: array headers promising more items than given
+        vec![0x94],
+        vec![0x94, 0x00],
+        vec![0x94, 0x00, 0x2a, 0x04],
+        vec![0xdc, 0x00, 0x04, 0x00, 0x2a],
+        vec![0xdd, 0x00, 0x00, 0x00, 0x04, 0x00],
+
+        // Oversized length markers: str, bin, array, map, and ext headers
+        // claiming far more data than is present
+        vec![0xdb, 0xff, 0xff, 0xff, 0xff, 0x61],
+        vec![0xc6, 0xff, 0xff, 0xff, 0xff, 0x00],
+        vec![0xdd, 0xff, 0xff, 0xff, 0xff, 0x00],
+        vec![0xdf, 0xff, 0xff, 0xff, 0xff, 0x00],
+        vec![0xc9, 0xff, 0xff, 0xff, 0xff, 0x01, 0x00],
+        vec![0x94, 0x00, 0x2a, 0x04, 0xdd, 0xff, 0xff, 0xff, 0xff],
+
+        // Wrong-type slots: a well-formed 4 item array where the message
+        // type, id, code, or args hold the wrong kind of value
+        vec![0x94, 0xa1, 0x61, 0x2a, 0x04, 0x90],
+        vec![0x94, 0x00, 0xc3, 0x04, 0x90],
+        vec![0x94, 0x00, 0x2a, 0xcb, 0x3f, 0xf0, 0, 0, 0, 0, 0, 0, 0x90],
+        vec![0x94, 0x00, 0x2a, 0x04, 0x2a],
+        vec![0x94, 0x00, 0xff, 0x04, 0x90],
+        vec![0x94, 0x01, 0x2a, 0x05, 0x80],
+        vec![0x93, 0x02, 0x04, 0xc0],
+
+        // Valid envelopes with nonsense arguments
+        vec![0x94, 0x00, 0x2a, 0x04, 0x93, 0xc0, 0xc0, 0xc0],
+        vec![0x94, 0x00, 0x2a, 0x07, 0x92, 0x01, 0xa1, 0xff],
+        vec![0x94, 0x01, 0x2a, 0x05, 0x91, 0x93, 0xc0, 0xc0, 0xc0],
+
+        // Reserved marker and invalid utf-8 in a string
+        vec![0xc1],
+        vec![0xa2, 0xc3, 0x28],
+
+        // Deeply nested arrays
+        vec![0x91; 2048],
+    ]
+}
+
+
+// ===========================================================================
+// Tests
+// ===========================================================================
+
+
+#[test]
+fn seed_corpus_never_panics()
+{
+    // --------------------
+    // GIVEN
+    // a corpus of malformed inputs
+    // --------------------
+    let corpus = seed_corpus();
+
+    // --------------------
+    // WHEN
+    // each input is fed through the decode pipeline
+    // THEN
+    // the pipeline returns without panicking
+    // --------------------
+    for data in corpus {
+        decode_pipeline(&data[..]);
+    }
+}
+
+
+quickcheck! {
+    fn random_bytes_never_panic(data: Vec<u8>) -> bool {
+        // --------------------
+        // GIVEN
+        // an arbitrary sequence of bytes
+        // --------------------
+        // --------------------
+        // WHEN
+        // the bytes are fed through the decode pipeline
+        // --------------------
+        decode_pipeline(&data[..]);
+
+        // --------------------
+        // THEN
+        // the pipeline returns without panicking
+        // --------------------
+        true
+    }
+
+    fn seeded_random_bytes_never_panic(seed: usize, tail: Vec<u8>) -> bool {
+        // --------------------
+        // GIVEN
+        // an input from the seed corpus with arbitrary bytes appended
+        // --------------------
+        let corpus = seed_corpus();
+        let mut data = corpus[seed % corpus.len()].clone();
+        data.extend(tail);
+
+        // --------------------
+        // WHEN
+        // the bytes are fed through the decode pipeline
+        // --------------------
+        decode_pipeline(&data[..]);
+
+        // --------------------
+        // THEN
+        // the pipeline returns without panicking
+        // --------------------
+        true
+    }
+}
+
+
+// ===========================================================================
+//
+// ===========================================================================
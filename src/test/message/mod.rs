@@ -16,6 +16,109 @@ mod v1;
 // ===========================================================================
 
 
+mod code_convert {
+    // Third party imports
+
+    use bytes::BytesMut;
+
+    // Local imports
+
+    use core::{AsBytes, CodeConvert, FromBytes};
+    use core::request::RpcRequest;
+    use message::{request, Request, RequestCode};
+    use message::v1;
+
+    #[test]
+    fn version_roundtrip()
+    {
+        // --------------------
+        // GIVEN
+        // the Version request code, whose value is 2
+        // --------------------
+        let code = RequestCode::Version;
+
+        // --------------------
+        // WHEN
+        // the code is converted to a number and back
+        // THEN
+        // the code's own value is used and
+        // every other value is an error
+        // --------------------
+        assert_eq!(code.to_u64(), 2);
+        assert_eq!(RequestCode::from_u64(2).unwrap(), code);
+        assert_eq!(RequestCode::min_number(), 2);
+        assert_eq!(RequestCode::max_number(), 2);
+        for n in &[0, 1, 3] {
+            assert_eq!(RequestCode::from_u64(*n).unwrap_err().code, *n);
+        }
+
+        // --------------------
+        // WHEN
+        // a version request is serialized and decoded
+        // THEN
+        // the decoded request's code is Version
+        // --------------------
+        let req = request(42).version(1);
+        let mut buf = BytesMut::from(&req.as_bytes()[..]);
+        let decoded = Request::from_bytes(&mut buf).unwrap().unwrap();
+        assert_eq!(decoded.message_method(), code);
+    }
+
+    #[test]
+    fn gaps_between_values()
+    {
+        // --------------------
+        // GIVEN
+        // the v1 request codes, whose values are the even numbers from 4
+        // to 26
+        // --------------------
+        let min = v1::RequestCode::min_number();
+        let max = v1::RequestCode::max_number();
+
+        // --------------------
+        // WHEN
+        // every number from the smallest to the largest value is converted
+        // into a code
+        // THEN
+        // the largest value is the largest discriminant and
+        // only the even numbers are codes
+        // --------------------
+        assert_eq!((min, max), (4, 26));
+        for n in min..max + 1 {
+            let result = v1::RequestCode::from_u64(n);
+            assert_eq!(result.is_ok(), n % 2 == 0, "{}", n);
+            if let Ok(code) = result {
+                assert_eq!(code.to_u64(), n);
+            }
+        }
+    }
+
+    #[test]
+    fn names()
+    {
+        // --------------------
+        // GIVEN
+        // every session and v1 code
+        // --------------------
+        // --------------------
+        // WHEN
+        // CodeConvert::name() is called on each code
+        // THEN
+        // the code's variant identifier is returned
+        // --------------------
+        assert_eq!(RequestCode::Version.name(), "Version");
+        assert_eq!(v1::RequestCode::Walk.name(), "Walk");
+        assert_eq!(v1::ResponseCode::WStat.name(), "WStat");
+        for code in v1::RequestCode::all() {
+            assert_eq!(code.name(), format!("{:?}", code));
+        }
+        for code in v1::ResponseCode::all() {
+            assert_eq!(code.name(), format!("{:?}", code));
+        }
+    }
+}
+
+
 mod requestbuilder {
 
     mod version {
@@ -139,7 +242,7 @@ mod responsebuilder {
                 // WHEN
                 // ResponseBuilder::version() is called w/ the version number
                 // --------------------
-                let msg = builder.version(num);
+                let msg = builder.version(num).unwrap();
 
                 // --------------------
                 // THEN
@@ -159,15 +262,386 @@ mod responsebuilder {
 }
 
 
+mod error_from_decode {
+    // Stdlib imports
+
+    use std::io;
+
+    // Local imports
+
+    use core::FromBytesError;
+    use core::request::ToRequestError;
+    use core::response::RpcResponse;
+    use message::{error_from_decode, ResponseCode};
+
+    type DecodeError = FromBytesError<ToRequestError>;
+
+    #[test]
+    fn length_mismatch()
+    {
+        // --------------------
+        // GIVEN
+        // a LengthMismatch decode error
+        // --------------------
+        let err: DecodeError = FromBytesError::LengthMismatch {
+            len: 4,
+            offset: 0,
+        };
+
+        // --------------------
+        // WHEN
+        // error_from_decode() is called w/ a request id and the error
+        // --------------------
+        let resp = error_from_decode(42, &err);
+
+        // --------------------
+        // THEN
+        // an Error response for the request id is returned and
+        // the error message mentions a length problem
+        // --------------------
+        assert_eq!(resp.message_id(), 42);
+        assert_eq!(resp.error_code(), ResponseCode::Error);
+        let errmsg = resp.result().as_str().unwrap();
+        assert!(errmsg.contains("length"));
+    }
+
+    #[test]
+    fn hides_internal_details()
+    {
+        // --------------------
+        // GIVEN
+        // a decode error wrapping an io error w/ an internal message
+        // --------------------
+        let ioerr = io::Error::new(io::ErrorKind::InvalidData, "internals");
+        let err: DecodeError = FromBytesError::InvalidDataRead(ioerr);
+
+        // --------------------
+        // WHEN
+        // error_from_decode() is called w/ a request id and the error
+        // --------------------
+        let resp = error_from_decode(42, &err);
+
+        // --------------------
+        // THEN
+        // the error message does not include the io error's message
+        // --------------------
+        let errmsg = resp.result().as_str().unwrap();
+        assert_eq!(errmsg, "Unable to decode request: malformed or truncated \
+                            data");
+    }
+}
+
+
+mod error_from_failure {
+
+    // Third party imports
+
+    use failure::Error;
+
+    // Local imports
+
+    use core::response::RpcResponse;
+    use message::{error_from_failure, ResponseCode};
+    use message::v1::request;
+
+    #[test]
+    fn chained_error()
+    {
+        // --------------------
+        // GIVEN
+        // a failure::Error made from an auth build error caused by a bad
+        //    username
+        // --------------------
+        let err: Error =
+            request(42).auth(1, "bad user", "fs").unwrap_err().into();
+
+        // --------------------
+        // WHEN
+        // error_from_failure() is called with a msg id and the error
+        // --------------------
+        let result = error_from_failure(42, &err);
+
+        // --------------------
+        // THEN
+        // an error response with the msg id is returned and
+        // the response's message starts with the top-level description and
+        // the response's message includes the cause's description
+        // --------------------
+        assert_eq!(result.message_id(), 42);
+        assert_eq!(result.error_code(), ResponseCode::Error);
+
+        let errmsg = result.result().as_str().unwrap();
+        assert!(errmsg.starts_with("Unable to build auth request message: "));
+        assert!(errmsg.contains("username is either empty"));
+    }
+}
+
+
+mod parse_version_reply {
+
+    // Third party imports
+
+    use rmpv::Value;
+
+    // Local imports
+
+    use core::Message;
+    use core::response::RpcResponse;
+    use message::{parse_version_reply, request, response, ResponseCode};
+
+    #[test]
+    fn version_reply()
+    {
+        // --------------------
+        // GIVEN
+        // a version request and
+        // a version response to the request
+        // --------------------
+        let req = request(42).version(1);
+        let resp = response(&req).version(1).unwrap();
+        let msg: Message = resp.into();
+
+        // --------------------
+        // WHEN
+        // parse_version_reply() is called with the response message
+        // --------------------
+        let result = parse_version_reply(msg);
+
+        // --------------------
+        // THEN
+        // the result is the version response
+        // --------------------
+        let val = match result {
+            Ok(Ok(resp)) => {
+                resp.message_id() == 42 &&
+                    resp.error_code() == ResponseCode::Version &&
+                    resp.result() == &Value::from(1)
+            }
+            _ => false,
+        };
+        assert!(val);
+    }
+
+    #[test]
+    fn error_reply()
+    {
+        // --------------------
+        // GIVEN
+        // a version request and
+        // an error response to the request
+        // --------------------
+        let req = request(42).version(9001);
+        let resp = response(&req).error("unsupported version");
+        let msg: Message = resp.into();
+
+        // --------------------
+        // WHEN
+        // parse_version_reply() is called with the response message
+        // --------------------
+        let result = parse_version_reply(msg);
+
+        // --------------------
+        // THEN
+        // the result is the error response
+        // --------------------
+        let val = match result {
+            Ok(Err(resp)) => {
+                resp.message_id() == 42 &&
+                    resp.error_code() == ResponseCode::Error &&
+                    resp.result().as_str() == Some("unsupported version")
+            }
+            _ => false,
+        };
+        assert!(val);
+    }
+}
+
+
+mod negotiated_max_size {
+
+    // Third party imports
+
+    use quickcheck::TestResult;
+
+    // Local imports
+
+    use message::{negotiated_max_size, negotiated_version, request,
+                  response};
+
+    quickcheck! {
+
+        fn granted_size_roundtrip(num: u32, requested: u32, granted: u32)
+            -> TestResult
+        {
+            // --------------------
+            // GIVEN
+            // a version request asking for a max size and
+            // a version response granting a max size
+            // --------------------
+            let req = request(42).version_with(num, requested);
+            let resp = response(&req).version_with(num, granted).unwrap();
+
+            // --------------------
+            // WHEN
+            // negotiated_max_size() and negotiated_version() are called
+            // --------------------
+            let max_size = negotiated_max_size(&req, &resp);
+            let version = negotiated_version(&resp);
+
+            // --------------------
+            // THEN
+            // the granted max size is returned and
+            // the version number is returned
+            // --------------------
+            let val = max_size == Some(granted) && version == Some(num);
+            TestResult::from_bool(val)
+        }
+
+        fn default_requested_size(num: u32, requested: u32) -> TestResult
+        {
+            // --------------------
+            // GIVEN
+            // a version request asking for a max size and
+            // a version response that does not grant a max size
+            // --------------------
+            let req = request(42).version_with(num, requested);
+            let resp = response(&req).version(num).unwrap();
+
+            // --------------------
+            // WHEN
+            // negotiated_max_size() and negotiated_version() are called
+            // --------------------
+            let max_size = negotiated_max_size(&req, &resp);
+            let version = negotiated_version(&resp);
+
+            // --------------------
+            // THEN
+            // the requested max size is returned and
+            // the version number is returned
+            // --------------------
+            let val = max_size == Some(requested) && version == Some(num);
+            TestResult::from_bool(val)
+        }
+    }
+
+    #[test]
+    fn no_size()
+    {
+        // --------------------
+        // GIVEN
+        // a version request without a max size and
+        // a version response without a max size and
+        // an error response to the request
+        // --------------------
+        let req = request(42).version(1);
+        let resp = response(&req).version(1).unwrap();
+        let err = response(&req).error("unsupported version");
+
+        // --------------------
+        // WHEN
+        // negotiated_max_size() is called with each response
+        // --------------------
+        let max_size = negotiated_max_size(&req, &resp);
+        let err_size = negotiated_max_size(&req, &err);
+
+        // --------------------
+        // THEN
+        // no max size is returned
+        // --------------------
+        assert_eq!(max_size, None);
+        assert_eq!(err_size, None);
+        assert_eq!(negotiated_version(&err), None);
+    }
+}
+
+
+mod as_bytes_with {
+    // Third party imports
+
+    use rmps::{from_slice, Serializer};
+    use rmps::encode::{StructArrayWriter, StructMapWriter};
+    use rmpv::Value;
+
+    // Local imports
+
+    use core::{AsBytes, RpcMessage};
+    use message::request;
+
+    #[test]
+    fn array_and_map_encodings()
+    {
+        // --------------------
+        // GIVEN
+        // a version request message
+        // --------------------
+        let req = request(42).version(1);
+
+        // --------------------
+        // WHEN
+        // RpcMessage::as_bytes_with() is called with a struct array writer
+        //    and
+        // RpcMessage::as_bytes_with() is called with a struct map writer
+        // --------------------
+        let array_bytes = req.as_bytes_with(StructArrayWriter, |_| {});
+        let map_bytes = req.as_bytes_with(StructMapWriter, |_| {});
+
+        // --------------------
+        // THEN
+        // both encodings decode into the request's value and
+        // since a message contains no structs, both encodings are the same
+        //    as the default encoding
+        // --------------------
+        let array_value: Value = from_slice(&array_bytes[..]).unwrap();
+        assert_eq!(&array_value, req.as_value());
+
+        let map_value: Value = from_slice(&map_bytes[..]).unwrap();
+        assert_eq!(&map_value, req.as_value());
+
+        assert_eq!(array_bytes, req.as_bytes());
+        assert_eq!(map_bytes, req.as_bytes());
+    }
+
+    #[test]
+    fn configure_serializer()
+    {
+        // --------------------
+        // GIVEN
+        // a version request message
+        // --------------------
+        let req = request(42).version(1);
+
+        // --------------------
+        // WHEN
+        // RpcMessage::as_bytes_with() is called with a configure closure
+        // --------------------
+        let mut called = false;
+        let result = req.as_bytes_with(
+            StructMapWriter,
+            |se: &mut Serializer<&mut Vec<u8>, StructMapWriter>| {
+                se.set_max_depth(8);
+                called = true;
+            },
+        );
+
+        // --------------------
+        // THEN
+        // the closure is called before serializing and
+        // the message is serialized
+        // --------------------
+        assert!(called);
+        assert_eq!(result, req.as_bytes());
+    }
+}
+
 mod infobuilder {
 
     mod done {
 
         // Local imports
 
-        use core::{MessageType, RpcMessage};
+        use core::{FromBytes, MessageType, RpcMessage};
         use core::notify::RpcNotice;
-        use message::{NotifyCode, info};
+        use message::{done_bytes, info, Info, NotifyCode};
 
         #[test]
         fn info_msg()
@@ -194,10 +668,261 @@ mod infobuilder {
             assert_eq!(msg.message_code(), NotifyCode::Done);
             assert_eq!(msg.message_args().len(), 0);
         }
+
+        #[test]
+        fn done_bytes_roundtrip()
+        {
+            // --------------------
+            // GIVEN
+            // the bytes of a done notification
+            // --------------------
+            let mut buf = done_bytes().try_mut().unwrap();
+
+            // --------------------
+            // WHEN
+            // the bytes are decoded into an Info message
+            // --------------------
+            let result = Info::from_bytes(&mut buf);
+
+            // --------------------
+            // THEN
+            // the message has a message code == NotifyCode::Done and
+            // the message does not have any arguments and
+            // all bytes were consumed
+            // --------------------
+            let msg = result.unwrap().unwrap();
+            assert_eq!(msg.message_code(), NotifyCode::Done);
+            assert_eq!(msg.message_args().len(), 0);
+            assert!(buf.is_empty());
+        }
+    }
+
+    mod ping {
+
+        // Third-party imports
+
+        use rmpv::Value;
+
+        // Local imports
+
+        use core::{AsBytes, CodeConvert, FromBytes, MessageType, RpcMessage};
+        use core::notify::{Origin, RpcNotice};
+        use message::{info, reply_to_ping, Info, NotifyCode};
+        use message::v1::{RequestCode, ResponseCode};
+
+        #[test]
+        fn ping_roundtrip()
+        {
+            // --------------------
+            // GIVEN
+            // a ping notification serialized into bytes
+            // --------------------
+            let ping = info().ping(42);
+            let mut buf = ping.as_bytes().try_mut().unwrap();
+
+            // --------------------
+            // WHEN
+            // the bytes are decoded into an Info message
+            // --------------------
+            let result = Info::from_bytes(&mut buf);
+
+            // --------------------
+            // THEN
+            // the message is a notification and
+            // the message has a message code == NotifyCode::Ping and
+            // the message's only argument is the token
+            // --------------------
+            let msg = result.unwrap().unwrap();
+            assert_eq!(msg.message_type(), MessageType::Notification);
+            assert_eq!(msg.message_code(), NotifyCode::Ping);
+            assert_eq!(msg.message_args(), &vec![Value::from(42)]);
+            assert!(buf.is_empty());
+        }
+
+        #[test]
+        fn reply()
+        {
+            // --------------------
+            // GIVEN
+            // a ping notification
+            // --------------------
+            let ping = info().ping(9001);
+
+            // --------------------
+            // WHEN
+            // reply_to_ping() is called with the ping
+            // --------------------
+            let result = reply_to_ping(&ping);
+
+            // --------------------
+            // THEN
+            // a pong notification with the same token is returned
+            // --------------------
+            let pong = result.unwrap();
+            assert_eq!(pong.message_code(), NotifyCode::Pong);
+            assert_eq!(pong.message_args(), &vec![Value::from(9001)]);
+        }
+
+        #[test]
+        fn no_reply_to_non_ping()
+        {
+            // --------------------
+            // GIVEN
+            // a done notification and
+            // a pong notification
+            // --------------------
+            let done = info().done();
+            let pong = info().pong(42);
+
+            // --------------------
+            // WHEN
+            // reply_to_ping() is called with each notification
+            // --------------------
+            // --------------------
+            // THEN
+            // None is returned
+            // --------------------
+            assert!(reply_to_ping(&done).is_none());
+            assert!(reply_to_ping(&pong).is_none());
+        }
+
+        #[test]
+        fn origin_is_client_range()
+        {
+            // --------------------
+            // GIVEN
+            // a ping notification and
+            // the pong notification answering it
+            // --------------------
+            let ping = info().ping(42);
+            let pong = reply_to_ping(&ping).unwrap();
+
+            // --------------------
+            // WHEN
+            // RpcNotice::notification_origin() is called on each
+            // --------------------
+            // --------------------
+            // THEN
+            // both report the client origin of their code range, even
+            // though the pong is sent by the side that received the ping
+            // --------------------
+            assert_eq!(ping.notification_origin(), Origin::Client);
+            assert_eq!(pong.notification_origin(), Origin::Client);
+        }
+
+        #[test]
+        fn codes_distinct()
+        {
+            // --------------------
+            // GIVEN
+            // the ping and pong codes
+            // --------------------
+            let codes = [NotifyCode::Ping.to_u64(), NotifyCode::Pong.to_u64()];
+
+            // --------------------
+            // WHEN
+            // the codes are compared against every other known code
+            // --------------------
+            // --------------------
+            // THEN
+            // no other notification, request, or response code is the same
+            // --------------------
+            for code in codes.iter() {
+                assert_ne!(*code, NotifyCode::Done.to_u64());
+                assert!(*code > RequestCode::max_number());
+                assert!(*code > ResponseCode::max_number());
+            }
+        }
     }
 }
 
 
+mod batch {
+    // Third party imports
+
+    use rmpv::Value;
+
+    // Local imports
+
+    use core::notify::RpcNotice;
+    use message::{batch_notifications, info, unbatch_notifications, Batch,
+                  BatchCode, Info, NotifyCode};
+
+    #[test]
+    fn batch_two_done()
+    {
+        // --------------------
+        // GIVEN
+        // two Done notifications
+        // --------------------
+        let notices = vec![info().done(), info().done()];
+
+        // --------------------
+        // WHEN
+        // batch_notifications() is called w/ the notifications
+        // --------------------
+        let batch = batch_notifications(notices);
+
+        // --------------------
+        // THEN
+        // a Batch notification w/ 2 args is returned and
+        // each arg is a Done notification
+        // --------------------
+        assert_eq!(batch.message_code(), BatchCode::Batch);
+        assert_eq!(batch.message_args().len(), 2);
+        let done = Value::from(info().done());
+        assert!(batch.message_args().iter().all(|arg| *arg == done));
+    }
+
+    #[test]
+    fn unbatch_two_done()
+    {
+        // --------------------
+        // GIVEN
+        // a Batch notification holding two Done notifications
+        // --------------------
+        let batch = batch_notifications(vec![info().done(), info().done()]);
+
+        // --------------------
+        // WHEN
+        // unbatch_notifications() is called w/ the batch
+        // --------------------
+        let result: Vec<Info> = unbatch_notifications(&batch).unwrap();
+
+        // --------------------
+        // THEN
+        // the two Done notifications are returned
+        // --------------------
+        assert_eq!(result.len(), 2);
+        for notice in result {
+            assert_eq!(notice.message_code(), NotifyCode::Done);
+            assert!(notice.message_args().is_empty());
+        }
+    }
+
+    #[test]
+    fn unbatch_invalid_arg()
+    {
+        // --------------------
+        // GIVEN
+        // a Batch notification whose only arg is not a notification
+        // --------------------
+        let batch = Batch::new(BatchCode::Batch, vec![Value::from(42)]);
+
+        // --------------------
+        // WHEN
+        // unbatch_notifications() is called w/ the batch
+        // --------------------
+        let result = unbatch_notifications::<NotifyCode>(&batch);
+
+        // --------------------
+        // THEN
+        // an error is returned
+        // --------------------
+        assert!(result.is_err());
+    }
+}
+
 // ===========================================================================
 //
 // ===========================================================================
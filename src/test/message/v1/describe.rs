@@ -0,0 +1,83 @@
+// src/test/message/v1/describe.rs
+// Copyright (C) 2017 authors and contributors (see AUTHORS file)
+//
+// This file is released under the MIT License.
+
+// ===========================================================================
+// Imports
+// ===========================================================================
+
+
+// Stdlib imports
+
+// Third-party imports
+
+// Local imports
+
+// ===========================================================================
+// Tests
+// ===========================================================================
+
+
+mod describe_exchange {
+    // Local imports
+
+    use message::v1::{describe_exchange, request, response, FileID, FileKind};
+
+    #[test]
+    fn auth_exchange()
+    {
+        // --------------------
+        // GIVEN
+        // an auth request and
+        // an auth response to the request
+        // --------------------
+        let req = request(42).auth(9001, "hello", "world").unwrap();
+        let file_id = FileID::new(FileKind::AUTH, 0, 7);
+        let resp = response(&req).auth(file_id).unwrap();
+
+        // --------------------
+        // WHEN
+        // describe_exchange() is called with the request and response
+        // --------------------
+        let result = describe_exchange(&req, &resp);
+
+        // --------------------
+        // THEN
+        // the string contains the request's method name and args and
+        // the string contains the response's method name
+        // --------------------
+        let expected = "Auth(authfile_id=9001, username=\"hello\", \
+                        fsname=\"world\") -> Auth(file_id=[16, 0, 7])";
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn no_response_args()
+    {
+        // --------------------
+        // GIVEN
+        // a clunk request and
+        // a clunk response to the request
+        // --------------------
+        let req = request(42).clunk(1);
+        let resp = response(&req).clunk().unwrap();
+
+        // --------------------
+        // WHEN
+        // describe_exchange() is called with the request and response
+        // --------------------
+        let result = describe_exchange(&req, &resp);
+
+        // --------------------
+        // THEN
+        // the response is rendered without args
+        // --------------------
+        assert_eq!(result, "Clunk(file_id=1) -> Clunk()");
+    }
+}
+
+
+// ===========================================================================
+//
+// ===========================================================================
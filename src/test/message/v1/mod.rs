@@ -8,11 +8,29 @@
 // ===========================================================================
 
 
+mod describe;
 mod requestbuilder;
 mod responsebuilder;
+mod spec;
 mod util;
 
 
+// ===========================================================================
+// Imports
+// ===========================================================================
+
+
+// Stdlib imports
+
+// Third-party imports
+
+// Local imports
+
+use core::CodeConvert;
+use core::request::{RequestMessage, RpcRequest};
+use core::response::{ResponseMessage, RpcResponse};
+
+
 // ===========================================================================
 // Helpers
 // ===========================================================================
@@ -28,6 +46,66 @@ fn invalid_string(s: &str) -> bool
 }
 
 
+// Return true if s contains a path separator
+fn has_separator(s: &str) -> bool
+{
+    s.contains(&['/', '\\'][..])
+}
+
+
+// Build a response to the given request and assert that the response's
+// message id matches the request's message id
+fn exchange<Q, S, F>(
+    req: RequestMessage<Q>, make_resp: F
+) -> (RequestMessage<Q>, ResponseMessage<S>)
+where
+    Q: CodeConvert<Q>,
+    S: CodeConvert<S>,
+    F: FnOnce(&RequestMessage<Q>) -> ResponseMessage<S>,
+{
+    let resp = make_resp(&req);
+    assert_eq!(resp.message_id(), req.message_id());
+    (req, resp)
+}
+
+
+// ===========================================================================
+// Tests
+// ===========================================================================
+
+
+mod protocol_set
+{
+    // Local imports
+
+    use core::MessageType;
+    use message::ProtocolSet;
+    use message::v1::{RequestCode, ResponseCode};
+
+    #[test]
+    fn v1_codes()
+    {
+        // --------------------
+        // GIVEN
+        // the v1 request and response codes
+        // --------------------
+        // --------------------
+        // WHEN
+        // the ProtocolSet constants are read
+        // --------------------
+        // --------------------
+        // THEN
+        // the version is 1 and
+        // the category is the matching message type
+        // --------------------
+        assert_eq!(RequestCode::VERSION, 1);
+        assert_eq!(ResponseCode::VERSION, 1);
+        assert_eq!(RequestCode::CATEGORY, MessageType::Request as u8);
+        assert_eq!(ResponseCode::CATEGORY, MessageType::Response as u8);
+    }
+}
+
+
 // ===========================================================================
 //
 // ===========================================================================
@@ -271,6 +271,114 @@ mod auth {
 }
 
 
+mod typed_auth {
+
+    // Third party imports
+
+    use quickcheck::TestResult;
+
+    // Local imports
+
+    use message::v1::{request, typed_request, BuildRequestError, Request};
+
+    // Helpers
+    use test::message::v1::invalid_string;
+
+    // Build an auth request with the typed builder
+    fn typed_auth(
+        fileid: u32, user: &str, fs: &str
+    ) -> Result<Request, BuildRequestError>
+    {
+        let req = typed_request(42).auth(fileid).username(user)?.fsname(fs)?;
+        Ok(req)
+    }
+
+    quickcheck! {
+
+        fn same_as_builder(fileid: u32, user: String, fs: String)
+            -> TestResult
+        {
+            // Ignore empty strings or strings with whitespace or strings
+            // with control characters
+            if invalid_string(&user[..]) || invalid_string(&fs[..]) {
+                return TestResult::discard();
+            }
+
+            // --------------------
+            // GIVEN
+            // a u32 file id and
+            // a valid user name string and
+            // a valid filesystem name string
+            // --------------------
+            // --------------------
+            // WHEN
+            // an auth request is built with the typed builder
+            // --------------------
+            let result = typed_auth(fileid, &user[..], &fs[..]);
+
+            // --------------------
+            // THEN
+            // the request is the same as the one built by
+            //    RequestBuilder::auth()
+            // --------------------
+            let expected = request(42).auth(fileid, &user[..], &fs[..]);
+            TestResult::from_bool(result.unwrap() == expected.unwrap())
+        }
+    }
+
+    #[test]
+    fn bad_username_early()
+    {
+        // --------------------
+        // GIVEN
+        // a typed auth builder
+        // --------------------
+        let builder = typed_request(42).auth(1);
+
+        // --------------------
+        // WHEN
+        // TypedAuthBuilder::username() is called w/ an invalid user name
+        // --------------------
+        let result = builder.username("bad user");
+
+        // --------------------
+        // THEN
+        // an auth error is returned before the filesystem name is given
+        // --------------------
+        match result {
+            Err(BuildRequestError::Auth(ref e)) if e.field == "username" => {}
+            _ => panic!("expected a username error"),
+        }
+    }
+
+    #[test]
+    fn bad_fsname()
+    {
+        // --------------------
+        // GIVEN
+        // a typed auth builder with a valid user name
+        // --------------------
+        let builder = typed_request(42).auth(1).username("user").unwrap();
+
+        // --------------------
+        // WHEN
+        // TypedAuthUserBuilder::fsname() is called w/ an empty name
+        // --------------------
+        let result = builder.fsname("");
+
+        // --------------------
+        // THEN
+        // an auth error for the filesystem name is returned
+        // --------------------
+        match result {
+            Err(BuildRequestError::Auth(ref e))
+                if e.field == "filesystem name" => {}
+            _ => panic!("expected a filesystem name error"),
+        }
+    }
+}
+
+
 mod flush {
     // Third party imports
 
@@ -702,7 +810,8 @@ mod walk {
     // Local imports
 
     use core::request::RpcRequest;
-    use message::v1::{request, BuildRequestError, RequestCode};
+    use message::v1::{request, BuildRequestError, BuildWalkError,
+                      RequestCode};
 
     quickcheck! {
 
@@ -731,10 +840,10 @@ mod walk {
             // the result is an error
             // --------------------
             let val = match result {
-                Err(e @ BuildRequestError::Walk(_)) => {
+                Err(BuildRequestError::Walk(ref e)) => {
                     let expected = format!(
-                        "Unable to build walk request message: newfile_id \
-                         ({}) has the same value as file_id",
+                        "Invalid newfile_id value ({}): newfile_id matches \
+                         file_id",
                         newfile_id
                     );
                     e.to_string() == expected
@@ -748,8 +857,10 @@ mod walk {
         fn create_request_message(file_id: u32, newfile_id: u32,
                                   path: Vec<String>) -> TestResult
         {
-            // Ignore invalid file_id
-            if file_id == newfile_id {
+            // Ignore invalid file_id and relative path elements
+            if file_id == newfile_id ||
+                path.iter().any(|p| p == "." || p == "..")
+            {
                 return TestResult::discard();
             }
 
@@ -804,6 +915,70 @@ mod walk {
             TestResult::from_bool(val)
         }
     }
+
+    #[test]
+    fn relative_path_rejected()
+    {
+        // --------------------
+        // GIVEN
+        // a path containing ".." and
+        // a request builder
+        // --------------------
+        let path = vec!["hello", "..", "world"];
+        let builder = request(42);
+
+        // --------------------
+        // WHEN
+        // RequestBuilder::walk() is called w/ the path
+        // --------------------
+        let result = builder.walk(1, 2, path);
+
+        // --------------------
+        // THEN
+        // a walk error naming the ".." element is returned
+        // --------------------
+        match result {
+            Err(BuildRequestError::Walk(BuildWalkError::RelativePath {
+                index,
+                element,
+            })) => {
+                assert_eq!(index, 1);
+                assert_eq!(element, "..");
+            }
+            _ => panic!("expected a relative path error"),
+        }
+    }
+
+    #[test]
+    fn relative_path_allowed()
+    {
+        // --------------------
+        // GIVEN
+        // a path containing "." and ".." and
+        // a request builder that allows relative paths
+        // --------------------
+        let path = vec![".", "..", "world"];
+        let builder = request(42).allow_relative_path();
+
+        // --------------------
+        // WHEN
+        // RequestBuilder::walk() is called w/ the path
+        // --------------------
+        let result = builder.walk(1, 2, path);
+
+        // --------------------
+        // THEN
+        // a walk request w/ the path is returned
+        // --------------------
+        let msg = result.unwrap();
+        let expected = Value::Array(vec![
+            Value::from("."),
+            Value::from(".."),
+            Value::from("world"),
+        ]);
+        assert_eq!(msg.message_method(), RequestCode::Walk);
+        assert_eq!(msg.message_args()[2], expected);
+    }
 }
 
 
@@ -877,10 +1052,11 @@ mod create {
     // Local imports
 
     use core::request::RpcRequest;
-    use message::v1::{request, BuildRequestError, OpenMode, RequestCode};
+    use message::v1::{openmode, request, ArgReason, BuildCreateError,
+                      BuildRequestError, OpenKind, OpenMode, RequestCode};
 
     // Helpers
-    use test::message::v1::invalid_string;
+    use test::message::v1::{has_separator, invalid_string};
 
     quickcheck! {
 
@@ -946,7 +1122,7 @@ mod create {
         fn create_request_message(fileid: u32, filename: String, mode: u8) -> TestResult
         {
             // Ignore invalid filename strings
-            if invalid_string(&filename[..]) {
+            if invalid_string(&filename[..]) || has_separator(&filename[..]) {
                 return TestResult::discard();
             }
 
@@ -954,7 +1130,7 @@ mod create {
             // GIVEN
             // a u32 file id and
             // a valid filename string and
-            // an OpenMode object and
+            // a writable OpenMode object and
             // a RequestBuilder object
             // --------------------
             let open_mode = match OpenMode::from_bits(mode) {
@@ -963,6 +1139,12 @@ mod create {
 
                 Ok(m) => m,
             };
+
+            // Discard any mode that does not allow writing
+            match open_mode.kind() {
+                OpenKind::Write | OpenKind::ReadWrite => {}
+                _ => return TestResult::discard(),
+            }
             let builder = request(42);
 
             // --------------------
@@ -1004,6 +1186,126 @@ mod create {
 
             TestResult::from_bool(val)
         }
+
+        fn non_writable_mode(fileid: u32, mode: u8) -> TestResult
+        {
+            // --------------------
+            // GIVEN
+            // a u32 file id and
+            // a valid filename string and
+            // an OpenMode object that does not allow writing and
+            // a RequestBuilder object
+            // --------------------
+            let open_mode = match OpenMode::from_bits(mode) {
+                // Discard any mode that has invalid bits set
+                Err(_) => return TestResult::discard(),
+
+                Ok(m) => m,
+            };
+
+            // Discard any mode that allows writing
+            let kind = open_mode.kind();
+            match kind {
+                OpenKind::Write | OpenKind::ReadWrite => {
+                    return TestResult::discard()
+                }
+                _ => {}
+            }
+            let builder = request(42);
+
+            // --------------------
+            // WHEN
+            // RequestBuilder::create() is called w/ fileid, filename, and mode
+            // --------------------
+            let result = builder.create(fileid, "hello", open_mode);
+
+            // --------------------
+            // THEN
+            // the result is a BuildRequestError::Create error and
+            // the cause is a read only error for the mode's kind
+            // --------------------
+            let val = match result {
+                Err(BuildRequestError::Create(BuildCreateError::ReadOnly(k))) => {
+                    k == kind
+                }
+                _ => false,
+            };
+
+            TestResult::from_bool(val)
+        }
+
+        fn filename_with_separator(head: String, tail: String, backslash: bool)
+            -> TestResult
+        {
+            // --------------------
+            // GIVEN
+            // a filename containing a path separator and
+            // no other invalid characters and
+            // a writable OpenMode object and
+            // a RequestBuilder object
+            // --------------------
+            let sep = if backslash { "\\" } else { "/" };
+            let filename = format!("{}{}{}", head, sep, tail);
+            if invalid_string(&filename[..]) {
+                return TestResult::discard();
+            }
+            let mode = openmode().kind(OpenKind::Write).create();
+            let builder = request(42);
+
+            // --------------------
+            // WHEN
+            // RequestBuilder::create() is called w/ the filename
+            // --------------------
+            let result = builder.create(1, &filename[..], mode);
+
+            // --------------------
+            // THEN
+            // the result is a BuildRequestError::Create error and
+            // the cause is a path separator name error
+            // --------------------
+            let val = match result {
+                Err(BuildRequestError::Create(
+                    BuildCreateError::NameError(e),
+                )) => {
+                    e.field == "filename"
+                        && e.reason == ArgReason::PathSeparator
+                        && e.to_string()
+                            == "filename is not allowed to contain a path \
+                                separator"
+                }
+                _ => false,
+            };
+
+            TestResult::from_bool(val)
+        }
+
+        fn clean_filename(filename: String) -> TestResult
+        {
+            // --------------------
+            // GIVEN
+            // a filename without whitespace, control characters, or path
+            // separators and
+            // a writable OpenMode object and
+            // a RequestBuilder object
+            // --------------------
+            if invalid_string(&filename[..]) || has_separator(&filename[..]) {
+                return TestResult::discard();
+            }
+            let mode = openmode().kind(OpenKind::Write).create();
+            let builder = request(42);
+
+            // --------------------
+            // WHEN
+            // RequestBuilder::create() is called w/ the filename
+            // --------------------
+            let result = builder.create(1, &filename[..], mode);
+
+            // --------------------
+            // THEN
+            // a request message is returned
+            // --------------------
+            TestResult::from_bool(result.is_ok())
+        }
     }
 }
 
@@ -1016,7 +1318,8 @@ mod read {
     // Local imports
 
     use core::request::RpcRequest;
-    use message::v1::{request, RequestCode};
+    use message::v1::{request, BuildReadError, BuildRequestError,
+                      RequestCode};
 
     proptest! {
         #[test]
@@ -1060,6 +1363,129 @@ mod read {
             prop_assert_eq!(msg_offset, offset);
             prop_assert_eq!(msg_count, count);
         }
+
+        #[test]
+        fn nonzero_count(file_id in prop::num::u32::ANY,
+                         offset in prop::num::u64::ANY,
+                         count in 1..u32::max_value())
+        {
+            // --------------------
+            // GIVEN
+            // a u32 file id and
+            // a u64 offset and
+            // a non-zero u32 count and
+            // a request builder
+            // --------------------
+            let builder = request(42);
+
+            // --------------------
+            // WHEN
+            // RequestBuilder::read_nonzero() is called w/ file_id, offset,
+            //    and count
+            // --------------------
+            let result = builder.read_nonzero(file_id, offset, count);
+
+            // --------------------
+            // THEN
+            // a read request message is returned and
+            // the msg's count arg has the same value as count
+            // --------------------
+            prop_assert!(result.is_ok());
+            let msg = result.unwrap();
+            prop_assert_eq!(msg.message_method(), RequestCode::Read);
+
+            let args = msg.message_args();
+            prop_assert_eq!(args[2].as_u64().unwrap() as u32, count);
+        }
+    }
+
+    #[test]
+    fn zero_count_error()
+    {
+        // --------------------
+        // GIVEN
+        // a request builder
+        // --------------------
+        let builder = request(42);
+
+        // --------------------
+        // WHEN
+        // RequestBuilder::read_nonzero() is called w/ a count of 0
+        // --------------------
+        let result = builder.read_nonzero(0, 0, 0);
+
+        // --------------------
+        // THEN
+        // a read error is returned
+        // --------------------
+        let val = match result {
+            Err(BuildRequestError::Read(BuildReadError::ZeroCount)) => true,
+            _ => false,
+        };
+        assert!(val);
+    }
+
+    #[test]
+    fn overflow_error()
+    {
+        // --------------------
+        // GIVEN
+        // a request builder
+        // --------------------
+        let builder = request(42);
+
+        // --------------------
+        // WHEN
+        // RequestBuilder::read_checked() is called w/ an offset of u64::MAX
+        //    and a count of 10
+        // --------------------
+        let result = builder.read_checked(0, u64::max_value(), 10);
+
+        // --------------------
+        // THEN
+        // a read overflow error is returned
+        // --------------------
+        let val = match result {
+            Err(BuildRequestError::Read(BuildReadError::Overflow {
+                offset,
+                count,
+            })) => offset == u64::max_value() && count == 10,
+            _ => false,
+        };
+        assert!(val);
+    }
+
+    proptest! {
+        #[test]
+        fn checked_no_overflow(file_id in prop::num::u32::ANY,
+                               count in prop::num::u32::ANY,
+                               offset in prop::num::u64::ANY)
+        {
+            // --------------------
+            // GIVEN
+            // a u32 file id and
+            // a u32 count and
+            // a u64 offset where offset + count does not overflow and
+            // a request builder
+            // --------------------
+            let offset = offset.min(u64::max_value() - count as u64);
+            let builder = request(42);
+
+            // --------------------
+            // WHEN
+            // RequestBuilder::read_checked() is called w/ file_id, offset,
+            //    and count
+            // --------------------
+            let result = builder.read_checked(file_id, offset, count);
+
+            // --------------------
+            // THEN
+            // the same request as RequestBuilder::read() is returned
+            // --------------------
+            prop_assert!(result.is_ok());
+            let expected = request(42).read(file_id, offset, count);
+            prop_assert_eq!(result.unwrap(), expected);
+        }
     }
 }
 
@@ -1337,6 +1763,505 @@ mod remove {
 }
 
 
+mod credentials {
+    // Third party imports
+
+    use quickcheck::TestResult;
+    use rmpv::Value;
+
+    // Local imports
+
+    use message::v1::{request, CredentialsError, ProtocolRequest, Request,
+                      RequestCode};
+
+    // Helpers
+    use test::message::v1::invalid_string;
+
+    // Build an auth request without going through RequestBuilder, so that
+    // invalid names can be used
+    fn raw_auth(user: Value, fs: Value) -> Request
+    {
+        let msgargs = vec![Value::from(1), user, fs];
+        Request::new(42, RequestCode::Auth, msgargs)
+    }
+
+    quickcheck! {
+        fn clean_pair(fileid: u32, user: String, fs: String) -> TestResult
+        {
+            // Ignore bad strings
+            if invalid_string(&user[..]) || invalid_string(&fs[..]) {
+                return TestResult::discard();
+            }
+
+            // --------------------
+            // GIVEN
+            // valid user and filesystem names and
+            // an auth request built from them
+            // --------------------
+            let req = request(42).auth(fileid, &user[..], &fs[..]).unwrap();
+
+            // --------------------
+            // WHEN
+            // ProtocolRequest::credentials() is called
+            // --------------------
+            let result = req.credentials();
+
+            // --------------------
+            // THEN
+            // the user and filesystem names are returned
+            // --------------------
+            let val = match result {
+                Ok((u, f)) => u == user && f == fs,
+                Err(_) => false,
+            };
+            TestResult::from_bool(val)
+        }
+
+        fn bad_username(user: String, fs: String) -> TestResult
+        {
+            // Ignore valid username and bad fs strings
+            if !invalid_string(&user[..]) || invalid_string(&fs[..]) {
+                return TestResult::discard();
+            }
+
+            // --------------------
+            // GIVEN
+            // an auth request w/ an invalid user name
+            // --------------------
+            let req = raw_auth(Value::from(&user[..]), Value::from(&fs[..]));
+
+            // --------------------
+            // WHEN
+            // ProtocolRequest::credentials() is called
+            // --------------------
+            let result = req.credentials();
+
+            // --------------------
+            // THEN
+            // a name error for the user name is returned
+            // --------------------
+            let val = match result {
+                Err(e @ CredentialsError::NameError(_)) => {
+                    e.to_string().starts_with("username ")
+                }
+                _ => false,
+            };
+            TestResult::from_bool(val)
+        }
+
+        fn bad_fsname(user: String, fs: String) -> TestResult
+        {
+            // Ignore bad username and valid fs strings
+            if invalid_string(&user[..]) || !invalid_string(&fs[..]) {
+                return TestResult::discard();
+            }
+
+            // --------------------
+            // GIVEN
+            // an auth request w/ an invalid filesystem name
+            // --------------------
+            let req = raw_auth(Value::from(&user[..]), Value::from(&fs[..]));
+
+            // --------------------
+            // WHEN
+            // ProtocolRequest::credentials() is called
+            // --------------------
+            let result = req.credentials();
+
+            // --------------------
+            // THEN
+            // a name error for the filesystem name is returned
+            // --------------------
+            let val = match result {
+                Err(e @ CredentialsError::NameError(_)) => {
+                    e.to_string().starts_with("filesystem name ")
+                }
+                _ => false,
+            };
+            TestResult::from_bool(val)
+        }
+    }
+
+    #[test]
+    fn bad_string_cases()
+    {
+        for bad in &["", "a b", "a\tb", "a\u{7}b", "\n"] {
+            // --------------------
+            // GIVEN
+            // an auth request w/ an empty user name, or a user name
+            // containing whitespace or control characters
+            // --------------------
+            let req = raw_auth(Value::from(*bad), Value::from("world"));
+
+            // --------------------
+            // WHEN
+            // ProtocolRequest::credentials() is called
+            // --------------------
+            let result = req.credentials();
+
+            // --------------------
+            // THEN
+            // a name error is returned
+            // --------------------
+            let val = match result {
+                Err(CredentialsError::NameError(_)) => true,
+                _ => false,
+            };
+            assert!(val, "{:?} was accepted", bad);
+        }
+    }
+
+    #[test]
+    fn non_string_arg()
+    {
+        // --------------------
+        // GIVEN
+        // an auth request whose filesystem name is an integer
+        // --------------------
+        let req = raw_auth(Value::from("hello"), Value::from(9001));
+
+        // --------------------
+        // WHEN
+        // ProtocolRequest::credentials() is called
+        // --------------------
+        let result = req.credentials();
+
+        // --------------------
+        // THEN
+        // a NotString error naming the filesystem name is returned
+        // --------------------
+        let val = match result {
+            Err(CredentialsError::NotString(ref var)) => {
+                var == "filesystem name"
+            }
+            _ => false,
+        };
+        assert!(val);
+    }
+
+    #[test]
+    fn not_auth_request()
+    {
+        // --------------------
+        // GIVEN
+        // a clunk request
+        // --------------------
+        let req = request(42).clunk(1);
+
+        // --------------------
+        // WHEN
+        // ProtocolRequest::credentials() is called
+        // --------------------
+        let result = req.credentials();
+
+        // --------------------
+        // THEN
+        // a WrongCode error is returned
+        // --------------------
+        let val = match result {
+            Err(CredentialsError::WrongCode(RequestCode::Clunk)) => true,
+            _ => false,
+        };
+        assert!(val);
+    }
+}
+
+mod name_error {
+    // Local imports
+
+    use message::v1::{openmode, request, ArgReason, BuildCreateError,
+                      BuildRequestError, CheckNameError, OpenKind};
+
+    #[test]
+    fn auth_fsname_field()
+    {
+        // --------------------
+        // GIVEN
+        // a request builder
+        // --------------------
+        let builder = request(42);
+
+        // --------------------
+        // WHEN
+        // RequestBuilder::auth() is called w/ a filesystem name containing
+        // whitespace
+        // --------------------
+        let result = builder.auth(1, "hello", "big world");
+
+        // --------------------
+        // THEN
+        // the name error names the filesystem name field and
+        // the reason is that whitespace is not allowed
+        // --------------------
+        match result {
+            Err(BuildRequestError::Auth(CheckNameError { field, reason })) => {
+                assert_eq!(field, "filesystem name");
+                assert_eq!(reason, ArgReason::WSNotPrintable);
+            }
+            _ => panic!("expected a name error"),
+        }
+    }
+
+    #[test]
+    fn create_filename_field()
+    {
+        // --------------------
+        // GIVEN
+        // a request builder and
+        // a writable open mode
+        // --------------------
+        let builder = request(42);
+        let mode = openmode().kind(OpenKind::Write).create();
+
+        // --------------------
+        // WHEN
+        // RequestBuilder::create() is called w/ an empty filename
+        // --------------------
+        let result = builder.create(1, "", mode);
+
+        // --------------------
+        // THEN
+        // the name error names the filename field and
+        // its display keeps the original phrasing
+        // --------------------
+        match result {
+            Err(BuildRequestError::Create(BuildCreateError::NameError(e))) => {
+                assert_eq!(e.field, "filename");
+                assert_eq!(e.reason, ArgReason::WSNotPrintable);
+                let expected = "filename is either empty, contains \
+                                whitespace, or contains control characters";
+                assert_eq!(e.to_string(), expected);
+            }
+            _ => panic!("expected a name error"),
+        }
+    }
+}
+
+mod require_distinct_ids {
+    // Third party imports
+
+    use quickcheck::TestResult;
+
+    // Local imports
+
+    use message::v1::{require_distinct_ids, BuildRequestError};
+
+    quickcheck! {
+        fn distinct_ids(ids: Vec<u32>) -> TestResult
+        {
+            let mut sorted = ids.clone();
+            sorted.sort();
+            sorted.dedup();
+            if sorted.len() != ids.len() {
+                return TestResult::discard();
+            }
+
+            // --------------------
+            // GIVEN
+            // a list of distinct file ids
+            // --------------------
+            // --------------------
+            // WHEN
+            // require_distinct_ids() is called w/ the list
+            // --------------------
+            let result = require_distinct_ids(&ids[..]);
+
+            // --------------------
+            // THEN
+            // Ok is returned
+            // --------------------
+            TestResult::from_bool(result.is_ok())
+        }
+    }
+
+    #[test]
+    fn duplicate_id()
+    {
+        // --------------------
+        // GIVEN
+        // a list of file ids where 7 appears at positions 1 and 3 and
+        // 9 appears at positions 2 and 4
+        // --------------------
+        let ids = [1, 7, 9, 7, 9];
+
+        // --------------------
+        // WHEN
+        // require_distinct_ids() is called w/ the list
+        // --------------------
+        let result = require_distinct_ids(&ids);
+
+        // --------------------
+        // THEN
+        // an error naming the first duplicate pair is returned
+        // --------------------
+        match result {
+            Err(e @ BuildRequestError::DuplicateID { .. }) => {
+                let expected = "Duplicate file id (7): args 1 and 3 must be \
+                                distinct";
+                assert_eq!(e.to_string(), expected);
+            }
+            _ => panic!("expected a DuplicateID error"),
+        }
+    }
+}
+
+mod fixed_overhead {
+    // Third party imports
+
+    use proptest::prelude::*;
+    use rmpv::Value;
+
+    // Local imports
+
+    use core::AsBytes;
+    use message::v1::{fixed_overhead, request, RequestCode};
+
+    proptest! {
+        #[test]
+        fn write_large_payload_exact(
+            msgid in prop::num::u32::ANY,
+            file_id in prop::num::u32::ANY,
+            offset in prop::num::u64::ANY,
+            extra in 0usize..1024)
+        {
+            // --------------------
+            // GIVEN
+            // a write request with a payload longer than 65535 bytes
+            // --------------------
+            let data = vec![0u8; 65536 + extra];
+            let count = data.len() as u32;
+            let req = request(msgid).write(file_id, offset, count, &data)
+                .unwrap();
+
+            // --------------------
+            // WHEN
+            // fixed_overhead() is called with the write's fixed args
+            // --------------------
+            let fixed_args = vec![Value::from(file_id), Value::from(offset),
+                                  Value::from(count)];
+            let result = fixed_overhead(msgid, RequestCode::Write,
+                                        &fixed_args);
+
+            // --------------------
+            // THEN
+            // the overhead plus the payload length is the encoded length
+            // --------------------
+            prop_assert_eq!(result + data.len(), req.as_bytes().len());
+        }
+
+        #[test]
+        fn write_small_payload_bound(
+            msgid in prop::num::u32::ANY,
+            file_id in prop::num::u32::ANY,
+            ref data in prop::collection::vec(prop::num::u8::ANY, 0..300))
+        {
+            // --------------------
+            // GIVEN
+            // a write request with a short payload
+            // --------------------
+            let count = data.len() as u32;
+            let req = request(msgid).write(file_id, 0, count, data).unwrap();
+
+            // --------------------
+            // WHEN
+            // fixed_overhead() is called with the write's fixed args
+            // --------------------
+            let fixed_args = vec![Value::from(file_id), Value::from(0),
+                                  Value::from(count)];
+            let result = fixed_overhead(msgid, RequestCode::Write,
+                                        &fixed_args);
+
+            // --------------------
+            // THEN
+            // the overhead plus the payload length is at most 3 bytes more
+            // than the encoded length
+            // --------------------
+            let total = result + data.len();
+            let encoded_len = req.as_bytes().len();
+            prop_assert!(total >= encoded_len);
+            prop_assert!(total - encoded_len <= 3);
+        }
+    }
+}
+
+
+
+mod reserve_zero_id {
+
+    // Third party imports
+
+    use rmpv::Value;
+
+    // Local imports
+
+    use core::request::{IDPolicy, RpcRequest};
+    use message::v1::{request_with_policy, Request, RequestCode};
+
+    #[test]
+    fn policy_enabled()
+    {
+        // --------------------
+        // GIVEN
+        // a policy that reserves message id 0
+        // --------------------
+        let policy = IDPolicy { reserve_zero_id: true };
+
+        // --------------------
+        // WHEN
+        // requests are created with message ids 0 and 1
+        // --------------------
+        let zero = request_with_policy(0, &policy);
+        let one = request_with_policy(1, &policy);
+        let zero_msg =
+            Request::new_with_policy(0, RequestCode::Clunk, vec![], &policy);
+        let one_msg = Request::new_with_policy(
+            1,
+            RequestCode::Clunk,
+            vec![Value::from(42)],
+            &policy,
+        );
+
+        // --------------------
+        // THEN
+        // id 0 is an error and
+        // id 1 creates the request
+        // --------------------
+        match zero {
+            Err(e) => assert_eq!(e.msgid, 0),
+            Ok(_) => panic!("expected a reserved id error"),
+        }
+        assert_eq!(one.ok().unwrap().clunk(42).message_id(), 1);
+        assert_eq!(zero_msg.unwrap_err().msgid, 0);
+        assert_eq!(one_msg.unwrap().message_id(), 1);
+    }
+
+    #[test]
+    fn policy_disabled()
+    {
+        // --------------------
+        // GIVEN
+        // the default policy
+        // --------------------
+        let policy = IDPolicy::default();
+
+        // --------------------
+        // WHEN
+        // requests are created with message id 0
+        // --------------------
+        let builder = request_with_policy(0, &policy);
+        let msg =
+            Request::new_with_policy(0, RequestCode::Clunk, vec![], &policy);
+
+        // --------------------
+        // THEN
+        // both requests are created with id 0
+        // --------------------
+        assert_eq!(builder.ok().unwrap().clunk(42).message_id(), 0);
+        assert_eq!(msg.unwrap().message_id(), 0);
+    }
+}
+
+
 // ===========================================================================
 //
 // ===========================================================================
@@ -70,6 +70,37 @@ mod auth {
         assert!(val);
     }
 
+    #[test]
+    fn non_auth_fileid()
+    {
+        // --------------------
+        // GIVEN
+        // a request message and
+        // a valid file id without the AUTH kind and
+        // a response builder
+        // --------------------
+        let req = request(42).auth(9001, "hello", "world").unwrap();
+        let fileid = FileID::new(FileKind::FILE, 0, 0);
+        assert!(fileid.is_valid());
+
+        // --------------------
+        // WHEN
+        // ResponseBuilder::auth() is called w/ the file id
+        // --------------------
+        let result = response(&req).auth(fileid);
+
+        // --------------------
+        // THEN
+        // an auth error holding the file id's kind is returned
+        // --------------------
+        match result {
+            Err(BuildResponseError::Auth(kind)) => {
+                assert_eq!(kind, FileKind::FILE.bits())
+            }
+            _ => panic!("expected an auth error"),
+        }
+    }
+
     quickcheck! {
         fn valid_fileid(filekind: u8, version: u32, path: u64) -> TestResult {
             let invalid: u8 = 0b00000111;
@@ -80,15 +111,15 @@ mod auth {
             }
             let kind = FileKind::from_bits(filekind).unwrap();
 
-            // discard invalid filekind values
-            if !kind.is_valid() {
+            // discard invalid and non-auth filekind values
+            if !kind.is_valid() || !kind.contains(FileKind::AUTH) {
                 return TestResult::discard();
             }
 
             // --------------------
             // GIVEN
             // a request message and
-            // a valid FileID and
+            // a valid auth FileID and
             // a response builder
             // --------------------
             let req = request(42).auth(9001, "hello", "world").unwrap();
@@ -191,37 +222,31 @@ mod flush {
 
     // Local imports
 
-    use core::request::RpcRequest;
     use core::response::RpcResponse;
     use message::v1::{request, response, BuildResponseError, ResponseCode};
 
+    // Helpers
+    use test::message::v1::exchange;
+
     #[test]
     fn valid_message_id()
     {
         // --------------------
         // GIVEN
-        // a flush request and
-        // a response builder
+        // a flush request
         // --------------------
         let req = request(42).flush(41).unwrap();
-        let builder = response(&req);
 
         // --------------------
         // WHEN
         // ResponseBuilder::flush() is called
-        // --------------------
-        let result = builder.flush();
-
-        // --------------------
+        //
         // THEN
         // a response message is returned and
-        // the response's id is the same as the request's id
+        // exchange() confirms the response's id is the same as the
+        // request's id
         // --------------------
-        let val = match result {
-            Ok(msg) => msg.message_id() == req.message_id(),
-            Err(_) => false,
-        };
-        assert!(val);
+        exchange(req, |req| response(req).flush().unwrap());
     }
 
     #[test]
@@ -229,28 +254,22 @@ mod flush {
     {
         // --------------------
         // GIVEN
-        // a flush request and
-        // a response builder
+        // a flush request
         // --------------------
         let req = request(42).flush(41).unwrap();
-        let builder = response(&req);
 
         // --------------------
         // WHEN
         // ResponseBuilder::flush() is called
         // --------------------
-        let result = builder.flush();
+        let (_, resp) = exchange(req, |req| response(req).flush().unwrap());
 
         // --------------------
         // THEN
         // a response message is returned and
         // the response's code is ResponseCode::Flush
         // --------------------
-        let val = match result {
-            Ok(msg) => msg.error_code() == ResponseCode::Flush,
-            Err(_) => false,
-        };
-        assert!(val);
+        assert_eq!(resp.error_code(), ResponseCode::Flush);
     }
 
     #[test]
@@ -468,6 +487,41 @@ mod attach {
             TestResult::from_bool(val)
         }
     }
+
+    #[test]
+    fn attach_dir()
+    {
+        // --------------------
+        // GIVEN
+        // an attach request message and
+        // a directory file id and
+        // a plain file id
+        // --------------------
+        let req = request(42).attach(0, 1, "hello", "world").unwrap();
+        let dir = FileID::new(FileKind::DIR, 0, 1);
+        let file = FileID::new(FileKind::FILE, 0, 1);
+
+        // --------------------
+        // WHEN
+        // ResponseBuilder::attach_dir() is called w/ both file ids
+        // --------------------
+        let dir_result = response(&req).attach_dir(dir);
+        let file_result = response(&req).attach_dir(file);
+
+        // --------------------
+        // THEN
+        // the directory file id gives the same response as attach() and
+        // the plain file id gives an attach error
+        // --------------------
+        let expected = response(&req).attach(dir).unwrap();
+        assert_eq!(dir_result.unwrap(), expected);
+        match file_result {
+            Err(BuildResponseError::Attach(kind)) => {
+                assert_eq!(kind, FileKind::FILE.bits())
+            }
+            _ => panic!("expected an attach error"),
+        }
+    }
 }
 
 mod walk {
@@ -561,6 +615,11 @@ mod walk {
         fn has_valid_fileid(path_id: Vec<u8>, version: u32, pathpart: u64)
             -> TestResult
         {
+            // A non-empty walk must resolve at least one file id
+            if path_id.is_empty() {
+                return TestResult::discard();
+            }
+
             let invalid: u8 = 0b00000111;
             let mut path: Vec<FileID> = Vec::with_capacity(path_id.len());
 
@@ -693,6 +752,111 @@ mod walk {
             TestResult::from_bool(val)
         }
     }
+
+    #[test]
+    fn empty_path_clones_fileid()
+    {
+        // --------------------
+        // GIVEN
+        // a walk request message w/ an empty path and
+        // a response builder
+        // --------------------
+        let req = request(42).walk(41, 42, vec![]).unwrap();
+
+        // --------------------
+        // WHEN
+        // ResponseBuilder::walk() is called w/ an empty vec of file ids
+        // --------------------
+        let result = response(&req).walk(&[]);
+
+        // --------------------
+        // THEN
+        // the result is a walk response message and
+        // the message's result is an empty array
+        // --------------------
+        let msg = result.unwrap();
+        assert_eq!(msg.message_id(), req.message_id());
+        assert_eq!(msg.error_code(), ResponseCode::Walk);
+        assert!(msg.result().as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn nonempty_path_no_fileid()
+    {
+        // --------------------
+        // GIVEN
+        // a walk request message w/ a 2 element path and
+        // a response builder
+        // --------------------
+        let req = request(42)
+            .walk(41, 42, vec!["hello", "world"])
+            .unwrap();
+
+        // --------------------
+        // WHEN
+        // ResponseBuilder::walk() is called w/ an empty vec of file ids
+        // --------------------
+        let result = response(&req).walk(&[]);
+
+        // --------------------
+        // THEN
+        // a WalkEmpty error is returned
+        // --------------------
+        let val = match result {
+            Err(e @ BuildResponseError::WalkEmpty(2)) => {
+                let expected = "Unable to build walk response message: \
+                                request walks 2 path elements but path_id \
+                                is empty";
+                e.to_string() == expected
+            }
+            _ => false,
+        };
+        assert!(val);
+    }
+
+    #[test]
+    fn walk_dir()
+    {
+        // --------------------
+        // GIVEN
+        // a walk request message w/ a 2 element path and
+        // directory file ids for both path elements and
+        // file ids where the last path element is a plain file
+        // --------------------
+        let req = request(42)
+            .walk(41, 42, vec!["hello", "world"])
+            .unwrap();
+        let dirs = [
+            FileID::new(FileKind::DIR, 0, 1),
+            FileID::new(FileKind::DIR | FileKind::TMP, 0, 2),
+        ];
+        let notdir = [
+            FileID::new(FileKind::DIR, 0, 1),
+            FileID::new(FileKind::FILE, 0, 2),
+        ];
+
+        // --------------------
+        // WHEN
+        // ResponseBuilder::walk_dir() is called w/ both sets of file ids
+        // --------------------
+        let dir_result = response(&req).walk_dir(&dirs);
+        let notdir_result = response(&req).walk_dir(&notdir);
+
+        // --------------------
+        // THEN
+        // the directory file ids give the same response as walk() and
+        // the plain file id gives a walk error for the last path element
+        // --------------------
+        let expected = response(&req).walk(&dirs).unwrap();
+        assert_eq!(dir_result.unwrap(), expected);
+        match notdir_result {
+            Err(BuildResponseError::Walk { index, kind }) => {
+                assert_eq!(index, 1);
+                assert_eq!(kind, FileKind::FILE.bits());
+            }
+            _ => panic!("expected a walk error"),
+        }
+    }
 }
 
 
@@ -880,10 +1044,10 @@ mod create {
     use core::request::RpcRequest;
     use core::response::RpcResponse;
     use message::v1::{request, response, BuildResponseError, FileID, FileKind,
-                      OpenMode, ResponseCode};
+                      OpenKind, OpenMode, ResponseCode};
 
     // Helpers
-    use test::message::v1::invalid_string;
+    use test::message::v1::{has_separator, invalid_string};
 
     quickcheck! {
         fn has_invalid_fileid(filename: String,
@@ -894,7 +1058,7 @@ mod create {
                               path: u64) -> TestResult
         {
             // Discard invalid filenames
-            if invalid_string(&filename[..]) {
+            if invalid_string(&filename[..]) || has_separator(&filename[..]) {
                 return TestResult::discard();
             }
 
@@ -930,6 +1094,12 @@ mod create {
 
                 Ok(m) => m,
             };
+
+            // Discard any mode that does not allow writing
+            match open_mode.kind() {
+                OpenKind::Write | OpenKind::ReadWrite => {}
+                _ => return TestResult::discard(),
+            }
             let req = request(42).create(client_file_id, &filename[..], open_mode).unwrap();
             let builder = response(&req);
 
@@ -966,7 +1136,7 @@ mod create {
                             path: u64) -> TestResult
         {
             // Discard invalid filenames
-            if invalid_string(&filename[..]) {
+            if invalid_string(&filename[..]) || has_separator(&filename[..]) {
                 return TestResult::discard();
             }
 
@@ -1002,6 +1172,12 @@ mod create {
 
                 Ok(m) => m,
             };
+
+            // Discard any mode that does not allow writing
+            match open_mode.kind() {
+                OpenKind::Write | OpenKind::ReadWrite => {}
+                _ => return TestResult::discard(),
+            }
             let req = request(42).create(client_file_id, &filename[..], open_mode).unwrap();
             let builder = response(&req);
 
@@ -1528,6 +1704,436 @@ mod remove {
 }
 
 
+mod as_fileid {
+    // Third party imports
+
+    use rmpv::Value;
+
+    // Local imports
+
+    use message::v1::{request, FileKind, ProtocolResponse, Response,
+                      ResponseCode};
+    use core::request::RpcRequest;
+
+    #[test]
+    fn version_too_big()
+    {
+        // --------------------
+        // GIVEN
+        // an auth response whose file id version is u32::MAX + 1
+        // --------------------
+        let req = request(42).auth(9001, "hello", "world").unwrap();
+        let fileid = Value::Array(vec![
+            Value::from(FileKind::AUTH.bits()),
+            Value::from(u32::max_value() as u64 + 1),
+            Value::from(42),
+        ]);
+        let resp = Response::new(req.message_id(), ResponseCode::Auth, fileid);
+
+        // --------------------
+        // WHEN
+        // ProtocolResponse::as_fileid() is called
+        // --------------------
+        let result = resp.as_fileid();
+
+        // --------------------
+        // THEN
+        // None is returned
+        // --------------------
+        assert!(result.is_none());
+    }
+}
+
+
+mod as_open_exclusive {
+    // Local imports
+
+    use core::response::RpcResponse;
+    use message::v1::{openmode, request, response, FileID, FileKind,
+                      OpenKind, ProtocolResponse};
+
+    #[test]
+    fn with_flag()
+    {
+        for &exclusive in &[true, false] {
+            // --------------------
+            // GIVEN
+            // an open request and
+            // a valid file id
+            // --------------------
+            let mode = openmode().kind(OpenKind::Read).create();
+            let req = request(42).open(1, mode);
+            let file_id = FileID::new(FileKind::FILE, 0, 7);
+
+            // --------------------
+            // WHEN
+            // ResponseBuilder::open_exclusive() is called w/ the exclusive
+            //    flag
+            // --------------------
+            let resp = response(&req)
+                .open_exclusive(file_id, 0, exclusive)
+                .unwrap();
+
+            // --------------------
+            // THEN
+            // the result has 3 items and
+            // ProtocolResponse::as_open_exclusive() returns the flag
+            // --------------------
+            assert_eq!(resp.result().as_array().unwrap().len(), 3);
+            assert_eq!(resp.as_open_exclusive(), exclusive);
+        }
+    }
+
+    #[test]
+    fn without_flag()
+    {
+        // --------------------
+        // GIVEN
+        // an open request and
+        // a valid file id
+        // --------------------
+        let mode = openmode().kind(OpenKind::Read).create();
+        let req = request(42).open(1, mode);
+        let file_id = FileID::new(FileKind::FILE, 0, 7);
+
+        // --------------------
+        // WHEN
+        // ResponseBuilder::open() is called
+        // --------------------
+        let resp = response(&req).open(file_id, 0).unwrap();
+
+        // --------------------
+        // THEN
+        // ProtocolResponse::as_open_exclusive() returns false
+        // --------------------
+        assert_eq!(resp.result().as_array().unwrap().len(), 2);
+        assert!(!resp.as_open_exclusive());
+    }
+
+    #[test]
+    fn not_open_response()
+    {
+        // --------------------
+        // GIVEN
+        // a clunk response
+        // --------------------
+        let req = request(42).clunk(1);
+        let resp = response(&req).clunk().unwrap();
+
+        // --------------------
+        // WHEN
+        // ProtocolResponse::as_open_exclusive() is called
+        // --------------------
+        let result = resp.as_open_exclusive();
+
+        // --------------------
+        // THEN
+        // false is returned
+        // --------------------
+        assert!(!result);
+    }
+}
+
+mod read_parts {
+    // Third party imports
+
+    use proptest::prelude::*;
+    use rmpv::Value;
+
+    // Local imports
+
+    use message::v1::{request, response, ProtocolResponse, ReadPartsError,
+                      Response, ResponseCode};
+
+    proptest! {
+        #[test]
+        fn borrowed_data(ref data in prop::collection::vec(
+            prop::num::u8::ANY, 0..256
+        ))
+        {
+            // --------------------
+            // GIVEN
+            // a read request and
+            // a read response built from a vec of bytes
+            // --------------------
+            let req = request(42).read(1, 0, 256);
+            let resp = response(&req).read(data.len() as u32, data).unwrap();
+
+            // --------------------
+            // WHEN
+            // ProtocolResponse::read_parts() is called
+            // --------------------
+            let result = resp.read_parts();
+
+            // --------------------
+            // THEN
+            // the count matches the number of bytes and
+            // the borrowed slice matches the original bytes
+            // --------------------
+            let (count, slice) = result.unwrap();
+            prop_assert_eq!(count as usize, data.len());
+            prop_assert_eq!(slice, &data[..]);
+        }
+    }
+
+    #[test]
+    fn count_mismatch()
+    {
+        // --------------------
+        // GIVEN
+        // a read response whose count is larger than its data
+        // --------------------
+        let result = vec![Value::from(5), Value::Binary(vec![1, 2, 3])];
+        let resp = Response::new(42, ResponseCode::Read, Value::Array(result));
+
+        // --------------------
+        // WHEN
+        // ProtocolResponse::read_parts() is called
+        // --------------------
+        let result = resp.read_parts();
+
+        // --------------------
+        // THEN
+        // a CountMismatch error is returned
+        // --------------------
+        let val = match result {
+            Err(ReadPartsError::CountMismatch(5, 3)) => true,
+            _ => false,
+        };
+        assert!(val);
+    }
+
+    #[test]
+    fn malformed_result()
+    {
+        // --------------------
+        // GIVEN
+        // a read response whose data is a string instead of binary
+        // --------------------
+        let result = vec![Value::from(3), Value::from("abc")];
+        let resp = Response::new(42, ResponseCode::Read, Value::Array(result));
+
+        // --------------------
+        // WHEN
+        // ProtocolResponse::read_parts() is called
+        // --------------------
+        let result = resp.read_parts();
+
+        // --------------------
+        // THEN
+        // a Malformed error is returned
+        // --------------------
+        let val = match result {
+            Err(ReadPartsError::Malformed) => true,
+            _ => false,
+        };
+        assert!(val);
+    }
+
+    #[test]
+    fn not_read_response()
+    {
+        // --------------------
+        // GIVEN
+        // a clunk response
+        // --------------------
+        let req = request(42).clunk(1);
+        let resp = response(&req).clunk().unwrap();
+
+        // --------------------
+        // WHEN
+        // ProtocolResponse::read_parts() is called
+        // --------------------
+        let result = resp.read_parts();
+
+        // --------------------
+        // THEN
+        // a WrongCode error is returned
+        // --------------------
+        let val = match result {
+            Err(ReadPartsError::WrongCode(ResponseCode::Clunk)) => true,
+            _ => false,
+        };
+        assert!(val);
+    }
+}
+
+mod as_attach {
+    // Third party imports
+
+    use rmpv::Value;
+
+    // Local imports
+
+    use message::v1::{request, response, AsAttachError, FileID, FileKind,
+                      ProtocolResponse, Response, ResponseCode};
+
+    #[test]
+    fn valid_response()
+    {
+        // --------------------
+        // GIVEN
+        // an attach request and
+        // an attach response w/ a valid root directory file id
+        // --------------------
+        let req = request(42).attach(1, 2, "hello", "world").unwrap();
+        let rootdir_id = FileID::new(FileKind::DIR, 3, 7);
+        let resp = response(&req).attach(rootdir_id).unwrap();
+
+        // --------------------
+        // WHEN
+        // ProtocolResponse::as_attach() is called
+        // --------------------
+        let result = resp.as_attach();
+
+        // --------------------
+        // THEN
+        // the root directory file id is returned
+        // --------------------
+        assert_eq!(result.unwrap(), rootdir_id);
+    }
+
+    #[test]
+    fn invalid_kind()
+    {
+        // --------------------
+        // GIVEN
+        // an attach response whose file id has both the DIR and AUTH kind
+        // bits set
+        // --------------------
+        let kind = FileKind::DIR | FileKind::AUTH;
+        let fileid = vec![
+            Value::from(kind.bits()),
+            Value::from(0),
+            Value::from(7),
+        ];
+        let resp =
+            Response::new(42, ResponseCode::Attach, Value::Array(fileid));
+
+        // --------------------
+        // WHEN
+        // ProtocolResponse::as_attach() is called
+        // --------------------
+        let result = resp.as_attach();
+
+        // --------------------
+        // THEN
+        // an InvalidKind error is returned
+        // --------------------
+        let val = match result {
+            Err(AsAttachError::InvalidKind(k)) => k == kind.bits(),
+            _ => false,
+        };
+        assert!(val);
+    }
+
+    #[test]
+    fn not_fileid()
+    {
+        // --------------------
+        // GIVEN
+        // an attach response whose result is a 2 item array
+        // --------------------
+        let result = vec![Value::from(0), Value::from(0)];
+        let resp =
+            Response::new(42, ResponseCode::Attach, Value::Array(result));
+
+        // --------------------
+        // WHEN
+        // ProtocolResponse::as_attach() is called
+        // --------------------
+        let result = resp.as_attach();
+
+        // --------------------
+        // THEN
+        // a FileID error is returned
+        // --------------------
+        let val = match result {
+            Err(AsAttachError::FileID(_)) => true,
+            _ => false,
+        };
+        assert!(val);
+    }
+
+    #[test]
+    fn not_attach_response()
+    {
+        // --------------------
+        // GIVEN
+        // a clunk response
+        // --------------------
+        let req = request(42).clunk(1);
+        let resp = response(&req).clunk().unwrap();
+
+        // --------------------
+        // WHEN
+        // ProtocolResponse::as_attach() is called
+        // --------------------
+        let result = resp.as_attach();
+
+        // --------------------
+        // THEN
+        // a WrongCode error is returned
+        // --------------------
+        let val = match result {
+            Err(AsAttachError::WrongCode(ResponseCode::Clunk)) => true,
+            _ => false,
+        };
+        assert!(val);
+    }
+}
+
+mod not_request {
+    // Third party imports
+
+    use rmpv::Value;
+
+    // Local imports
+
+    use core::{CodeConvert, FromMessage, Message, MessageType};
+    use message::v1::{response, BuildResponseError, Request, RequestCode};
+
+    #[test]
+    fn notification_shaped_message()
+    {
+        // --------------------
+        // GIVEN
+        // a request wrapping a notification shaped message and
+        // a request wrapping a 4 item message with the notification type
+        // --------------------
+        let msgtype = Value::from(MessageType::Notification.to_number());
+        let code = Value::from(RequestCode::Clunk.to_number());
+        let notice = Value::Array(vec![msgtype.clone(), code.clone(),
+                                       Value::Array(vec![])]);
+        let long_notice = Value::Array(vec![msgtype, Value::from(42), code,
+                                            Value::Array(vec![])]);
+        let requests: Vec<Request> = vec![notice, long_notice]
+            .into_iter()
+            .map(|v| Request::from_msg_unchecked(Message::from_msg(v).unwrap()))
+            .collect();
+
+        for req in &requests {
+            // --------------------
+            // WHEN
+            // a clunk response is built for the request
+            // --------------------
+            let result = response(req).clunk();
+
+            // --------------------
+            // THEN
+            // a not request error is returned
+            // --------------------
+            match result {
+                Err(BuildResponseError::NotRequest(t)) => {
+                    assert_eq!(t, MessageType::Notification)
+                }
+                _ => panic!("expected a not request error"),
+            }
+        }
+    }
+}
+
+
 // ===========================================================================
 //
 // ===========================================================================
@@ -0,0 +1,1025 @@
+// src/test/message/v1/spec.rs
+// Copyright (C) 2017 authors and contributors (see AUTHORS file)
+//
+// This file is released under the MIT License.
+
+// ===========================================================================
+// Imports
+// ===========================================================================
+
+
+// Stdlib imports
+
+// Third-party imports
+
+// Local imports
+
+// ===========================================================================
+// Tests
+// ===========================================================================
+
+
+mod expected_request_args {
+    // Local imports
+
+    use message::v1::{expected_request_args, RequestCode};
+
+    #[test]
+    fn all_codes()
+    {
+        // --------------------
+        // GIVEN
+        // every request code and
+        // the number of args documented for each code
+        // --------------------
+        let expected = vec![
+            (RequestCode::Auth, 3),
+            (RequestCode::Flush, 1),
+            (RequestCode::Attach, 4),
+            (RequestCode::Walk, 3),
+            (RequestCode::Open, 2),
+            (RequestCode::Create, 3),
+            (RequestCode::Read, 3),
+            (RequestCode::Write, 4),
+            (RequestCode::Clunk, 1),
+            (RequestCode::Remove, 1),
+            (RequestCode::Stat, 1),
+            (RequestCode::WStat, 2),
+        ];
+
+        for (code, numargs) in expected {
+            // --------------------
+            // WHEN
+            // expected_request_args() is called with the code
+            // --------------------
+            let result = expected_request_args(code.clone());
+
+            // --------------------
+            // THEN
+            // the documented number of args is returned
+            // --------------------
+            assert_eq!(result, numargs, "{:?}", code);
+        }
+    }
+}
+
+
+mod expected_response_args {
+    // Local imports
+
+    use message::v1::{expected_response_args, ResponseCode};
+
+    #[test]
+    fn all_codes()
+    {
+        // --------------------
+        // GIVEN
+        // every response code and
+        // the number of args documented for each code
+        // --------------------
+        let expected = vec![
+            (ResponseCode::Auth, 1),
+            (ResponseCode::Flush, 0),
+            (ResponseCode::Attach, 1),
+            (ResponseCode::Walk, 1),
+            (ResponseCode::Open, 2),
+            (ResponseCode::Create, 2),
+            (ResponseCode::Read, 2),
+            (ResponseCode::Write, 1),
+            (ResponseCode::Clunk, 0),
+            (ResponseCode::Remove, 0),
+            (ResponseCode::Stat, 1),
+            (ResponseCode::WStat, 0),
+        ];
+
+        for (code, numargs) in expected {
+            // --------------------
+            // WHEN
+            // expected_response_args() is called with the code
+            // --------------------
+            let result = expected_response_args(code.clone());
+
+            // --------------------
+            // THEN
+            // the documented number of args is returned
+            // --------------------
+            assert_eq!(result, numargs, "{:?}", code);
+        }
+    }
+}
+
+
+mod file_id_arg_positions {
+    // Local imports
+
+    use message::v1::{file_id_arg_positions, RequestCode};
+
+    #[test]
+    fn all_codes()
+    {
+        // --------------------
+        // GIVEN
+        // every request code and
+        // the positions of the file id args for each code
+        // --------------------
+        let expected: Vec<(RequestCode, &[usize])> = vec![
+            (RequestCode::Auth, &[0]),
+            (RequestCode::Flush, &[]),
+            (RequestCode::Attach, &[0, 1]),
+            (RequestCode::Walk, &[0, 1]),
+            (RequestCode::Open, &[0]),
+            (RequestCode::Create, &[0]),
+            (RequestCode::Read, &[0]),
+            (RequestCode::Write, &[0]),
+            (RequestCode::Clunk, &[0]),
+            (RequestCode::Remove, &[0]),
+            (RequestCode::Stat, &[0]),
+            (RequestCode::WStat, &[0]),
+        ];
+
+        for (code, positions) in expected {
+            // --------------------
+            // WHEN
+            // file_id_arg_positions() is called with the code
+            // --------------------
+            let result = file_id_arg_positions(code.clone());
+
+            // --------------------
+            // THEN
+            // the file id arg positions are returned
+            // --------------------
+            assert_eq!(result, positions, "{:?}", code);
+        }
+    }
+}
+
+mod validate_arity {
+    // Third party imports
+
+    use rmpv::Value;
+
+    // Local imports
+
+    use message::v1::{expected_request_args, ArityError, Request,
+                      RequestCode};
+
+    fn all_codes() -> Vec<RequestCode>
+    {
+        vec![
+            RequestCode::Auth,
+            RequestCode::Flush,
+            RequestCode::Attach,
+            RequestCode::Walk,
+            RequestCode::Open,
+            RequestCode::Create,
+            RequestCode::Read,
+            RequestCode::Write,
+            RequestCode::Clunk,
+            RequestCode::Remove,
+            RequestCode::Stat,
+            RequestCode::WStat,
+        ]
+    }
+
+    #[test]
+    fn correct_count()
+    {
+        for code in all_codes() {
+            // --------------------
+            // GIVEN
+            // a request with the expected number of args for its code
+            // --------------------
+            let numargs = expected_request_args(code.clone());
+            let args = vec![Value::Nil; numargs];
+            let req = Request::new(42, code.clone(), args);
+
+            // --------------------
+            // WHEN
+            // Request::validate_arity() is called
+            // --------------------
+            let result = req.validate_arity();
+
+            // --------------------
+            // THEN
+            // Ok is returned
+            // --------------------
+            assert!(result.is_ok(), "{:?}", code);
+        }
+    }
+
+    #[test]
+    fn wrong_count()
+    {
+        for code in all_codes() {
+            // --------------------
+            // GIVEN
+            // a request with one more arg than expected for its code
+            // --------------------
+            let numargs = expected_request_args(code.clone());
+            let args = vec![Value::Nil; numargs + 1];
+            let req = Request::new(42, code.clone(), args);
+
+            // --------------------
+            // WHEN
+            // Request::validate_arity() is called
+            // --------------------
+            let result = req.validate_arity();
+
+            // --------------------
+            // THEN
+            // an error is returned with the expected and actual counts
+            // --------------------
+            let val = match result {
+                Err(ArityError { expected, actual }) => {
+                    expected == numargs && actual == numargs + 1
+                }
+                _ => false,
+            };
+            assert!(val, "{:?}", code);
+        }
+    }
+
+    #[test]
+    fn auth_missing_arg()
+    {
+        // --------------------
+        // GIVEN
+        // an auth request with only 2 args
+        // --------------------
+        let args = vec![Value::from(1), Value::from("user")];
+        let req = Request::new(42, RequestCode::Auth, args);
+
+        // --------------------
+        // WHEN
+        // Request::validate_arity() is called
+        // --------------------
+        let result = req.validate_arity();
+
+        // --------------------
+        // THEN
+        // an error is returned and
+        // the error message reports the expected and actual counts
+        // --------------------
+        let val = match result {
+            Err(e) => {
+                e.to_string()
+                    == "Invalid number of arguments: expected 3, got 2"
+            }
+            _ => false,
+        };
+        assert!(val);
+    }
+}
+
+mod validate_arity_mode {
+    // Third party imports
+
+    use rmpv::Value;
+
+    // Local imports
+
+    use message::v1::{ArityError, ArityMode, Request, RequestCode};
+
+    // Build an auth request with the given number of args
+    fn auth_request(numargs: usize) -> Request
+    {
+        Request::new(42, RequestCode::Auth, vec![Value::Nil; numargs])
+    }
+
+    #[test]
+    fn strict_exact()
+    {
+        // --------------------
+        // GIVEN
+        // an auth request with exactly 3 args
+        // --------------------
+        let req = auth_request(3);
+
+        // --------------------
+        // WHEN
+        // Request::validate_arity_mode() is called in strict mode
+        // --------------------
+        let result = req.validate_arity_mode(ArityMode::Strict);
+
+        // --------------------
+        // THEN
+        // Ok is returned
+        // --------------------
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn strict_too_few()
+    {
+        // --------------------
+        // GIVEN
+        // an auth request with 2 args
+        // --------------------
+        let req = auth_request(2);
+
+        // --------------------
+        // WHEN
+        // Request::validate_arity_mode() is called in strict mode
+        // --------------------
+        let result = req.validate_arity_mode(ArityMode::Strict);
+
+        // --------------------
+        // THEN
+        // an error is returned
+        // --------------------
+        let val = match result {
+            Err(ArityError {
+                expected: 3,
+                actual: 2,
+            }) => true,
+            _ => false,
+        };
+        assert!(val);
+    }
+
+    #[test]
+    fn strict_too_many()
+    {
+        // --------------------
+        // GIVEN
+        // an auth request with 4 args
+        // --------------------
+        let req = auth_request(4);
+
+        // --------------------
+        // WHEN
+        // Request::validate_arity_mode() is called in strict mode
+        // --------------------
+        let result = req.validate_arity_mode(ArityMode::Strict);
+
+        // --------------------
+        // THEN
+        // an error is returned
+        // --------------------
+        let val = match result {
+            Err(ArityError {
+                expected: 3,
+                actual: 4,
+            }) => true,
+            _ => false,
+        };
+        assert!(val);
+    }
+
+    #[test]
+    fn lenient()
+    {
+        // --------------------
+        // GIVEN
+        // auth requests with 2, 3, and 4 args
+        // --------------------
+        let too_few = auth_request(2);
+        let exact = auth_request(3);
+        let too_many = auth_request(4);
+
+        // --------------------
+        // WHEN
+        // Request::validate_arity_mode() is called in lenient mode
+        // --------------------
+        let too_few = too_few.validate_arity_mode(ArityMode::Lenient);
+        let exact = exact.validate_arity_mode(ArityMode::Lenient);
+        let too_many = too_many.validate_arity_mode(ArityMode::Lenient);
+
+        // --------------------
+        // THEN
+        // only the request with too few args is an error
+        // --------------------
+        assert!(too_few.is_err());
+        assert!(exact.is_ok());
+        assert!(too_many.is_ok());
+    }
+}
+
+mod validate_spec {
+    // Third party imports
+
+    use rmpv::Value;
+
+    // Local imports
+
+    use message::v1::{openmode, request, BuildCreateError, BuildRequestError,
+                      OpenKind, Request, RequestCode, SpecError};
+
+    // Check that validate_spec() fails with an error matching f
+    fn assert_spec_error<F>(req: Request, f: F)
+    where
+        F: Fn(&SpecError) -> bool,
+    {
+        match req.validate_spec() {
+            Err(ref e) if f(e) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn valid_requests()
+    {
+        // --------------------
+        // GIVEN
+        // valid requests built with the request builder
+        // --------------------
+        let mode = openmode().kind(OpenKind::ReadWrite).create();
+        let requests = vec![
+            request(1).auth(9001, "hello", "world").unwrap(),
+            request(2).flush(1).unwrap(),
+            request(3).attach(1, 9001, "hello", "world").unwrap(),
+            request(4).walk(1, 2, vec!["a", "b"]).unwrap(),
+            request(5).open(2, mode),
+            request(6).create(1, "file", mode).unwrap(),
+            request(7).read(2, 0, 10),
+            request(8).write(2, 0, 3, &vec![1, 2, 3]).unwrap(),
+            request(9).clunk(2),
+            request(10).remove(2),
+        ];
+
+        for req in requests {
+            // --------------------
+            // WHEN
+            // Request::validate_spec() is called
+            // --------------------
+            let result = req.validate_spec();
+
+            // --------------------
+            // THEN
+            // Ok is returned
+            // --------------------
+            assert!(result.is_ok(), "{:?}", req);
+        }
+    }
+
+    #[test]
+    fn wrong_arity()
+    {
+        // GIVEN an auth request with 2 args
+        let args = vec![Value::from(1), Value::from("hello")];
+        let req = Request::new(42, RequestCode::Auth, args);
+
+        // WHEN/THEN validate_spec() returns an arity error
+        assert_spec_error(req, |e| match *e {
+            SpecError::Arity(_) => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn wrong_arg_type()
+    {
+        // GIVEN an auth request whose file id is a string
+        let args = vec![
+            Value::from("1"),
+            Value::from("hello"),
+            Value::from("world"),
+        ];
+        let req = Request::new(42, RequestCode::Auth, args);
+
+        // WHEN/THEN validate_spec() returns an arg type error for arg 0
+        assert_spec_error(req, |e| match *e {
+            SpecError::ArgType { index: 0, .. } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn file_id_out_of_range()
+    {
+        // GIVEN an auth request whose file id does not fit into a u32
+        let args = vec![
+            Value::from(u64::max_value()),
+            Value::from("hello"),
+            Value::from("world"),
+        ];
+        let req = Request::new(42, RequestCode::Auth, args);
+
+        // WHEN/THEN validate_spec() returns an arg range error for arg 0
+        assert_spec_error(req, |e| match *e {
+            SpecError::ArgRange { index: 0, .. } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn invalid_name()
+    {
+        // GIVEN an auth request whose username contains whitespace
+        let args = vec![
+            Value::from(1),
+            Value::from("hello world"),
+            Value::from("world"),
+        ];
+        let req = Request::new(42, RequestCode::Auth, args);
+
+        // WHEN/THEN validate_spec() returns the auth builder error
+        assert_spec_error(req, |e| match *e {
+            SpecError::Build(BuildRequestError::Auth(_)) => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn flush_own_id()
+    {
+        // GIVEN a flush request for its own message id
+        let req = Request::new(42, RequestCode::Flush, vec![Value::from(42)]);
+
+        // WHEN/THEN validate_spec() returns the flush builder error
+        assert_spec_error(req, |e| match *e {
+            SpecError::Build(BuildRequestError::Flush(42)) => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn invalid_mode_bits()
+    {
+        // GIVEN an open request whose mode has invalid bits set
+        let args = vec![Value::from(1), Value::from(0b00111100)];
+        let req = Request::new(42, RequestCode::Open, args);
+
+        // WHEN/THEN validate_spec() returns an invalid mode error
+        assert_spec_error(req, |e| match *e {
+            SpecError::InvalidMode(_) => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn create_read_only()
+    {
+        // GIVEN a create request with a read only mode
+        let args = vec![Value::from(1), Value::from("file"), Value::from(0)];
+        let req = Request::new(42, RequestCode::Create, args);
+
+        // WHEN/THEN validate_spec() returns the create builder error
+        assert_spec_error(req, |e| match *e {
+            SpecError::Build(BuildRequestError::Create(
+                BuildCreateError::ReadOnly(_),
+            )) => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn walk_path_not_strings()
+    {
+        // GIVEN a walk request whose path contains an integer
+        let path = Value::Array(vec![Value::from("a"), Value::from(1)]);
+        let args = vec![Value::from(1), Value::from(2), path];
+        let req = Request::new(42, RequestCode::Walk, args);
+
+        // WHEN/THEN validate_spec() returns an arg type error for arg 2
+        assert_spec_error(req, |e| match *e {
+            SpecError::ArgType { index: 2, .. } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn write_count_mismatch()
+    {
+        // GIVEN a write request whose count does not match its data
+        let args = vec![
+            Value::from(1),
+            Value::from(0),
+            Value::from(4),
+            Value::Binary(vec![1, 2, 3]),
+        ];
+        let req = Request::new(42, RequestCode::Write, args);
+
+        // WHEN/THEN validate_spec() returns the write builder error
+        assert_spec_error(req, |e| match *e {
+            SpecError::Build(BuildRequestError::Write(4, 3)) => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn wstat_unique_keys()
+    {
+        // GIVEN a wstat request whose map has distinct keys
+        let stat = Value::Map(vec![
+            (Value::from("mode"), Value::from(0o644)),
+            (Value::from("name"), Value::from("file")),
+        ]);
+        let req = Request::new(42, RequestCode::WStat,
+                               vec![Value::from(1), stat]);
+
+        // WHEN/THEN validate_spec() returns Ok
+        assert!(req.validate_spec().is_ok());
+    }
+
+    #[test]
+    fn wstat_duplicate_key()
+    {
+        // GIVEN a wstat request whose map has two "mode" keys
+        let stat = Value::Map(vec![
+            (Value::from("mode"), Value::from(0o644)),
+            (Value::from("name"), Value::from("file")),
+            (Value::from("mode"), Value::from(0o600)),
+        ]);
+        let req = Request::new(42, RequestCode::WStat,
+                               vec![Value::from(1), stat]);
+
+        // WHEN/THEN validate_spec() returns an error naming the "mode" key
+        assert_spec_error(req, |e| match *e {
+            SpecError::DuplicateKey { index: 1, ref key } => key == "mode",
+            _ => false,
+        });
+    }
+}
+
+mod validate_result_shape {
+    // Third-party imports
+
+    use rmpv::Value;
+
+    // Local imports
+
+    use message::v1::{Response, ResponseCode};
+
+    fn fileid() -> Value
+    {
+        Value::Array(vec![Value::from(0), Value::from(1), Value::from(7)])
+    }
+
+    #[test]
+    fn well_shaped()
+    {
+        let results = vec![
+            (ResponseCode::Auth, fileid()),
+            (ResponseCode::Flush, Value::Nil),
+            (ResponseCode::Attach, fileid()),
+            (ResponseCode::Walk, Value::Array(vec![fileid(), fileid()])),
+            (ResponseCode::Walk, Value::Array(vec![])),
+            (
+                ResponseCode::Open,
+                Value::Array(vec![fileid(), Value::from(0)]),
+            ),
+            (
+                ResponseCode::Open,
+                Value::Array(vec![fileid(), Value::from(0), Value::from(true)]),
+            ),
+            (
+                ResponseCode::Create,
+                Value::Array(vec![fileid(), Value::from(0)]),
+            ),
+            (
+                ResponseCode::Read,
+                Value::Array(vec![Value::from(2), Value::Binary(vec![1, 2])]),
+            ),
+            (ResponseCode::Write, Value::from(2)),
+            (ResponseCode::Clunk, Value::Nil),
+            (ResponseCode::Remove, Value::Nil),
+            (ResponseCode::Stat, Value::Map(vec![])),
+            (ResponseCode::WStat, Value::Nil),
+        ];
+
+        for (code, result) in results {
+            // --------------------
+            // GIVEN
+            // a response whose result has the shape documented for its code
+            // --------------------
+            let resp = Response::new(42, code.clone(), result);
+
+            // --------------------
+            // WHEN
+            // Response::validate_result_shape() is called
+            // --------------------
+            let ret = resp.validate_result_shape();
+
+            // --------------------
+            // THEN
+            // Ok is returned
+            // --------------------
+            assert!(ret.is_ok(), "{:?} was rejected", code);
+        }
+    }
+
+    #[test]
+    fn mis_shaped()
+    {
+        let results = vec![
+            (ResponseCode::Auth, Value::Array(vec![Value::from(0)])),
+            (ResponseCode::Flush, Value::from(0)),
+            (ResponseCode::Walk, Value::Array(vec![Value::from(0)])),
+            (ResponseCode::Open, Value::Array(vec![fileid()])),
+            (
+                ResponseCode::Create,
+                Value::Array(vec![fileid(), Value::from(0), Value::from(true)]),
+            ),
+            (
+                ResponseCode::Read,
+                Value::Array(vec![Value::from(2), Value::from("ab")]),
+            ),
+            (ResponseCode::Write, Value::from(-1)),
+            (ResponseCode::Clunk, Value::from(true)),
+            (ResponseCode::Stat, Value::Nil),
+        ];
+
+        for (code, result) in results {
+            // --------------------
+            // GIVEN
+            // a response whose result does not have the shape documented for
+            // its code
+            // --------------------
+            let resp = Response::new(42, code.clone(), result);
+
+            // --------------------
+            // WHEN
+            // Response::validate_result_shape() is called
+            // --------------------
+            let ret = resp.validate_result_shape();
+
+            // --------------------
+            // THEN
+            // a ShapeError for the code is returned
+            // --------------------
+            match ret {
+                Err(e) => assert_eq!(e.code, code),
+                Ok(_) => panic!("{:?} was accepted", code),
+            }
+        }
+    }
+
+    #[test]
+    fn error_message()
+    {
+        // --------------------
+        // GIVEN
+        // a clunk response w/ an int result
+        // --------------------
+        let resp = Response::new(42, ResponseCode::Clunk, Value::from(1));
+
+        // --------------------
+        // WHEN
+        // Response::validate_result_shape() is called
+        // --------------------
+        let ret = resp.validate_result_shape();
+
+        // --------------------
+        // THEN
+        // the error names the code, the expected shape, and the actual type
+        // --------------------
+        let expected = "Invalid Clunk response result: expected nil, got int";
+        assert_eq!(ret.unwrap_err().to_string(), expected);
+    }
+}
+
+
+mod validate_response {
+    // Third-party imports
+
+    use rmpv::Value;
+
+    // Local imports
+
+    use message::v1::{request, response, response_code_for,
+                      validate_response, FileID, FileKind, RequestCode,
+                      Response, ResponseCode, ResponseValidationError};
+
+    #[test]
+    fn response_codes()
+    {
+        // --------------------
+        // GIVEN
+        // request codes
+        // --------------------
+        // --------------------
+        // WHEN
+        // response_code_for() is called with each request code
+        // THEN
+        // the response code answering the request is returned
+        // --------------------
+        assert_eq!(response_code_for(RequestCode::Auth), ResponseCode::Auth);
+        assert_eq!(response_code_for(RequestCode::Walk), ResponseCode::Walk);
+        assert_eq!(response_code_for(RequestCode::WStat), ResponseCode::WStat);
+    }
+
+    #[test]
+    fn valid_auth_exchange()
+    {
+        // --------------------
+        // GIVEN
+        // an auth request and
+        // an auth response built for the request
+        // --------------------
+        let req = request(42).auth(1, "user", "fs").unwrap();
+        let resp = response(&req)
+            .auth(FileID::new(FileKind::AUTH, 0, 1))
+            .unwrap();
+
+        // --------------------
+        // WHEN
+        // validate_response() is called with the request and response
+        // --------------------
+        let result = validate_response(&req, &resp);
+
+        // --------------------
+        // THEN
+        // the response is valid
+        // --------------------
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn wrong_id()
+    {
+        // --------------------
+        // GIVEN
+        // an auth request and
+        // an auth response with a different msg id
+        // --------------------
+        let req = request(42).auth(1, "user", "fs").unwrap();
+        let fileid = Value::Array(vec![Value::from(0), Value::from(0),
+                                       Value::from(1)]);
+        let resp = Response::new(43, ResponseCode::Auth, fileid);
+
+        // --------------------
+        // WHEN
+        // validate_response() is called with the request and response
+        // --------------------
+        let result = validate_response(&req, &resp);
+
+        // --------------------
+        // THEN
+        // an id mismatch error is returned
+        // --------------------
+        match result {
+            Err(ResponseValidationError::IDMismatch {
+                expected: 42,
+                actual: 43,
+            }) => {}
+            _ => panic!("expected an id mismatch error"),
+        }
+    }
+
+    #[test]
+    fn wrong_code()
+    {
+        // --------------------
+        // GIVEN
+        // an auth request and
+        // a clunk response with the same msg id
+        // --------------------
+        let req = request(42).auth(1, "user", "fs").unwrap();
+        let resp = Response::new(42, ResponseCode::Clunk, Value::Nil);
+
+        // --------------------
+        // WHEN
+        // validate_response() is called with the request and response
+        // --------------------
+        let result = validate_response(&req, &resp);
+
+        // --------------------
+        // THEN
+        // a code mismatch error is returned
+        // --------------------
+        match result {
+            Err(ResponseValidationError::CodeMismatch {
+                request: RequestCode::Auth,
+                expected: ResponseCode::Auth,
+                actual: ResponseCode::Clunk,
+            }) => {}
+            _ => panic!("expected a code mismatch error"),
+        }
+    }
+
+    #[test]
+    fn wrong_shape()
+    {
+        // --------------------
+        // GIVEN
+        // an auth request and
+        // an auth response whose result is not a file id
+        // --------------------
+        let req = request(42).auth(1, "user", "fs").unwrap();
+        let resp = Response::new(42, ResponseCode::Auth, Value::Nil);
+
+        // --------------------
+        // WHEN
+        // validate_response() is called with the request and response
+        // --------------------
+        let result = validate_response(&req, &resp);
+
+        // --------------------
+        // THEN
+        // a shape error is returned
+        // --------------------
+        match result {
+            Err(ResponseValidationError::Shape(_)) => {}
+            _ => panic!("expected a shape error"),
+        }
+    }
+}
+
+
+mod legal_responses {
+    // Local imports
+
+    use core::CodeConvert;
+    use message;
+    use message::v1::{legal_responses, RequestCode, ResponseCode};
+
+    #[test]
+    fn auth_or_error()
+    {
+        // --------------------
+        // GIVEN
+        // the auth request code
+        // --------------------
+        let code = RequestCode::Auth;
+
+        // --------------------
+        // WHEN
+        // legal_responses() is called with the code
+        // --------------------
+        let result = legal_responses(code);
+
+        // --------------------
+        // THEN
+        // the auth and error response codes are returned
+        // --------------------
+        let auth = ResponseCode::Auth.to_u64();
+        let error = message::ResponseCode::Error.to_u64();
+        assert_eq!(result.len(), 2);
+        assert!(result.contains(&auth));
+        assert!(result.contains(&error));
+    }
+}
+
+
+
+mod validate_batch {
+    // Third party imports
+
+    use rmpv::Value;
+
+    // Local imports
+
+    use message::v1::{request, validate_batch, Request, RequestCode,
+                      SpecError};
+
+    #[test]
+    fn first_invalid_index()
+    {
+        // --------------------
+        // GIVEN
+        // a batch of requests where the third and fourth requests are
+        // invalid
+        // --------------------
+        let bad_arity = vec![Value::from(1)];
+        let batch = vec![
+            request(1).auth(9001, "hello", "world").unwrap(),
+            request(2).clunk(9001),
+            Request::new(3, RequestCode::Auth, bad_arity.clone()),
+            Request::new(4, RequestCode::Attach, bad_arity),
+        ];
+
+        // --------------------
+        // WHEN
+        // validate_batch() is called w/ the batch
+        // --------------------
+        let result = validate_batch(&batch);
+
+        // --------------------
+        // THEN
+        // the index and error of the third request are returned
+        // --------------------
+        match result {
+            Err((2, SpecError::Arity(_))) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn valid_batch()
+    {
+        // --------------------
+        // GIVEN
+        // an empty batch and
+        // a batch of valid requests
+        // --------------------
+        let batch = vec![
+            request(1).auth(9001, "hello", "world").unwrap(),
+            request(2).clunk(9001),
+        ];
+
+        // --------------------
+        // WHEN
+        // validate_batch() is called w/ both batches
+        // THEN
+        // Ok is returned
+        // --------------------
+        assert!(validate_batch(&[]).is_ok());
+        assert!(validate_batch(&batch).is_ok());
+    }
+}
+
+
+// ===========================================================================
+//
+// ===========================================================================
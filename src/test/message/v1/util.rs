@@ -610,9 +610,599 @@ mod openmode {
             assert_eq!(obj.flags(), OpenFlag::ONOFLAG);
         }
     }
+
+    #[cfg(unix)]
+    mod from_posix {
+        // Stdlib imports
+
+        // Third-party imports
+
+        use libc::{O_CREAT, O_RDONLY, O_RDWR, O_TRUNC, O_WRONLY};
+
+        // Local imports
+
+        use message::v1::{openmode, OpenFlag, OpenKind, OpenMode};
+
+        #[test]
+        fn rdwr_trunc()
+        {
+            // --------------------
+            // GIVEN
+            // O_RDWR | O_TRUNC open flags
+            // --------------------
+            let flags = O_RDWR | O_TRUNC;
+
+            // --------------------
+            // WHEN
+            // OpenMode::from_posix() is called with the flags
+            // --------------------
+            let result = OpenMode::from_posix(flags).unwrap();
+
+            // --------------------
+            // THEN
+            // the mode is a read/write mode with the truncate flag set
+            // --------------------
+            let expected = openmode()
+                .kind(OpenKind::ReadWrite)
+                .flags(OpenFlag::OTRUNC)
+                .create();
+            assert_eq!(result, expected);
+            assert_eq!(result.bits(), 0b10000010);
+        }
+
+        #[test]
+        fn access_modes()
+        {
+            // --------------------
+            // GIVEN
+            // each POSIX access mode
+            // --------------------
+            let cases = vec![
+                (O_RDONLY, OpenKind::Read),
+                (O_WRONLY, OpenKind::Write),
+                (O_RDWR, OpenKind::ReadWrite),
+            ];
+
+            for (flags, kind) in cases {
+                // --------------------
+                // WHEN
+                // OpenMode::from_posix() is called with the access mode
+                // --------------------
+                let result = OpenMode::from_posix(flags).unwrap();
+
+                // --------------------
+                // THEN
+                // the mode has the matching kind and no flags
+                // --------------------
+                assert_eq!(result.kind(), kind);
+                assert_eq!(result.flags(), OpenFlag::ONOFLAG);
+            }
+        }
+
+        #[test]
+        fn unsupported_flags_ignored()
+        {
+            // --------------------
+            // GIVEN
+            // O_WRONLY | O_CREAT open flags
+            // --------------------
+            let flags = O_WRONLY | O_CREAT;
+
+            // --------------------
+            // WHEN
+            // OpenMode::from_posix() is called with the flags
+            // --------------------
+            let result = OpenMode::from_posix(flags).unwrap();
+
+            // --------------------
+            // THEN
+            // O_CREAT is ignored
+            // --------------------
+            assert_eq!(result, openmode().kind(OpenKind::Write).create());
+        }
+
+        #[test]
+        fn invalid_access_mode()
+        {
+            // --------------------
+            // GIVEN
+            // open flags with both access mode bits set
+            // --------------------
+            let flags = O_WRONLY | O_RDWR;
+
+            // --------------------
+            // WHEN
+            // OpenMode::from_posix() is called with the flags
+            // --------------------
+            let result = OpenMode::from_posix(flags);
+
+            // --------------------
+            // THEN
+            // an error is returned
+            // --------------------
+            assert!(result.is_err());
+        }
+    }
+}
+
+
+mod can_open {
+    // Local imports
+
+    use message::v1::{can_open, openmode, FileID, FileKind, OpenError,
+                      OpenFlag, OpenKind};
+
+    #[test]
+    fn directory_read_only()
+    {
+        // --------------------
+        // GIVEN
+        // a directory file id and
+        // a read only open mode
+        // --------------------
+        let file_id = FileID::new(FileKind::DIR, 0, 42);
+        let mode = openmode().kind(OpenKind::Read).create();
+
+        // --------------------
+        // WHEN
+        // can_open() is called with the file id and mode
+        // --------------------
+        let result = can_open(&file_id, mode);
+
+        // --------------------
+        // THEN
+        // Ok is returned
+        // --------------------
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn directory_write()
+    {
+        // --------------------
+        // GIVEN
+        // a directory file id
+        // --------------------
+        let file_id = FileID::new(FileKind::DIR, 0, 42);
+
+        for &kind in &[OpenKind::Write, OpenKind::ReadWrite] {
+            // --------------------
+            // WHEN
+            // can_open() is called with the file id and a writable mode
+            // --------------------
+            let mode = openmode().kind(kind).create();
+            let result = can_open(&file_id, mode);
+
+            // --------------------
+            // THEN
+            // a DirWrite error is returned
+            // --------------------
+            let val = match result {
+                Err(OpenError::DirWrite(k)) => k == kind,
+                _ => false,
+            };
+            assert!(val);
+        }
+    }
+
+    #[test]
+    fn directory_truncate()
+    {
+        // --------------------
+        // GIVEN
+        // a directory file id and
+        // a read only open mode with the truncate flag set
+        // --------------------
+        let file_id = FileID::new(FileKind::DIR, 0, 42);
+        let mode = openmode()
+            .kind(OpenKind::Read)
+            .flags(OpenFlag::OTRUNC)
+            .create();
+
+        // --------------------
+        // WHEN
+        // can_open() is called with the file id and mode
+        // --------------------
+        let result = can_open(&file_id, mode);
+
+        // --------------------
+        // THEN
+        // a DirTruncate error is returned
+        // --------------------
+        let val = match result {
+            Err(OpenError::DirTruncate) => true,
+            _ => false,
+        };
+        assert!(val);
+    }
+
+    #[test]
+    fn file_write()
+    {
+        // --------------------
+        // GIVEN
+        // a regular file id and
+        // a read/write open mode with the truncate flag set
+        // --------------------
+        let file_id = FileID::new(FileKind::FILE, 0, 42);
+        let mode = openmode()
+            .kind(OpenKind::ReadWrite)
+            .flags(OpenFlag::OTRUNC)
+            .create();
+
+        // --------------------
+        // WHEN
+        // can_open() is called with the file id and mode
+        // --------------------
+        let result = can_open(&file_id, mode);
+
+        // --------------------
+        // THEN
+        // Ok is returned
+        // --------------------
+        assert!(result.is_ok());
+    }
+}
+
+mod filekind {
+    // Stdlib imports
+
+    // Third-party imports
+
+    // Local imports
+
+    use message::v1::{FileKind, FileKindError};
+
+    #[test]
+    fn validate_valid()
+    {
+        // --------------------
+        // GIVEN
+        // the bits of a valid combination of kinds
+        // --------------------
+        let bits = (FileKind::DIR | FileKind::TMP).bits();
+
+        // --------------------
+        // WHEN
+        // FileKind::validate() is called with the bits
+        // --------------------
+        let result = FileKind::validate(bits);
+
+        // --------------------
+        // THEN
+        // the FileKind holding both kinds is returned
+        // --------------------
+        assert_eq!(result.unwrap(), FileKind::DIR | FileKind::TMP);
+    }
+
+    #[test]
+    fn validate_unknown_bits()
+    {
+        // --------------------
+        // GIVEN
+        // the bits of the dir kind and an unused reserved bit
+        // --------------------
+        let bits = FileKind::DIR.bits() | 0b00000010;
+
+        // --------------------
+        // WHEN
+        // FileKind::validate() is called with the bits
+        // --------------------
+        let result = FileKind::validate(bits);
+
+        // --------------------
+        // THEN
+        // a FileKindError::UnknownBits error holding only the reserved bit
+        // is returned
+        // --------------------
+        let val = match result {
+            Err(FileKindError::UnknownBits(0b00000010)) => true,
+            _ => false,
+        };
+        assert!(val);
+    }
+
+    #[test]
+    fn validate_invalid_combination()
+    {
+        // --------------------
+        // GIVEN
+        // the bits of the dir and auth kinds
+        // --------------------
+        let bits = (FileKind::DIR | FileKind::AUTH).bits();
+
+        // --------------------
+        // WHEN
+        // FileKind::validate() is called with the bits
+        // --------------------
+        let result = FileKind::validate(bits);
+
+        // --------------------
+        // THEN
+        // a FileKindError::InvalidCombination error is returned
+        // --------------------
+        let val = match result {
+            Err(FileKindError::InvalidCombination(b)) => b == bits,
+            _ => false,
+        };
+        assert!(val);
+    }
 }
 
 
+mod fileid {
+
+    mod from_value {
+        // Stdlib imports
+
+        // Third-party imports
+
+        use failure::Fail;
+        use quickcheck::TestResult;
+        use rmpv::Value;
+
+        // Local imports
+
+        use message::v1::{FileID, FileIDError, FileKind};
+
+        quickcheck! {
+
+            fn valid_value(version: u32, path: u64) -> TestResult
+            {
+                // --------------------
+                // GIVEN
+                // a value holding a [kind, version, path] array
+                // --------------------
+                let kind = FileKind::DIR;
+                let val = Value::Array(vec![
+                    Value::from(kind.bits()),
+                    Value::from(version),
+                    Value::from(path),
+                ]);
+
+                // --------------------
+                // WHEN
+                // FileID::from_value() is called with the value
+                // --------------------
+                let result = FileID::from_value(&val);
+
+                // --------------------
+                // THEN
+                // a FileID holding the kind, version, and path is returned
+                // --------------------
+                let expected = FileID::new(kind, version, path);
+                let val = match result {
+                    Ok(fileid) => fileid == expected,
+                    Err(_) => false,
+                };
+                TestResult::from_bool(val)
+            }
+        }
+
+        #[test]
+        fn version_too_big()
+        {
+            // --------------------
+            // GIVEN
+            // a file id value with a version of u32::MAX + 1
+            // --------------------
+            let version = u32::max_value() as u64 + 1;
+            let val = Value::Array(vec![
+                Value::from(FileKind::FILE.bits()),
+                Value::from(version),
+                Value::from(42),
+            ]);
+
+            // --------------------
+            // WHEN
+            // FileID::from_value() is called with the value
+            // --------------------
+            let result = FileID::from_value(&val);
+
+            // --------------------
+            // THEN
+            // a FileIDError::InvalidVersion error is returned and
+            // the version is not truncated into a u32
+            // --------------------
+            let val = match result {
+                Err(e @ FileIDError::InvalidVersion(_)) => {
+                    let cause = e.cause().unwrap();
+                    let expected = format!(
+                        "Expected value <= {} but got value {}",
+                        u32::max_value(),
+                        version
+                    );
+                    e.to_string() == "Invalid file id version" &&
+                        cause.to_string() == expected
+                }
+                _ => false,
+            };
+            assert!(val);
+        }
+
+        #[test]
+        fn wrong_array_length()
+        {
+            // --------------------
+            // GIVEN
+            // a file id value with only 2 items
+            // --------------------
+            let val = Value::Array(vec![Value::from(0), Value::from(0)]);
+
+            // --------------------
+            // WHEN
+            // FileID::from_value() is called with the value
+            // --------------------
+            let result = FileID::from_value(&val);
+
+            // --------------------
+            // THEN
+            // a FileIDError::ArrayLength error is returned
+            // --------------------
+            let val = match result {
+                Err(FileIDError::ArrayLength(2)) => true,
+                _ => false,
+            };
+            assert!(val);
+        }
+
+        #[test]
+        fn unknown_kind()
+        {
+            // --------------------
+            // GIVEN
+            // a file id value with unknown kind bits
+            // --------------------
+            let val = Value::Array(vec![
+                Value::from(0b00000001),
+                Value::from(0),
+                Value::from(0),
+            ]);
+
+            // --------------------
+            // WHEN
+            // FileID::from_value() is called with the value
+            // --------------------
+            let result = FileID::from_value(&val);
+
+            // --------------------
+            // THEN
+            // a FileIDError::UnknownKind error is returned
+            // --------------------
+            let val = match result {
+                Err(FileIDError::UnknownKind(0b00000001)) => true,
+                _ => false,
+            };
+            assert!(val);
+        }
+    }
+}
+
+
+mod filemode {
+
+    mod query {
+        // Local imports
+
+        use message::v1::FileMode;
+
+        #[test]
+        fn regular_file()
+        {
+            // --------------------
+            // GIVEN
+            // a 0o644 file mode
+            // --------------------
+            let mode = FileMode::OWNER_READ | FileMode::OWNER_WRITE
+                | FileMode::GROUP_READ | FileMode::OTHER_READ;
+
+            // --------------------
+            // WHEN
+            // the mode is queried
+            // --------------------
+            // --------------------
+            // THEN
+            // the mode is a readable and writable non-directory and
+            // the mode is not executable and
+            // the mode's bits are 0o644
+            // --------------------
+            assert_eq!(mode.bits(), 0o644);
+            assert!(!mode.is_dir());
+            assert!(mode.is_readable());
+            assert!(mode.is_writable());
+            assert!(!mode.is_executable());
+        }
+
+        #[test]
+        fn directory()
+        {
+            // --------------------
+            // GIVEN
+            // a directory file mode with 0o755 permissions
+            // --------------------
+            let mode = FileMode::from_u32(0x80000000 | 0o755).unwrap();
+
+            // --------------------
+            // WHEN
+            // the mode is queried
+            // --------------------
+            // --------------------
+            // THEN
+            // the mode is a readable, writable, and executable directory and
+            // the group and other permissions are read and execute
+            // --------------------
+            assert!(mode.is_dir());
+            assert!(mode.is_readable());
+            assert!(mode.is_writable());
+            assert!(mode.is_executable());
+            let group_other_rx = FileMode::GROUP_READ | FileMode::GROUP_EXEC
+                | FileMode::OTHER_READ | FileMode::OTHER_EXEC;
+            let group_other_w = FileMode::GROUP_WRITE | FileMode::OTHER_WRITE;
+            assert!(mode.contains(group_other_rx));
+            assert!(!mode.intersects(group_other_w));
+        }
+    }
+
+    mod from_u32 {
+        // Third party imports
+
+        use proptest::prelude::*;
+
+        // Local imports
+
+        use message::v1::FileMode;
+
+        proptest! {
+            #[test]
+            fn valid_bits(bits in prop::num::u32::ANY)
+            {
+                // --------------------
+                // GIVEN
+                // a u32 with only mode bits set
+                // --------------------
+                let bits = bits & FileMode::all().bits();
+
+                // --------------------
+                // WHEN
+                // FileMode::from_u32() is called with the bits
+                // --------------------
+                let result = FileMode::from_u32(bits);
+
+                // --------------------
+                // THEN
+                // a FileMode with the same bits is returned
+                // --------------------
+                prop_assert_eq!(result.unwrap().bits(), bits);
+            }
+
+            #[test]
+            fn reserved_bits(bits in prop::num::u32::ANY)
+            {
+                // --------------------
+                // GIVEN
+                // a u32 with at least one reserved bit set
+                // --------------------
+                let reserved = bits & !FileMode::all().bits();
+                prop_assume!(reserved != 0);
+
+                // --------------------
+                // WHEN
+                // FileMode::from_u32() is called with the bits
+                // --------------------
+                let result = FileMode::from_u32(bits);
+
+                // --------------------
+                // THEN
+                // an error reporting the reserved bits is returned
+                // --------------------
+                let expected =
+                    format!("Reserved file mode bits set: {:b}", reserved);
+                prop_assert_eq!(result.unwrap_err().to_string(), expected);
+            }
+        }
+    }
+}
+
 // ===========================================================================
 //
 // ===========================================================================
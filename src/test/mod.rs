@@ -10,7 +10,10 @@
 
 mod core;
 mod future;
+mod fuzz;
 mod message;
+mod session;
+mod util;
 
 
 // ===========================================================================
@@ -0,0 +1,1052 @@
+// src/test/session.rs
+// Copyright (C) 2017 authors and contributors (see AUTHORS file)
+//
+// This file is released under the MIT License.
+
+// ===========================================================================
+// Imports
+// ===========================================================================
+
+
+// Stdlib imports
+
+// Third-party imports
+
+// Local imports
+
+// ===========================================================================
+// Tests
+// ===========================================================================
+
+
+mod pending {
+    // Local imports
+
+    use core::request::RpcRequest;
+    use message::v1::{request, RequestCode};
+    use session::Pending;
+
+    #[test]
+    fn empty()
+    {
+        // --------------------
+        // GIVEN
+        // a new Pending object
+        // --------------------
+        let pending: Pending<RequestCode> = Pending::new();
+
+        // --------------------
+        // WHEN
+        // Pending::len() and Pending::is_empty() are called
+        // --------------------
+        let len = pending.len();
+        let empty = pending.is_empty();
+
+        // --------------------
+        // THEN
+        // there are no pending requests
+        // --------------------
+        assert_eq!(len, 0);
+        assert!(empty);
+    }
+
+    #[test]
+    fn register_remove()
+    {
+        // --------------------
+        // GIVEN
+        // a Pending object and
+        // a registered request
+        // --------------------
+        let mut pending = Pending::new();
+        let old = pending.register(request(42).clunk(1));
+        assert!(old.is_none());
+
+        // --------------------
+        // WHEN
+        // Pending::remove() is called with the request's message id
+        // --------------------
+        let result = pending.remove(42);
+
+        // --------------------
+        // THEN
+        // the request is returned and
+        // there are no more pending requests
+        // --------------------
+        assert_eq!(result.unwrap().message_method(), RequestCode::Clunk);
+        assert!(pending.get(42).is_none());
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn filter_by_code()
+    {
+        // --------------------
+        // GIVEN
+        // a Pending object and
+        // registered requests with a mix of codes
+        // --------------------
+        let mut pending = Pending::new();
+        pending.register(request(1).clunk(100));
+        pending.register(request(2).remove(100));
+        pending.register(request(3).read(100, 0, 10));
+        pending.register(request(4).clunk(101));
+        pending.register(request(5).flush(2).unwrap());
+
+        // --------------------
+        // WHEN
+        // Pending::filter_by_code() is called with RequestCode::Clunk
+        // --------------------
+        let result = pending.filter_by_code(RequestCode::Clunk);
+
+        // --------------------
+        // THEN
+        // only the ids of the clunk requests are returned
+        // --------------------
+        assert_eq!(pending.len(), 5);
+        assert_eq!(result, vec![1, 4]);
+        assert!(pending.filter_by_code(RequestCode::Walk).is_empty());
+    }
+}
+
+
+mod build_walk {
+    // Local imports
+
+    use core::request::RpcRequest;
+    use message::v1::{request, response, BuildRequestError, BuildWalkError,
+                      FileID, FileKind, RequestCode};
+    use session::{OpenFiles, Pending, WalkError};
+
+    fn pending() -> Pending<RequestCode>
+    {
+        let mut pending = Pending::new();
+        pending.register(request(1).attach(10, 11, "user", "fs").unwrap());
+        pending.register(request(2).read(12, 0, 10));
+        pending
+    }
+
+    #[test]
+    fn fresh_newfile_id()
+    {
+        // --------------------
+        // GIVEN
+        // a Pending object with requests using file ids 10, 11, and 12
+        // --------------------
+        let pending = pending();
+        let files = OpenFiles::new();
+
+        // --------------------
+        // WHEN
+        // Pending::build_walk() is called with an unused newfile_id
+        // --------------------
+        let result = pending.build_walk(&files, 3, 10, 13, vec!["a", "b"]);
+
+        // --------------------
+        // THEN
+        // a walk request is returned
+        // --------------------
+        let req = result.unwrap();
+        assert_eq!(req.message_id(), 3);
+        assert_eq!(req.message_method(), RequestCode::Walk);
+    }
+
+    #[test]
+    fn newfile_id_in_use()
+    {
+        // --------------------
+        // GIVEN
+        // a Pending object with requests using file ids 10, 11, and 12
+        // --------------------
+        let pending = pending();
+        let files = OpenFiles::new();
+
+        for &newfile_id in &[10, 11, 12] {
+            // --------------------
+            // WHEN
+            // Pending::build_walk() is called with a newfile_id that is
+            //    already in use
+            // --------------------
+            let result =
+                pending.build_walk(&files, 3, 0, newfile_id, vec!["a"]);
+
+            // --------------------
+            // THEN
+            // a FileIDInUse error is returned
+            // --------------------
+            let val = match result {
+                Err(WalkError::FileIDInUse(id)) => id == newfile_id,
+                _ => false,
+            };
+            assert!(val);
+        }
+    }
+
+    #[test]
+    fn newfile_id_live()
+    {
+        // --------------------
+        // GIVEN
+        // an OpenFiles tracker that has seen an attach of file id 20
+        //    complete and
+        // a Pending object without any request using file id 20
+        // --------------------
+        let mut files = OpenFiles::new();
+        let rootdir = FileID::new(FileKind::DIR, 0, 1);
+        let req = request(1).attach(20, 0, "user", "fs").unwrap();
+        let resp = response(&req).attach(rootdir).unwrap();
+        files.observe_request(&req);
+        assert!(files.observe_response(&resp));
+        let pending = pending();
+
+        // --------------------
+        // WHEN
+        // Pending::build_walk() is called with the attached file id as the
+        //    newfile_id
+        // --------------------
+        let result = pending.build_walk(&files, 3, 10, 20, vec!["a"]);
+
+        // --------------------
+        // THEN
+        // a FileIDInUse error is returned
+        // --------------------
+        let val = match result {
+            Err(WalkError::FileIDInUse(20)) => true,
+            _ => false,
+        };
+        assert!(val);
+    }
+
+    #[test]
+    fn builder_error()
+    {
+        // --------------------
+        // GIVEN
+        // an empty Pending object
+        // --------------------
+        let pending = Pending::new();
+
+        // --------------------
+        // WHEN
+        // Pending::build_walk() is called with file_id == newfile_id
+        // --------------------
+        let result = pending.build_walk(&OpenFiles::new(), 3, 5, 5, vec![]);
+
+        // --------------------
+        // THEN
+        // the walk builder's error is returned
+        // --------------------
+        let val = match result {
+            Err(WalkError::Build(BuildRequestError::Walk(
+                BuildWalkError::MatchingID(5),
+            ))) => true,
+            _ => false,
+        };
+        assert!(val);
+    }
+}
+
+mod sequential {
+    // Local imports
+
+    use message::{request, response};
+    use session::{SequenceError, Sequential};
+
+    #[test]
+    fn matched_exchange()
+    {
+        // --------------------
+        // GIVEN
+        // a Sequential guard and
+        // a request and its response
+        // --------------------
+        let mut guard = Sequential::new();
+        let req = request(42).version(1);
+        let resp = response(&req).version(1).unwrap();
+
+        // --------------------
+        // WHEN
+        // the request is sent and
+        // the response is received
+        // --------------------
+        let sent = guard.send(&req);
+        let outstanding = guard.outstanding();
+        let received = guard.recv(&resp);
+
+        // --------------------
+        // THEN
+        // both calls succeed and
+        // no request is outstanding afterwards
+        // --------------------
+        assert!(sent.is_ok());
+        assert_eq!(outstanding, Some(42));
+        assert!(received.is_ok());
+        assert_eq!(guard.outstanding(), None);
+    }
+
+    #[test]
+    fn unsolicited_response()
+    {
+        // --------------------
+        // GIVEN
+        // a Sequential guard with no outstanding request and
+        // a response
+        // --------------------
+        let mut guard = Sequential::new();
+        let req = request(42).version(1);
+        let resp = response(&req).version(1).unwrap();
+
+        // --------------------
+        // WHEN
+        // the response is received
+        // --------------------
+        let result = guard.recv(&resp);
+
+        // --------------------
+        // THEN
+        // an Unsolicited error is returned
+        // --------------------
+        let val = match result {
+            Err(SequenceError::Unsolicited(42)) => true,
+            _ => false,
+        };
+        assert!(val);
+    }
+
+    #[test]
+    fn id_mismatch()
+    {
+        // --------------------
+        // GIVEN
+        // a Sequential guard and
+        // a sent request and
+        // a response to a different request
+        // --------------------
+        let mut guard = Sequential::new();
+        let req = request(42).version(1);
+        let other = request(9001).version(1);
+        let resp = response(&other).version(1).unwrap();
+        guard.send(&req).unwrap();
+
+        // --------------------
+        // WHEN
+        // the response is received
+        // --------------------
+        let result = guard.recv(&resp);
+
+        // --------------------
+        // THEN
+        // an IDMismatch error is returned and
+        // the request is still outstanding
+        // --------------------
+        let val = match result {
+            Err(SequenceError::IDMismatch {
+                expected: 42,
+                actual: 9001,
+            }) => true,
+            _ => false,
+        };
+        assert!(val);
+        assert_eq!(guard.outstanding(), Some(42));
+    }
+
+    #[test]
+    fn request_outstanding()
+    {
+        // --------------------
+        // GIVEN
+        // a Sequential guard and
+        // a sent request
+        // --------------------
+        let mut guard = Sequential::new();
+        guard.send(&request(42).version(1)).unwrap();
+
+        // --------------------
+        // WHEN
+        // another request is sent before a response is received
+        // --------------------
+        let result = guard.send(&request(43).version(1));
+
+        // --------------------
+        // THEN
+        // an Outstanding error is returned
+        // --------------------
+        let val = match result {
+            Err(SequenceError::Outstanding(42)) => true,
+            _ => false,
+        };
+        assert!(val);
+    }
+}
+
+mod monotonic {
+    // Third-party imports
+
+    use quickcheck::TestResult;
+
+    // Local imports
+
+    use session::{MonotonicGuard, OrderError};
+
+    quickcheck! {
+        fn increasing_ids(first: u32, step: u32) -> TestResult
+        {
+            let second = match first.checked_add(step) {
+                Some(s) if step > 0 => s,
+                _ => return TestResult::discard(),
+            };
+
+            // --------------------
+            // GIVEN
+            // a guard that has accepted the first id
+            // --------------------
+            let mut guard = MonotonicGuard::new();
+            guard.check(first).unwrap();
+
+            // --------------------
+            // WHEN
+            // MonotonicGuard::check() is called w/ a larger id
+            // --------------------
+            let result = guard.check(second);
+
+            // --------------------
+            // THEN
+            // the id is accepted and
+            // it becomes the previous id
+            // --------------------
+            TestResult::from_bool(
+                result.is_ok() && guard.previous() == Some(second)
+            )
+        }
+
+        fn not_increasing(first: u32, second: u32) -> TestResult
+        {
+            if first == u32::max_value() || second > first {
+                return TestResult::discard();
+            }
+
+            // --------------------
+            // GIVEN
+            // a guard that has accepted the first id
+            // --------------------
+            let mut guard = MonotonicGuard::new();
+            guard.check(first).unwrap();
+
+            // --------------------
+            // WHEN
+            // MonotonicGuard::check() is called w/ a repeated or smaller id
+            // --------------------
+            let result = guard.check(second);
+
+            // --------------------
+            // THEN
+            // an error naming both ids is returned and
+            // the previous id is unchanged
+            // --------------------
+            let val = match result {
+                Err(OrderError::NotIncreasing { previous, actual }) => {
+                    previous == first && actual == second
+                }
+                _ => false,
+            };
+            TestResult::from_bool(val && guard.previous() == Some(first))
+        }
+    }
+
+    #[test]
+    fn repeat()
+    {
+        // --------------------
+        // GIVEN
+        // a guard that has accepted id 42
+        // --------------------
+        let mut guard = MonotonicGuard::new();
+        guard.check(42).unwrap();
+
+        // --------------------
+        // WHEN
+        // MonotonicGuard::check() is called w/ id 42 again
+        // --------------------
+        let result = guard.check(42);
+
+        // --------------------
+        // THEN
+        // an error is returned
+        // --------------------
+        let expected = "Invalid msg id (42): must be greater than the \
+                        previous msg id (42)";
+        assert_eq!(result.unwrap_err().to_string(), expected);
+    }
+
+    #[test]
+    fn wrap_past_max()
+    {
+        // --------------------
+        // GIVEN
+        // a guard that has accepted u32::MAX
+        // --------------------
+        let mut guard = MonotonicGuard::new();
+        guard.check(u32::max_value()).unwrap();
+
+        // --------------------
+        // WHEN
+        // MonotonicGuard::check() is called w/ 0 and then 1
+        // --------------------
+        let wrap = guard.check(0);
+        let next = guard.check(1);
+
+        // --------------------
+        // THEN
+        // both ids are accepted and
+        // ids must increase again after the wrap
+        // --------------------
+        assert!(wrap.is_ok());
+        assert!(next.is_ok());
+        assert!(guard.wrapped());
+        assert!(guard.check(0).is_err());
+    }
+
+    #[test]
+    fn repeat_max()
+    {
+        // --------------------
+        // GIVEN
+        // a guard that has accepted u32::MAX
+        // --------------------
+        let mut guard = MonotonicGuard::new();
+        guard.check(u32::max_value()).unwrap();
+
+        // --------------------
+        // WHEN
+        // MonotonicGuard::check() is called w/ u32::MAX again
+        // --------------------
+        let result = guard.check(u32::max_value());
+
+        // --------------------
+        // THEN
+        // a not increasing error is returned and
+        // the ids have not wrapped
+        // --------------------
+        match result {
+            Err(OrderError::NotIncreasing { previous, actual }) => {
+                assert_eq!(previous, u32::max_value());
+                assert_eq!(actual, u32::max_value());
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(!guard.wrapped());
+    }
+
+    #[test]
+    fn second_wrap()
+    {
+        // --------------------
+        // GIVEN
+        // a guard whose ids have wrapped from u32::MAX to 0
+        // --------------------
+        let mut guard = MonotonicGuard::new();
+        guard.check(u32::max_value()).unwrap();
+        guard.check(0).unwrap();
+
+        // --------------------
+        // WHEN
+        // MonotonicGuard::check() is called w/ u32::MAX
+        // --------------------
+        let result = guard.check(u32::max_value());
+
+        // --------------------
+        // THEN
+        // an already wrapped error is returned and
+        // the previous id is unchanged
+        // --------------------
+        match result {
+            Err(OrderError::AlreadyWrapped(id)) => {
+                assert_eq!(id, u32::max_value())
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(guard.previous(), Some(0));
+        assert!(guard.check(1).is_ok());
+    }
+}
+
+mod exchange
+{
+    // Third-party imports
+
+    use rmpv::Value;
+
+    // Local imports
+
+    use core::request::RpcRequest;
+    use core::response::RpcResponse;
+    use message::v1::{request, BuildRequestError, FileID, FileKind,
+                      RequestCode, ResponseCode};
+    use session::Exchange;
+
+    #[test]
+    fn auth_request_and_response()
+    {
+        // --------------------
+        // GIVEN
+        // an exchange built from an auth request and
+        // an auth file id
+        // --------------------
+        let exchange = Exchange::build(42, |b| b.auth(1, "hello", "world"))
+            .unwrap();
+        let authfile = FileID::new(FileKind::AUTH, 0, 1);
+
+        // --------------------
+        // WHEN
+        // Exchange::response() is used to build an auth response
+        // --------------------
+        let result = exchange.response().auth(authfile);
+
+        // --------------------
+        // THEN
+        // the request is the auth request and
+        // the response answers the request with the auth file id
+        // --------------------
+        let req = exchange.request();
+        assert_eq!(req.message_id(), 42);
+        assert_eq!(req.message_method(), RequestCode::Auth);
+
+        let resp = result.unwrap();
+        assert_eq!(resp.message_id(), req.message_id());
+        assert_eq!(resp.error_code(), ResponseCode::Auth);
+        let expected = Value::Array(vec![
+            Value::from(authfile.kind.bits()),
+            Value::from(authfile.version),
+            Value::from(authfile.path),
+        ]);
+        assert_eq!(resp.result(), &expected);
+    }
+
+    #[test]
+    fn build_error()
+    {
+        // --------------------
+        // GIVEN
+        // a request builder closure that fails
+        // --------------------
+        // --------------------
+        // WHEN
+        // Exchange::build() is called with the closure
+        // --------------------
+        let result = Exchange::build(42, |b| b.auth(1, "", "world"));
+
+        // --------------------
+        // THEN
+        // the builder's error is returned
+        // --------------------
+        match result {
+            Err(BuildRequestError::Auth(_)) => {}
+            _ => panic!("expected an auth build error"),
+        }
+    }
+
+    #[test]
+    fn into_request()
+    {
+        // --------------------
+        // GIVEN
+        // an exchange created from a request
+        // --------------------
+        let req = request(42).clunk(1);
+        let expected = req.clone();
+        let exchange = Exchange::new(req);
+
+        // --------------------
+        // WHEN
+        // Exchange::into_request() is called
+        // --------------------
+        let result = exchange.into_request();
+
+        // --------------------
+        // THEN
+        // the original request is returned
+        // --------------------
+        assert_eq!(result, expected);
+    }
+}
+
+
+mod open_files
+{
+    // Local imports
+
+    use message::v1::{openmode, request, response, FileID, FileKind,
+                      OpenKind};
+    use session::OpenFiles;
+
+    #[test]
+    fn open_read_clunk()
+    {
+        // --------------------
+        // GIVEN
+        // an OpenFiles tracker
+        // --------------------
+        let mut files = OpenFiles::new();
+        let rootdir = FileID::new(FileKind::DIR, 0, 1);
+        let file = FileID::new(FileKind::FILE, 0, 2);
+
+        // --------------------
+        // WHEN
+        // the requests and responses of an attach, walk, open, read, and
+        // clunk of both file ids are observed in order
+        // --------------------
+        let mut live = Vec::new();
+
+        let req = request(1).attach(1, 0, "user", "fs").unwrap();
+        let resp = response(&req).attach(rootdir).unwrap();
+        files.observe_request(&req);
+        assert!(files.observe_response(&resp));
+        live.push(files.file_ids());
+
+        let req = request(2).walk(1, 2, vec!["hello"]).unwrap();
+        let resp = response(&req).walk(&[file]).unwrap();
+        files.observe_request(&req);
+        assert!(files.observe_response(&resp));
+        live.push(files.file_ids());
+
+        let mode = openmode().kind(OpenKind::Read).create();
+        let req = request(3).open(2, mode);
+        let resp = response(&req).open(file, 0).unwrap();
+        files.observe_request(&req);
+        assert!(files.observe_response(&resp));
+        live.push(files.file_ids());
+
+        let req = request(4).read(2, 0, 5);
+        let resp = response(&req).read(5, b"hello").unwrap();
+        files.observe_request(&req);
+        assert!(files.observe_response(&resp));
+        live.push(files.file_ids());
+
+        for &(msgid, file_id) in &[(5, 2), (6, 1)] {
+            let req = request(msgid).clunk(file_id);
+            let resp = response(&req).clunk().unwrap();
+            files.observe_request(&req);
+            assert!(files.observe_response(&resp));
+            live.push(files.file_ids());
+        }
+
+        // --------------------
+        // THEN
+        // the file ids are live between being attached or walked to and
+        // being clunked and
+        // no file ids are live at the end
+        // --------------------
+        let expected = vec![
+            vec![1],
+            vec![1, 2],
+            vec![1, 2],
+            vec![1, 2],
+            vec![1],
+            vec![],
+        ];
+        assert_eq!(live, expected);
+        assert!(files.is_empty());
+        assert_eq!(files.len(), 0);
+    }
+
+    #[test]
+    fn unanswered_clunk()
+    {
+        // --------------------
+        // GIVEN
+        // an OpenFiles tracker with a live file id and
+        // an observed clunk request for the file id
+        // --------------------
+        let mut files = OpenFiles::new();
+        let rootdir = FileID::new(FileKind::DIR, 0, 1);
+        let req = request(1).attach(1, 0, "user", "fs").unwrap();
+        let resp = response(&req).attach(rootdir).unwrap();
+        files.observe_request(&req);
+        files.observe_response(&resp);
+
+        files.observe_request(&request(2).clunk(1));
+
+        // --------------------
+        // WHEN
+        // a response with a different msg id is observed
+        // --------------------
+        let other = request(3).clunk(1);
+        let result = files.observe_response(&response(&other).clunk().unwrap());
+
+        // --------------------
+        // THEN
+        // the response is ignored and
+        // the file id is still live
+        // --------------------
+        assert!(!result);
+        assert!(files.contains(1));
+        assert_eq!(files.file_ids(), vec![1]);
+    }
+}
+
+
+
+mod stat_cache
+{
+    // Third-party imports
+
+    use rmpv::Value;
+
+    // Local imports
+
+    use message::v1::{FileID, FileKind};
+    use session::StatCache;
+
+    fn stat(size: u64) -> Value
+    {
+        Value::Map(vec![(Value::from("size"), Value::from(size))])
+    }
+
+    #[test]
+    fn hit()
+    {
+        // --------------------
+        // GIVEN
+        // a cache holding a stat result for a file id
+        // --------------------
+        let mut cache = StatCache::new();
+        let file_id = FileID::new(FileKind::FILE, 3, 42);
+        cache.put(file_id, stat(10));
+
+        // --------------------
+        // WHEN
+        // StatCache::get() is called with the same file id
+        // --------------------
+        let result = cache.get(&file_id);
+
+        // --------------------
+        // THEN
+        // the cached stat result is returned
+        // --------------------
+        assert_eq!(result, Some(&stat(10)));
+    }
+
+    #[test]
+    fn miss_after_version_bump()
+    {
+        // --------------------
+        // GIVEN
+        // a cache holding a stat result for a file id and
+        // the same file id with a newer version
+        // --------------------
+        let mut cache = StatCache::new();
+        let old = FileID::new(FileKind::FILE, 3, 42);
+        let new = FileID::new(FileKind::FILE, 4, 42);
+        cache.put(old, stat(10));
+
+        // --------------------
+        // WHEN
+        // StatCache::get() is called with the newer file id and
+        // a result for the newer file id is put in the cache
+        // --------------------
+        let before = cache.get(&new).cloned();
+        cache.put(new, stat(20));
+
+        // --------------------
+        // THEN
+        // the newer file id misses before its result is put and
+        // the older file id's result is evicted and
+        // a later result for the older file id is ignored
+        // --------------------
+        assert_eq!(before, None);
+        assert_eq!(cache.get(&old), None);
+        assert_eq!(cache.get(&new), Some(&stat(20)));
+        assert_eq!(cache.len(), 1);
+
+        cache.put(old, stat(30));
+        assert_eq!(cache.get(&old), None);
+        assert_eq!(cache.get(&new), Some(&stat(20)));
+    }
+
+    #[test]
+    fn different_paths_coexist()
+    {
+        // --------------------
+        // GIVEN
+        // an empty cache and
+        // 2 file ids with different paths
+        // --------------------
+        let mut cache = StatCache::new();
+        let first = FileID::new(FileKind::FILE, 3, 42);
+        let second = FileID::new(FileKind::DIR, 0, 43);
+
+        // --------------------
+        // WHEN
+        // a stat result is put for each file id
+        // --------------------
+        cache.put(first, stat(10));
+        cache.put(second, stat(20));
+
+        // --------------------
+        // THEN
+        // both results are cached
+        // --------------------
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&first), Some(&stat(10)));
+        assert_eq!(cache.get(&second), Some(&stat(20)));
+    }
+}
+
+
+mod message_id_gen
+{
+    // Local imports
+
+    use session::{MessageIdGen, MonotonicGuard};
+
+    quickcheck! {
+        fn accepted_by_guard(first: u32, count: u8) -> bool
+        {
+            // --------------------
+            // GIVEN
+            // a msg id generator starting at an arbitrary id and
+            // a monotonic guard
+            // --------------------
+            let mut ids = MessageIdGen::new(first);
+            let mut guard = MonotonicGuard::new();
+
+            // --------------------
+            // WHEN
+            // ids are generated
+            // --------------------
+            let generated: Vec<u32> =
+                (0..count as usize + 1).map(|_| ids.next_id()).collect();
+
+            // --------------------
+            // THEN
+            // the first id is the starting id and
+            // every id is accepted by the guard
+            // --------------------
+            generated[0] == first &&
+                generated.iter().all(|&id| guard.check(id).is_ok())
+        }
+    }
+}
+
+
+mod plan_walk
+{
+    // Local imports
+
+    use core::request::RpcRequest;
+    use message::v1::{request, response, FileID, FileKind, RequestCode};
+    use session::{plan_walk, MessageIdGen, OpenFiles};
+
+    #[test]
+    fn split_chained_walks()
+    {
+        // --------------------
+        // GIVEN
+        // a path of 40 elements and
+        // a msg id generator and
+        // an OpenFiles tracker with no live file ids
+        // --------------------
+        let names: Vec<String> = (0..40).map(|i| format!("dir{}", i)).collect();
+        let path: Vec<&str> = names.iter().map(|n| &n[..]).collect();
+        let mut ids = MessageIdGen::new(100);
+        let files = OpenFiles::new();
+
+        // --------------------
+        // WHEN
+        // plan_walk() is called w/ a max of 16 elements per walk
+        // --------------------
+        let result = plan_walk(1, &path[..], &mut ids, &files, 16);
+
+        // --------------------
+        // THEN
+        // 3 walk requests of 16, 16, and 8 elements are returned and
+        // the 1st walk starts from the given file id and
+        // every other walk starts from the new file id of the walk before
+        //    it and
+        // the walks' path elements make up the whole path in order and
+        // the walks take consecutive msg ids and
+        // the new file ids are distinct from each other and the file id
+        // --------------------
+        assert_eq!(result.len(), 3);
+
+        let mut cur_id = 1;
+        let mut walked: Vec<String> = Vec::new();
+        let mut msgids: Vec<u32> = Vec::new();
+        let mut file_ids = vec![cur_id];
+        for (req, &len) in result.iter().zip(&[16, 16, 8]) {
+            assert_eq!(req.message_method(), RequestCode::Walk);
+            let args = req.message_args();
+            assert_eq!(args[0].as_u64(), Some(cur_id as u64));
+
+            let newfile_id = args[1].as_u64().unwrap() as u32;
+            assert!(!file_ids.contains(&newfile_id));
+            file_ids.push(newfile_id);
+            cur_id = newfile_id;
+
+            let elems = args[2].as_array().unwrap();
+            assert_eq!(elems.len(), len);
+            walked.extend(elems.iter().map(|e| e.as_str().unwrap().to_owned()));
+            msgids.push(req.message_id());
+        }
+        assert_eq!(walked, names);
+        assert_eq!(msgids, vec![100, 101, 102]);
+        assert_eq!(ids.next_id(), 103);
+    }
+
+    #[test]
+    fn skip_file_ids_in_use()
+    {
+        // --------------------
+        // GIVEN
+        // an empty path and
+        // an OpenFiles tracker with live file ids 0 and 1 and
+        // an unanswered open request using file id 3
+        // --------------------
+        let mut files = OpenFiles::new();
+        let rootdir = FileID::new(FileKind::DIR, 0, 1);
+        let req = request(1).attach(0, 5, "user", "fs").unwrap();
+        let resp = response(&req).attach(rootdir).unwrap();
+        files.observe_request(&req);
+        assert!(files.observe_response(&resp));
+
+        let req = request(2).walk(0, 1, vec![]).unwrap();
+        let resp = response(&req).walk(&[]).unwrap();
+        files.observe_request(&req);
+        assert!(files.observe_response(&resp));
+
+        files.observe_request(&request(3).read(3, 0, 10));
+        let mut ids = MessageIdGen::new(7);
+
+        // --------------------
+        // WHEN
+        // plan_walk() is called from file id 2 w/ a max of 1 element per
+        //    walk, once w/ the empty path and once w/ a path of 2 elements
+        // --------------------
+        let empty = plan_walk(2, &[], &mut ids, &files, 1);
+        let result = plan_walk(2, &["a", "b"], &mut ids, &files, 1);
+
+        // --------------------
+        // THEN
+        // a single walk of the empty path to file id 4 is returned and
+        // the 2 element path is walked to file ids 4 and then 5
+        // --------------------
+        assert_eq!(empty.len(), 1);
+        let args = empty[0].message_args();
+        assert_eq!(empty[0].message_id(), 7);
+        assert_eq!(args[0].as_u64(), Some(2));
+        assert_eq!(args[1].as_u64(), Some(4));
+        assert_eq!(args[2].as_array().map(|p| p.len()), Some(0));
+
+        let file_ids: Vec<_> = result.iter()
+            .map(|req| {
+                let args = req.message_args();
+                (args[0].as_u64().unwrap(), args[1].as_u64().unwrap())
+            })
+            .collect();
+        assert_eq!(file_ids, vec![(2, 4), (4, 5)]);
+    }
+}
+
+// ===========================================================================
+//
+// ===========================================================================
@@ -0,0 +1,212 @@
+// src/test/util.rs
+// Copyright (C) 2017 authors and contributors (see AUTHORS file)
+//
+// This file is released under the MIT License.
+
+// ===========================================================================
+// Imports
+// ===========================================================================
+
+
+// Stdlib imports
+
+// Third-party imports
+
+// Local imports
+
+// ===========================================================================
+// Tests
+// ===========================================================================
+
+
+mod full_chain {
+    // Local imports
+
+    use message::v1::{request, BuildRequestError};
+    use util::FullChain;
+
+    #[test]
+    fn auth_bad_username()
+    {
+        // --------------------
+        // GIVEN
+        // an auth build error caused by a bad username
+        // --------------------
+        let err = match request(42).auth(1, "bad user", "fs") {
+            Err(e @ BuildRequestError::Auth(_)) => e,
+            _ => unreachable!(),
+        };
+
+        // --------------------
+        // WHEN
+        // FullChain::full_chain() is called on the error
+        // --------------------
+        let result = err.full_chain();
+
+        // --------------------
+        // THEN
+        // the top-level message and the cause message are joined by ": "
+        // --------------------
+        let expected = "Unable to build auth request message: username is \
+                        either empty, contains whitespace, or contains \
+                        control characters";
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn no_cause()
+    {
+        // --------------------
+        // GIVEN
+        // a flush build error which has no cause
+        // --------------------
+        let err = match request(42).flush(42) {
+            Err(e) => e,
+            _ => unreachable!(),
+        };
+
+        // --------------------
+        // WHEN
+        // FullChain::full_chain() is called on the error
+        // --------------------
+        let result = err.full_chain();
+
+        // --------------------
+        // THEN
+        // only the top-level message is returned
+        // --------------------
+        assert_eq!(result, err.to_string());
+    }
+}
+
+
+
+mod value_to_json {
+    // Third-party imports
+
+    use rmpv::Value;
+
+    // Local imports
+
+    use util::value_to_json;
+
+    #[test]
+    fn base64_padding()
+    {
+        // --------------------
+        // GIVEN
+        // binary values of 1, 2, and 3 bytes
+        // --------------------
+        let values = [
+            Value::Binary(b"M".to_vec()),
+            Value::Binary(b"Ma".to_vec()),
+            Value::Binary(b"Man".to_vec()),
+        ];
+
+        // --------------------
+        // WHEN
+        // value_to_json() is called with each value
+        // --------------------
+        let result: Vec<String> = values.iter().map(value_to_json).collect();
+
+        // --------------------
+        // THEN
+        // each value is a padded base64 string
+        // --------------------
+        assert_eq!(result, vec!["\"TQ==\"", "\"TWE=\"", "\"TWFu\""]);
+    }
+
+    #[test]
+    fn nested_values()
+    {
+        // --------------------
+        // GIVEN
+        // a map holding an ext value, an escaped string, a non-string key,
+        //    and a NaN float
+        // --------------------
+        let val = Value::Map(vec![
+            (Value::from("ext"), Value::Ext(5, vec![0xff])),
+            (Value::from("text"), Value::from("a\"b\\c\n\u{1}")),
+            (Value::from(1), Value::Array(vec![Value::Nil, Value::from(-2)])),
+            (Value::from("nan"), Value::F64(f64::NAN)),
+        ]);
+
+        // --------------------
+        // WHEN
+        // value_to_json() is called with the map
+        // --------------------
+        let result = value_to_json(&val);
+
+        // --------------------
+        // THEN
+        // the ext value is a tagged object and
+        // the string is escaped and
+        // the non-string key is replaced by its JSON text and
+        // the NaN float is null
+        // --------------------
+        let expected = "{\"ext\":{\"ext\":5,\"data\":\"/w==\"},\
+                        \"text\":\"a\\\"b\\\\c\\n\\u0001\",\
+                        \"1\":[null,-2],\
+                        \"nan\":null}";
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn control_and_non_bmp_chars()
+    {
+        // --------------------
+        // GIVEN
+        // a string holding the lowest and highest control characters below
+        //    0x20, the DEL character, and a character outside of the basic
+        //    multilingual plane
+        // --------------------
+        let val = Value::from("\u{0}\u{1f}\u{7f}\u{1f600}");
+
+        // --------------------
+        // WHEN
+        // value_to_json() is called with the string
+        // --------------------
+        let result = value_to_json(&val);
+
+        // --------------------
+        // THEN
+        // the control characters below 0x20 are \u escaped and
+        // the DEL character and the non-BMP character are kept as is
+        // --------------------
+        assert_eq!(result, "\"\\u0000\\u001f\u{7f}\u{1f600}\"");
+    }
+
+    #[test]
+    fn non_finite_floats()
+    {
+        // --------------------
+        // GIVEN
+        // infinite and NaN floats of both widths and
+        // a finite float
+        // --------------------
+        let values = [
+            Value::F64(f64::INFINITY),
+            Value::F64(f64::NEG_INFINITY),
+            Value::F32(f32::NAN),
+            Value::F32(f32::INFINITY),
+            Value::F64(-1.5),
+        ];
+
+        // --------------------
+        // WHEN
+        // value_to_json() is called with each value
+        // --------------------
+        let result: Vec<String> = values.iter().map(value_to_json).collect();
+
+        // --------------------
+        // THEN
+        // every non-finite float is null and
+        // the finite float is a JSON number
+        // --------------------
+        assert_eq!(result, vec!["null", "null", "null", "null", "-1.5"]);
+    }
+}
+
+// ===========================================================================
+//
+// ===========================================================================
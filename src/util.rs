@@ -12,6 +12,9 @@
 
 // Third-party imports
 
+use failure::Fail;
+use rmpv::Value;
+
 // Local imports
 
 
@@ -41,6 +44,170 @@ pub fn is_printable(s: &str, ws_printable: bool) -> bool
 }
 
 
+// ===========================================================================
+// Error helpers
+// ===========================================================================
+
+
+/// Format an error and all of its causes as a single string.
+pub trait FullChain
+{
+    /// Return the error's message followed by the message of each of its
+    /// causes, separated by ": ".
+    fn full_chain(&self) -> String;
+}
+
+
+impl<T> FullChain for T
+where
+    T: Fail,
+{
+    fn full_chain(&self) -> String
+    {
+        let fail: &dyn Fail = self;
+        let chain: Vec<String> =
+            fail.iter_chain().map(|e| e.to_string()).collect();
+        chain.join(": ")
+    }
+}
+
+
+// ===========================================================================
+// JSON
+// ===========================================================================
+
+
+const BASE64_CHARS: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+
+/// Convert a msgpack value into a JSON string.
+///
+/// The JSON text and base64 encoding are written by hand rather than
+/// pulling in serde_json and a base64 crate, since only this one-way,
+/// infallible conversion is needed.
+///
+/// The conversion is meant for logging, so it never fails:
+///
+/// * binary data becomes a standard, padded base64 string
+/// * ext data becomes an object of the form `{"ext": type, "data": base64}`
+/// * map keys that are not strings are replaced by their JSON text
+/// * strings that are not valid utf-8 are converted lossily
+/// * NaN and infinite floats become null
+pub fn value_to_json(val: &Value) -> String
+{
+    let mut ret = String::new();
+    write_json(val, &mut ret);
+    ret
+}
+
+
+// Append the JSON text of a value to out
+fn write_json(val: &Value, out: &mut String)
+{
+    match *val {
+        Value::Nil => out.push_str("null"),
+        Value::Boolean(b) => out.push_str(if b { "true" } else { "false" }),
+        Value::Integer(ref n) => match n.as_u64() {
+            Some(n) => out.push_str(&n.to_string()),
+            None => out.push_str(&n.as_i64().unwrap_or(0).to_string()),
+        },
+        Value::F32(f) => write_json_float(f as f64, out),
+        Value::F64(f) => write_json_float(f, out),
+        Value::String(ref s) => {
+            write_json_str(&String::from_utf8_lossy(s.as_bytes()), out)
+        }
+        Value::Binary(ref data) => write_json_str(&base64(data), out),
+        Value::Array(ref items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_json(item, out);
+            }
+            out.push(']');
+        }
+        Value::Map(ref pairs) => {
+            out.push('{');
+            for (i, pair) in pairs.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                match pair.0.as_str() {
+                    Some(k) => write_json_str(k, out),
+                    None => write_json_str(&value_to_json(&pair.0), out),
+                }
+                out.push(':');
+                write_json(&pair.1, out);
+            }
+            out.push('}');
+        }
+        Value::Ext(ty, ref data) => {
+            out.push_str(&format!("{{\"ext\":{},\"data\":", ty));
+            write_json_str(&base64(data), out);
+            out.push('}');
+        }
+    }
+}
+
+
+// Append a float as a JSON number, or null if it has no JSON representation
+fn write_json_float(f: f64, out: &mut String)
+{
+    if f.is_finite() {
+        out.push_str(&f.to_string());
+    } else {
+        out.push_str("null");
+    }
+}
+
+
+// Append a quoted and escaped JSON string to out
+fn write_json_str(s: &str, out: &mut String)
+{
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                out.push_str(&format!("\\u{:04x}", c as u32))
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+
+// Encode bytes as standard, padded base64
+fn base64(data: &[u8]) -> String
+{
+    let mut ret = String::new();
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).cloned().unwrap_or(0),
+            chunk.get(2).cloned().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (n >> (18 - 6 * i)) & 0x3f;
+                ret.push(BASE64_CHARS[index as usize] as char);
+            } else {
+                ret.push('=');
+            }
+        }
+    }
+    ret
+}
+
+
 // ===========================================================================
 //
 // ===========================================================================